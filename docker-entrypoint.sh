@@ -25,6 +25,13 @@ export WAYPOINT_DATABASE_URL="$DATABASE_URL"
 COMMAND="${1:-migrate}"
 shift 2>/dev/null || true
 
+# run-and-exit reads only WAYPOINT_* env vars; map the Flyway-style ones over
+if [ "$COMMAND" = "run-and-exit" ]; then
+  export WAYPOINT_MIGRATIONS_LOCATIONS="${WAYPOINT_MIGRATIONS_LOCATIONS:-$LOCATIONS}"
+  export WAYPOINT_SSL_MODE="${WAYPOINT_SSL_MODE:-$SSL_MODE}"
+  exec waypoint run-and-exit "$@"
+fi
+
 exec waypoint \
   --url "$DATABASE_URL" \
   --locations "$LOCATIONS" \
@@ -0,0 +1,29 @@
+//! Input read from the terminal, and `--no-input` (or `WAYPOINT_NO_INPUT`)
+//! for environments with nobody to type it.
+//!
+//! Every read of user input goes through this module. With `--no-input`, a
+//! read that would wait on a terminal fails with
+//! [`WaypointError::InputRequired`] instead, so a CI job never hangs.
+
+use std::io::IsTerminal;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use waypoint_core::error::WaypointError;
+
+static NO_INPUT: AtomicBool = AtomicBool::new(false);
+
+/// Turn terminal reads into errors for the rest of this invocation.
+pub fn set_no_input(no_input: bool) {
+    NO_INPUT.store(no_input, Ordering::Relaxed);
+}
+
+/// Read all of stdin as `what`, e.g. "migration SQL".
+pub fn read_stdin(what: &str) -> Result<String, WaypointError> {
+    let stdin = std::io::stdin();
+    if NO_INPUT.load(Ordering::Relaxed) && stdin.is_terminal() {
+        return Err(WaypointError::InputRequired {
+            what: what.to_string(),
+        });
+    }
+    Ok(std::io::read_to_string(stdin)?)
+}
@@ -0,0 +1,140 @@
+//! `[lineage] url`: POST an OpenLineage run event before and after migrate
+//! (`lineage` feature). Like the status file, a failed send is logged and
+//! never changes the exit code.
+
+use std::future::Future;
+
+use tokio::task::JoinHandle;
+use waypoint_core::config::WaypointConfig;
+use waypoint_core::error::WaypointError;
+use waypoint_core::lineage::{LineageRun, RunEvent};
+use waypoint_core::MigrateReport;
+
+/// Run `migrate` between a START event and a COMPLETE or FAIL event.
+///
+/// START is sent in the background, so an unreachable endpoint does not hold
+/// up the migration; the final event waits for it, keeping the two in order.
+/// Failed post-checks count as a failed run.
+pub async fn traced(
+    config: &WaypointConfig,
+    migrate: impl Future<Output = Result<MigrateReport, WaypointError>>,
+) -> Result<MigrateReport, WaypointError> {
+    let run = LineageRun::new(config);
+    let start = send(config, run.start());
+    let result = migrate.await;
+    let event = match &result {
+        Ok(report) => match report.ensure_post_checks_passed() {
+            Ok(()) => run.complete(report),
+            Err(e) => run.fail(&e),
+        },
+        Err(e) => run.fail(e),
+    };
+    if let Some(start) = start {
+        let _ = start.await;
+    }
+    if let Some(end) = send(config, event) {
+        let _ = end.await;
+    }
+    result
+}
+
+/// Start sending `event` to the configured endpoint, if any.
+#[cfg(feature = "lineage")]
+fn send(config: &WaypointConfig, event: RunEvent) -> Option<JoinHandle<()>> {
+    use std::time::Duration;
+
+    let url = config.lineage.url.clone()?;
+    let api_key = config.lineage.api_key.clone();
+    Some(tokio::task::spawn_blocking(move || {
+        let event_type = event.event_type;
+        let agent: ureq::Agent = ureq::Agent::config_builder()
+            .timeout_connect(Some(Duration::from_secs(2)))
+            .timeout_global(Some(Duration::from_secs(10)))
+            .build()
+            .into();
+        let mut request = agent.post(&url).header("User-Agent", "waypoint");
+        if let Some(key) = api_key {
+            request = request.header("Authorization", format!("Bearer {}", key));
+        }
+        match request.send_json(&event) {
+            Ok(_) => log::debug!("Sent lineage event; url={}, event={:?}", url, event_type),
+            Err(e) => log::warn!(
+                "Failed to send lineage event; url={}, event={:?}, error={}",
+                url,
+                event_type,
+                e
+            ),
+        }
+    }))
+}
+
+/// Without the `lineage` feature, say once that a configured endpoint is
+/// ignored.
+#[cfg(not(feature = "lineage"))]
+fn send(config: &WaypointConfig, _event: RunEvent) -> Option<JoinHandle<()>> {
+    use std::sync::Once;
+
+    static WARNED: Once = Once::new();
+    if config.lineage.url.is_some() {
+        WARNED.call_once(|| {
+            log::warn!("lineage.url is set, but waypoint was built without the `lineage` feature; no events are sent")
+        });
+    }
+    None
+}
+
+#[cfg(all(test, feature = "lineage"))]
+mod tests {
+    use super::*;
+    use std::io::{BufRead, BufReader, Read, Write};
+    use std::net::TcpListener;
+
+    /// Accept `count` POSTs and return the `eventType` of each, in order.
+    fn collector(count: usize) -> (String, std::thread::JoinHandle<Vec<String>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/api/v1/lineage", listener.local_addr().unwrap());
+        let handle = std::thread::spawn(move || {
+            let mut events = Vec::new();
+            for stream in listener.incoming().take(count) {
+                let mut stream = stream.unwrap();
+                let mut reader = BufReader::new(stream.try_clone().unwrap());
+                let mut length = 0;
+                loop {
+                    let mut line = String::new();
+                    reader.read_line(&mut line).unwrap();
+                    if line.trim().is_empty() {
+                        break;
+                    }
+                    if let Some((name, value)) = line.split_once(':') {
+                        if name.eq_ignore_ascii_case("content-length") {
+                            length = value.trim().parse().unwrap();
+                        }
+                    }
+                }
+                let mut body = vec![0; length];
+                reader.read_exact(&mut body).unwrap();
+                let event: serde_json::Value = serde_json::from_slice(&body).unwrap();
+                events.push(event["eventType"].as_str().unwrap().to_string());
+                stream
+                    .write_all(b"HTTP/1.1 200 OK\r\nContent-Length: 0\r\nConnection: close\r\n\r\n")
+                    .unwrap();
+            }
+            events
+        });
+        (url, handle)
+    }
+
+    #[tokio::test]
+    async fn test_traced_sends_start_then_fail() {
+        let (url, collected) = collector(2);
+        let mut config = WaypointConfig::default();
+        config.lineage.url = Some(url);
+
+        let result = traced(&config, async {
+            Err(WaypointError::ConfigError("boom".to_string()))
+        })
+        .await;
+        assert!(matches!(result, Err(WaypointError::ConfigError(_))));
+        assert_eq!(collected.join().unwrap(), vec!["START", "FAIL"]);
+    }
+}
@@ -2,40 +2,49 @@
 //! Provides clap-based command routing for 16 subcommands, exit code mapping
 //! based on error type, and multi-database dispatch.
 
-mod output;
+mod input;
+mod lineage;
+mod report_file;
+mod run_and_exit;
 #[cfg(feature = "self-update")]
 mod self_update;
+#[cfg(feature = "serve")]
+mod serve;
+mod status_file;
 
 use std::process;
 
-use clap::{Parser, Subcommand};
+use clap::{CommandFactory, FromArgMatches, Parser, Subcommand};
 use colored::Colorize;
 
-use waypoint_core::config::{normalize_location, CliOverrides, WaypointConfig};
-use waypoint_core::error::WaypointError;
+use waypoint_core::config::{normalize_location, CliOverrides, OutputTheme, WaypointConfig};
+use waypoint_core::error::{ConnectionErrorKind, WaypointError};
+use waypoint_core::messages::{hint, tr, Msg};
 use waypoint_core::migration::MigrationVersion;
+use waypoint_core::render as output;
 use waypoint_core::{UndoTarget, Waypoint};
 
-/// Print a report as JSON (when `--json` is active) or via a terminal formatter.
-/// The 4-argument form accepts a `quiet` flag; when quiet and not JSON, output is suppressed.
+/// Print a report as JSON (when `--json` is active) or via a terminal formatter,
+/// and write it to the `--report-file` if one is configured.
+///
+/// Under `--quiet` nothing is printed, except that the 5-argument form prints
+/// the one line its summary function returns for a report needing attention.
 macro_rules! print_report {
-    ($report:expr, $json:expr, $printer:path) => {
-        if $json {
-            println!(
-                "{}",
-                serde_json::to_string_pretty(&$report).expect("JSON serialization failed")
-            );
-        } else {
-            $printer(&$report);
-        }
-    };
     ($report:expr, $json:expr, $quiet:expr, $printer:path) => {
+        print_report!($report, $json, $quiet, $printer, |_| None::<String>)
+    };
+    ($report:expr, $json:expr, $quiet:expr, $printer:path, $summary:expr) => {
+        report_file::write(&$report);
         if $json {
             println!(
                 "{}",
                 serde_json::to_string_pretty(&$report).expect("JSON serialization failed")
             );
-        } else if !$quiet {
+        } else if $quiet {
+            if let Some(line) = $summary(&$report) {
+                println!("{}", line);
+            }
+        } else {
             $printer(&$report);
         }
     };
@@ -85,10 +94,18 @@ struct Cli {
     #[arg(long, value_name = "SECS")]
     connect_timeout: Option<u32>,
 
+    /// Overall connect deadline in seconds, including retries (default: 0 = no deadline)
+    #[arg(long, value_name = "SECS")]
+    connect_deadline: Option<u32>,
+
     /// Statement timeout in seconds (default: 0 = no limit)
     #[arg(long, value_name = "SECS")]
     statement_timeout: Option<u32>,
 
+    /// Log a heartbeat every N seconds while migration SQL runs (default: 0 = off)
+    #[arg(long, value_name = "SECS")]
+    heartbeat_secs: Option<u32>,
+
     /// Allow out-of-order migrations
     #[arg(long, overrides_with = "no_out_of_order")]
     out_of_order: bool,
@@ -113,7 +130,7 @@ struct Cli {
     #[arg(long, global = true)]
     dry_run: bool,
 
-    /// Suppress non-essential output
+    /// No tables: print nothing when healthy, else one summary line (errors still go to stderr)
     #[arg(short, long, global = true)]
     quiet: bool,
 
@@ -157,6 +174,38 @@ struct Cli {
     #[arg(long, value_name = "SECS", global = true)]
     keepalive: Option<u32>,
 
+    /// Strict mode: warnings and lint errors fail, missing locations and out-of-order are refused
+    #[arg(long, global = true)]
+    strict: bool,
+
+    /// Dev mode: allow out-of-order and clean, skip pre-flight, auto-repair edited checksums
+    #[arg(long, global = true)]
+    dev: bool,
+
+    /// Terminate a stale holder of the migration lock instead of failing
+    #[arg(long, global = true)]
+    steal_lock: bool,
+
+    /// Also write the command's full JSON report to this file (atomically)
+    #[arg(long, value_name = "PATH", global = true)]
+    report_file: Option<String>,
+
+    /// Table theme for migration states: color, minimal, high-contrast
+    #[arg(long, value_name = "THEME", global = true)]
+    theme: Option<OutputTheme>,
+
+    /// Never wait for terminal input; fail instead (for CI)
+    #[arg(long, global = true)]
+    no_input: bool,
+
+    /// Language for hints and summaries: a built-in code (en, de) or a .toml catalog path
+    #[arg(long, value_name = "LANG", global = true)]
+    lang: Option<String>,
+
+    /// Set a placeholder value, overriding config and env (repeatable)
+    #[arg(long = "set", value_name = "KEY=VALUE", value_parser = parse_placeholder, global = true)]
+    set: Vec<(String, String)>,
+
     #[command(subcommand)]
     command: Commands,
 }
@@ -166,13 +215,49 @@ struct Cli {
 enum Commands {
     /// Apply pending migrations
     Migrate {
-        /// Migrate up to this version only
+        /// Migrate up to this version only (overrides migrations.target)
         #[arg(long, value_name = "VERSION")]
         target: Option<String>,
+
+        /// With --dry-run, print a draft U__ script for each pending migration without one
+        #[arg(long, requires = "dry_run")]
+        with_undo: bool,
+    },
+
+    /// Create the next versioned migration file
+    New {
+        /// Description for the file name, e.g. "add users table"
+        description: String,
+
+        /// Start the migration from this SQL file ('-' reads stdin)
+        #[arg(long, value_name = "FILE")]
+        from: Option<String>,
+
+        /// Also write a U__ undo script drafted from the migration's DDL
+        #[arg(long)]
+        with_undo: bool,
+    },
+
+    /// Convert Alembic revisions or a Liquibase changelog into V__ files
+    Import {
+        /// Tool the history comes from: alembic or liquibase
+        #[arg(long, value_name = "TOOL")]
+        from: waypoint_core::commands::import::ImportSource,
+
+        /// Alembic versions/ directory, or Liquibase changelog file
+        path: std::path::PathBuf,
+
+        /// Write files here instead of the first migration location
+        #[arg(long, value_name = "DIR")]
+        output: Option<std::path::PathBuf>,
     },
 
     /// Show migration status
-    Info,
+    Info {
+        /// Show only the most recent successful migration and when it was applied
+        #[arg(long)]
+        last: bool,
+    },
 
     /// Validate applied migrations
     Validate,
@@ -180,6 +265,12 @@ enum Commands {
     /// Repair the schema history table
     Repair,
 
+    /// Inspect or release the migration lock
+    Lock {
+        #[command(subcommand)]
+        action: LockAction,
+    },
+
     /// Baseline an existing database
     Baseline {
         /// Version to baseline at
@@ -214,9 +305,6 @@ enum Commands {
         /// Disable specific rules (comma-separated)
         #[arg(long, value_name = "RULES", value_delimiter = ',')]
         disable: Vec<String>,
-        /// Exit code 1 if any errors found
-        #[arg(long)]
-        strict: bool,
     },
 
     /// Auto-generate changelog from migration DDL
@@ -288,6 +376,66 @@ enum Commands {
     /// Dry-run migrations in a temporary schema
     Simulate,
 
+    /// Replay applied migrations on a scratch database and compare schemas (PostgreSQL)
+    #[cfg(feature = "postgres")]
+    Verify {
+        /// Connection URL of the scratch (shadow) database to replay into
+        #[arg(long, value_name = "URL")]
+        shadow_url: String,
+    },
+
+    /// Serve an HTTP API for status, pending migrations, and triggering migrate
+    #[cfg(feature = "serve")]
+    Serve {
+        /// Address to listen on
+        #[arg(long, value_name = "ADDR", default_value = "127.0.0.1:8080")]
+        listen: String,
+
+        /// Keep an operator-readable status file up to date (or WAYPOINT_STATUS_FILE)
+        #[arg(long, value_name = "PATH")]
+        status_file: Option<String>,
+
+        /// Stream locks, wait events and index build progress of the running
+        /// migration, observed over a second connection (PostgreSQL)
+        #[arg(long)]
+        monitor: bool,
+    },
+
+    /// Migrate using environment-only config, then exit (for init containers)
+    RunAndExit {
+        /// Write a JSON run summary to this file (default: fd 3 if open)
+        #[arg(long, value_name = "PATH")]
+        summary_file: Option<String>,
+
+        /// Write an operator-readable status file (or WAYPOINT_STATUS_FILE)
+        #[arg(long, value_name = "PATH")]
+        status_file: Option<String>,
+    },
+
+    /// Create a database, migrate it, and mark it as a template (PostgreSQL)
+    #[cfg(feature = "postgres")]
+    ProvisionTemplate {
+        /// Name of the template database to create
+        #[arg(long)]
+        name: String,
+
+        /// Drop and re-provision the template if it already exists
+        #[arg(long)]
+        replace: bool,
+    },
+
+    /// Create a database as a copy of a template (PostgreSQL)
+    #[cfg(feature = "postgres")]
+    Clone {
+        /// Name of the template database to copy
+        #[arg(long, value_name = "TEMPLATE")]
+        from_template: String,
+
+        /// Name of the database to create
+        #[arg(long)]
+        name: String,
+    },
+
     /// Update waypoint to the latest version
     #[cfg(feature = "self-update")]
     SelfUpdate {
@@ -297,15 +445,48 @@ enum Commands {
     },
 }
 
+/// `waypoint lock` actions.
+#[derive(Subcommand)]
+enum LockAction {
+    /// Show whether the migration lock is held, and by which session
+    Status,
+
+    /// Release the lock by terminating the session holding it (requires --force)
+    Release,
+}
+
+/// Parse a `--set KEY=VALUE` argument; the value may itself contain `=`.
+fn parse_placeholder(arg: &str) -> Result<(String, String), String> {
+    match arg.split_once('=') {
+        Some((key, value)) if !key.trim().is_empty() => {
+            Ok((key.trim().to_string(), value.to_string()))
+        }
+        _ => Err(format!("expected KEY=VALUE, got '{}'", arg)),
+    }
+}
+
+fn main() {
+    // Before the runtime exists, so its descriptors cannot be mistaken for fd 3.
+    run_and_exit::claim_fd3();
+    async_main();
+}
+
 #[tokio::main]
-async fn main() {
-    let cli = Cli::parse();
+async fn async_main() {
+    let matches = Cli::command().get_matches();
+    let cli = Cli::from_arg_matches(&matches).unwrap_or_else(|e| e.exit());
+    let command_name = matches.subcommand_name().map(str::to_string);
+
+    // Windows consoles only render ANSI colors once VT processing is enabled.
+    #[cfg(windows)]
+    let _ = colored::control::set_virtual_terminal(true);
 
     // Set up logging (suppress when JSON output is requested)
     let filter = if cli.json {
         "error"
     } else if cli.verbose {
-        "debug"
+        // SQL has its own target; opt in with RUST_LOG=waypoint::sql=debug.
+        "debug,waypoint::sql=info"
     } else if cli.quiet {
         "error"
     } else {
@@ -314,11 +495,11 @@ async fn main() {
 
     env_logger::Builder::new()
         .parse_env(env_logger::Env::default().default_filter_or(filter))
-        .format_target(false)
+        .format_target(cli.verbose)
         .format_timestamp(None)
         .init();
 
-    if let Err(e) = run(cli).await {
+    if let Err(e) = run(cli, command_name).await {
         print_error(&e);
         process::exit(exit_code(&e));
     }
@@ -347,6 +528,7 @@ fn exit_code(error: &WaypointError) -> i32 {
         #[cfg(feature = "mysql")]
         WaypointError::MysqlError(_) => 4,
         WaypointError::ConnectionLost { .. } => 4,
+        WaypointError::ConnectionFailed { .. } => 4,
         WaypointError::MigrationFailed { .. } => 5,
         WaypointError::MigrationParseError(_) => 5,
         WaypointError::HookFailed { .. } => 5,
@@ -364,6 +546,9 @@ fn exit_code(error: &WaypointError) -> i32 {
         WaypointError::GuardFailed { .. } => 13,
         WaypointError::MigrationBlocked { .. } => 14,
         WaypointError::SimulationFailed { .. } => 15,
+        WaypointError::PostCheckFailed { .. } => 16,
+        WaypointError::IncompatibleHistory { .. } => 17,
+        WaypointError::InputRequired { .. } => 18,
         WaypointError::DiffFailed { .. } => 1,
         WaypointError::SnapshotError { .. } => 1,
         WaypointError::GitError(_) => 1,
@@ -373,7 +558,8 @@ fn exit_code(error: &WaypointError) -> i32 {
 }
 
 /// Build configuration, resolve multi-database mode, and dispatch the chosen subcommand.
-async fn run(cli: Cli) -> Result<(), WaypointError> {
+/// `command_name` selects the `[command.<name>]` config section to apply.
+async fn run(cli: Cli, command_name: Option<String>) -> Result<(), WaypointError> {
     let json_output = cli.json;
     let dry_run = cli.dry_run;
     let quiet = cli.quiet;
@@ -381,10 +567,47 @@ async fn run(cli: Cli) -> Result<(), WaypointError> {
     let force = cli.force;
     let simulate_flag = cli.simulate;
 
+    // Clear any previous report first, so a failure below never leaves it stale.
+    if let Some(path) = &cli.report_file {
+        report_file::init(path);
+    }
+
+    input::set_no_input(
+        cli.no_input
+            || std::env::var("WAYPOINT_NO_INPUT")
+                .is_ok_and(|v| v == "1" || v.eq_ignore_ascii_case("true")),
+    );
+
+    if let Some(lang) = cli
+        .lang
+        .clone()
+        .or_else(|| std::env::var("WAYPOINT_LANG").ok())
+    {
+        waypoint_core::messages::set_language(&lang)?;
+    }
+
     // Handle self-update before config/DB setup (no database needed)
     #[cfg(feature = "self-update")]
     if let Commands::SelfUpdate { check } = &cli.command {
-        return self_update::self_update(*check, json_output);
+        return self_update::self_update(*check, json_output, quiet);
+    }
+
+    // run-and-exit ignores waypoint.toml and CLI overrides entirely
+    if let Commands::RunAndExit {
+        summary_file,
+        status_file,
+    } = &cli.command
+    {
+        if let Ok(path) = std::env::var("WAYPOINT_REPORT_FILE") {
+            report_file::init(&path);
+        }
+        return run_and_exit::run(
+            summary_file.as_deref(),
+            status_file.as_deref(),
+            json_output,
+            quiet,
+        )
+        .await;
     }
 
     // Build CLI overrides with negation flag support
@@ -421,6 +644,7 @@ async fn run(cli: Cli) -> Result<(), WaypointError> {
         ssl_mode: cli.ssl_mode,
         connect_timeout: cli.connect_timeout,
         statement_timeout: cli.statement_timeout,
+        heartbeat_secs: cli.heartbeat_secs,
         environment: cli.environment,
         dependency_ordering: if cli.dependency_ordering {
             Some(true)
@@ -429,26 +653,93 @@ async fn run(cli: Cli) -> Result<(), WaypointError> {
         },
         keepalive: cli.keepalive,
         batch_transaction: if cli.transaction { Some(true) } else { None },
+        connect_deadline: cli.connect_deadline,
+        report_file: cli.report_file,
+        steal_lock: if cli.steal_lock { Some(true) } else { None },
+        theme: cli.theme,
+        strict: if cli.strict { Some(true) } else { None },
+        dev: if cli.dev { Some(true) } else { None },
+        placeholders: cli.set,
+        command: command_name,
     };
 
     // Load config
     let mut config = WaypointConfig::load(cli.config.as_deref(), &overrides)?;
+    if let Some(path) = &config.migrations.report_file {
+        report_file::init(path);
+    }
+    output::set_theme(config.migrations.theme);
 
     // Override preflight if --skip-preflight
     if skip_preflight {
         config.preflight.enabled = false;
     }
 
+    #[cfg(feature = "serve")]
+    if let Commands::Serve {
+        listen,
+        status_file,
+        monitor,
+    } = &cli.command
+    {
+        let status_file = status_file
+            .clone()
+            .or_else(|| std::env::var("WAYPOINT_STATUS_FILE").ok());
+        return serve::run(config, listen, status_file, force, *monitor).await;
+    }
+
     // === Commands that don't need a DB connection ===
 
     match &cli.command {
-        Commands::Lint { disable, strict } => {
+        Commands::New {
+            description,
+            from,
+            with_undo,
+        } => {
+            let sql = match from.as_deref() {
+                Some("-") => Some(input::read_stdin("migration SQL (--from -)")?),
+                Some(path) => Some(std::fs::read_to_string(path)?),
+                None => None,
+            };
+            let report = waypoint_core::commands::new::execute(
+                &config,
+                description,
+                sql.as_deref(),
+                *with_undo,
+                config.dialect_kind(),
+            )?;
+            print_report!(report, json_output, quiet, output::print_new_report);
+            return Ok(());
+        }
+        Commands::Import {
+            from,
+            path,
+            output: out_dir,
+        } => {
+            let report =
+                waypoint_core::commands::import::execute(&config, *from, path, out_dir.as_deref())?;
+            print_report!(
+                report,
+                json_output,
+                quiet,
+                output::print_import_report,
+                output::quiet_import
+            );
+            return Ok(());
+        }
+        Commands::Lint { disable } => {
             let mut disabled = config.lint.disabled_rules.clone();
             disabled.extend(disable.iter().cloned());
             let report =
                 waypoint_core::commands::lint::execute(&config.migrations.locations, &disabled)?;
-            print_report!(report, json_output, output::print_lint_report);
-            if *strict && report.error_count > 0 {
+            print_report!(
+                report,
+                json_output,
+                quiet,
+                output::print_lint_report,
+                output::quiet_lint
+            );
+            if config.migrations.strict && report.error_count > 0 {
                 return Err(WaypointError::LintFailed {
                     error_count: report.error_count,
                     details: format!("{} warning(s)", report.warning_count),
@@ -462,6 +753,7 @@ async fn run(cli: Cli) -> Result<(), WaypointError> {
                 from.as_deref(),
                 to.as_deref(),
             )?;
+            report_file::write(&report);
             if json_output {
                 println!(
                     "{}",
@@ -498,6 +790,7 @@ async fn run(cli: Cli) -> Result<(), WaypointError> {
                 &config.migrations.locations,
                 base,
             )?;
+            report_file::write(&report);
             if json_output {
                 println!(
                     "{}",
@@ -510,7 +803,7 @@ async fn run(cli: Cli) -> Result<(), WaypointError> {
                         report.conflicts.len()
                     );
                 }
-            } else {
+            } else if !quiet {
                 output::print_conflict_report(&report);
             }
             if report.has_conflicts {
@@ -536,7 +829,7 @@ async fn run(cli: Cli) -> Result<(), WaypointError> {
             waypoint_core::MultiWaypoint::connect(databases, cli.database.as_deref()).await?;
 
         match &cli.command {
-            Commands::Migrate { target } => {
+            Commands::Migrate { target, .. } => {
                 let result = waypoint_core::MultiWaypoint::migrate_with_options(
                     databases,
                     &clients,
@@ -546,7 +839,7 @@ async fn run(cli: Cli) -> Result<(), WaypointError> {
                     force,
                 )
                 .await?;
-                print_report!(result, json_output, output::print_multi_result);
+                print_report!(result, json_output, quiet, output::print_multi_result);
                 if !result.all_succeeded {
                     return Err(WaypointError::MultiDbError {
                         name: "multi".to_string(),
@@ -554,10 +847,10 @@ async fn run(cli: Cli) -> Result<(), WaypointError> {
                     });
                 }
             }
-            Commands::Info => {
+            Commands::Info { last: false } => {
                 let all_info =
                     waypoint_core::MultiWaypoint::info(databases, &clients, &order).await?;
-                print_report!(all_info, json_output, output::print_multi_info);
+                print_report!(all_info, json_output, quiet, output::print_multi_info);
             }
             _ => {
                 // For other commands, run on filtered single DB
@@ -590,11 +883,15 @@ async fn run(cli: Cli) -> Result<(), WaypointError> {
 
     // Dry-run mode: show what would be applied using info/explain
     if dry_run {
-        if let Commands::Migrate { .. } = &cli.command {
+        if let Commands::Migrate { with_undo, .. } = &cli.command {
             let wp = Waypoint::new(config).await?;
             let report =
                 waypoint_core::commands::explain::execute_db(wp.client(), &wp.config).await?;
-            print_report!(report, json_output, output::print_explain_report);
+            print_report!(report, json_output, quiet, output::print_explain_report);
+            // A dry run writes nothing; the JSON report already carries the drafts.
+            if *with_undo && !json_output {
+                output::print_undo_drafts(&report);
+            }
             return Ok(());
         }
     }
@@ -652,7 +949,12 @@ async fn run_single_db_command(
             if simulate_before || wp.config.simulation.simulate_before_migrate {
                 let sim_report = wp.simulate().await?;
                 if !sim_report.passed {
-                    print_report!(sim_report, json_output, output::print_simulation_report);
+                    print_report!(
+                        sim_report,
+                        json_output,
+                        quiet,
+                        output::print_simulation_report
+                    );
                     return Err(WaypointError::SimulationFailed {
                         reason: sim_report
                             .errors
@@ -667,16 +969,52 @@ async fn run_single_db_command(
                 }
             }
 
-            let report = wp.migrate_with_options(target.as_deref(), force).await?;
-            print_report!(report, json_output, quiet, output::print_migrate_summary);
+            let report = lineage::traced(
+                &wp.config,
+                wp.migrate_with_options(target.as_deref(), force),
+            )
+            .await?;
+            print_report!(
+                report,
+                json_output,
+                quiet,
+                output::print_migrate_summary,
+                output::quiet_migrate
+            );
+            report.ensure_post_checks_passed()?;
+        }
+        Commands::Info { last: true } => {
+            let report = waypoint_core::LastAppliedReport {
+                report_version: waypoint_core::REPORT_VERSION,
+                last_applied: wp.last_applied().await?,
+            };
+            print_report!(
+                report,
+                json_output,
+                quiet,
+                output::print_last_applied,
+                output::quiet_last_applied
+            );
         }
-        Commands::Info => {
+        Commands::Info { last: false } => {
             let infos = wp.info().await?;
-            print_report!(infos, json_output, quiet, output::print_info_table);
+            print_report!(
+                infos,
+                json_output,
+                quiet,
+                output::print_info_table,
+                output::quiet_info
+            );
         }
         Commands::Validate => {
             let report = wp.validate().await?;
-            print_report!(report, json_output, quiet, output::print_validate_result);
+            print_report!(
+                report,
+                json_output,
+                quiet,
+                output::print_validate_result,
+                output::quiet_validate
+            );
         }
         Commands::Repair => {
             let report = wp.repair().await?;
@@ -688,11 +1026,11 @@ async fn run_single_db_command(
         } => {
             wp.baseline(baseline_version.as_deref(), baseline_description.as_deref())
                 .await?;
+            let result =
+                serde_json::json!({"success": true, "message": "Successfully baselined schema."});
+            report_file::write(&result);
             if json_output {
-                println!(
-                    "{}",
-                    serde_json::json!({"success": true, "message": "Successfully baselined schema."})
-                );
+                println!("{}", result);
             } else if !quiet {
                 println!("{}", "Successfully baselined schema.".green().bold());
             }
@@ -706,11 +1044,11 @@ async fn run_single_db_command(
                 UndoTarget::Last
             };
             let report = wp.undo(undo_target).await?;
-            print_report!(report, json_output, output::print_undo_summary);
+            print_report!(report, json_output, quiet, output::print_undo_summary);
         }
         Commands::Clean { allow_clean } => {
             let dropped = wp.clean(*allow_clean).await?;
-            print_report!(dropped, json_output, output::print_clean_result);
+            print_report!(dropped, json_output, quiet, output::print_clean_result);
         }
         Commands::Diff {
             target_url,
@@ -726,7 +1064,13 @@ async fn run_single_db_command(
                 }
             };
             let report = wp.diff(target).await?;
-            print_report!(report, json_output, output::print_diff_report);
+            print_report!(
+                report,
+                json_output,
+                quiet,
+                output::print_diff_report,
+                output::quiet_diff
+            );
             if report.has_changes {
                 let output_path = if *auto_version {
                     // Determine next version from existing migrations
@@ -746,13 +1090,28 @@ async fn run_single_db_command(
                 };
                 if let Some(path) = output_path {
                     std::fs::write(&path, &report.generated_sql).map_err(WaypointError::IoError)?;
-                    println!("{}", format!("Generated SQL written to {}", path).green());
+                    if !quiet {
+                        println!("{}", format!("Generated SQL written to {}", path).green());
+                    }
                 }
             }
         }
+        Commands::Lock { action } => {
+            let report = match action {
+                LockAction::Status => wp.lock_status().await?,
+                LockAction::Release => wp.lock_release(force).await?,
+            };
+            print_report!(
+                report,
+                json_output,
+                quiet,
+                output::print_lock_report,
+                output::quiet_lock
+            );
+        }
         Commands::Drift => {
             let report = wp.drift().await?;
-            print_report!(report, json_output, output::print_drift_report);
+            print_report!(report, json_output, quiet, output::print_drift_report);
             if report.has_drift {
                 return Err(WaypointError::DriftDetected {
                     count: report.drifts.len(),
@@ -767,36 +1126,53 @@ async fn run_single_db_command(
         }
         Commands::Snapshot => {
             let report = wp.snapshot(&wp.config.snapshots).await?;
-            print_report!(report, json_output, output::print_snapshot_report);
+            print_report!(report, json_output, quiet, output::print_snapshot_report);
         }
         Commands::Restore { snapshot_id } => match snapshot_id {
             Some(id) => {
                 let report = wp.restore(&wp.config.snapshots, id).await?;
-                print_report!(report, json_output, output::print_restore_report);
+                print_report!(report, json_output, quiet, output::print_restore_report);
             }
             None => {
                 let snapshots =
                     waypoint_core::commands::snapshot::list_snapshots(&wp.config.snapshots)?;
-                print_report!(snapshots, json_output, output::print_snapshot_list);
+                print_report!(snapshots, json_output, quiet, output::print_snapshot_list);
             }
         },
         Commands::Preflight => {
             let report = wp.preflight().await?;
-            print_report!(report, json_output, output::print_preflight_report);
+            print_report!(
+                report,
+                json_output,
+                quiet,
+                output::print_preflight_report,
+                output::quiet_preflight
+            );
         }
         Commands::Safety { file } => {
             if let Some(path) = file {
                 let report =
                     waypoint_core::commands::safety::execute_file_db(wp.client(), &wp.config, path)
                         .await?;
-                print_report!(report, json_output, output::print_safety_report);
+                print_report!(
+                    report,
+                    json_output,
+                    quiet,
+                    output::print_safety_report,
+                    output::quiet_safety
+                );
             } else {
                 let report = wp.safety().await?;
+                report_file::write(&report);
                 if json_output {
                     println!(
                         "{}",
                         serde_json::to_string_pretty(&report).expect("JSON serialization failed")
                     );
+                } else if quiet {
+                    if let Some(line) = output::quiet_safety_overall(report.overall_verdict) {
+                        println!("{}", line);
+                    }
                 } else {
                     for r in &report.reports {
                         output::print_safety_report(r);
@@ -807,15 +1183,23 @@ async fn run_single_db_command(
         }
         Commands::Advise { fix_file } => {
             let report = wp.advise().await?;
-            print_report!(report, json_output, output::print_advisor_report);
+            print_report!(
+                report,
+                json_output,
+                quiet,
+                output::print_advisor_report,
+                output::quiet_advisor
+            );
             if let Some(path) = fix_file {
                 waypoint_core::commands::advisor::write_fix_file(&report, path)?;
-                println!("{}", format!("Fix SQL written to {}", path).green());
+                if !quiet {
+                    println!("{}", format!("Fix SQL written to {}", path).green());
+                }
             }
         }
         Commands::Simulate => {
             let report = wp.simulate().await?;
-            print_report!(report, json_output, output::print_simulation_report);
+            print_report!(report, json_output, quiet, output::print_simulation_report);
             if !report.passed {
                 return Err(WaypointError::SimulationFailed {
                     reason: report
@@ -827,14 +1211,68 @@ async fn run_single_db_command(
                 });
             }
         }
+        #[cfg(feature = "postgres")]
+        Commands::Verify { shadow_url } => {
+            let report = wp.verify(shadow_url).await?;
+            print_report!(report, json_output, quiet, output::print_verify_report);
+            if !report.differences.is_empty() {
+                return Err(WaypointError::DriftDetected {
+                    count: report.differences.len(),
+                    details: report
+                        .differences
+                        .iter()
+                        .map(|d| d.to_string())
+                        .collect::<Vec<_>>()
+                        .join(", "),
+                });
+            }
+            if !report.repeatable_issues.is_empty() {
+                return Err(WaypointError::ValidationFailed(format!(
+                    "repeatable migration(s) not re-runnable: {}",
+                    report
+                        .repeatable_issues
+                        .iter()
+                        .map(|i| i.script.as_str())
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                )));
+            }
+        }
+        #[cfg(feature = "postgres")]
+        Commands::ProvisionTemplate { name, replace } => {
+            let report = wp.provision_template(name, *replace).await?;
+            print_report!(
+                report,
+                json_output,
+                quiet,
+                output::print_provision_template_report
+            );
+        }
+        #[cfg(feature = "postgres")]
+        Commands::Clone {
+            from_template,
+            name,
+        } => {
+            let report = wp.clone_from_template(from_template, name).await?;
+            print_report!(report, json_output, quiet, output::print_clone_report);
+        }
         // No-DB commands handled earlier
-        Commands::Lint { .. } | Commands::Changelog { .. } | Commands::CheckConflicts { .. } => {
+        Commands::New { .. }
+        | Commands::Import { .. }
+        | Commands::Lint { .. }
+        | Commands::Changelog { .. }
+        | Commands::CheckConflicts { .. }
+        | Commands::RunAndExit { .. } => {
             unreachable!("handled before DB setup")
         }
         #[cfg(feature = "self-update")]
         Commands::SelfUpdate { .. } => {
             unreachable!("handled before DB setup")
         }
+        #[cfg(feature = "serve")]
+        Commands::Serve { .. } => {
+            unreachable!("handled before DB setup")
+        }
     }
 
     Ok(())
@@ -845,211 +1283,96 @@ async fn run_single_db_command(
 // reserved variants until 0.4.0 drops the variants entirely.
 #[allow(deprecated)]
 fn print_error(error: &WaypointError) {
-    eprintln!("{} {}", "ERROR:".red().bold(), error);
+    eprintln!("{} {}", tr(Msg::LabelError, &[]).red().bold(), error);
 
     // Provide actionable guidance
-    match error {
-        WaypointError::ConfigError(_) => {
-            eprintln!(
-                "{}",
-                "Hint: Check your waypoint.toml or set WAYPOINT_DATABASE_URL environment variable."
-                    .dimmed()
-            );
-        }
-        WaypointError::DatabaseError(_) => {
-            eprintln!(
-                "{}",
-                "Hint: Verify database is running and connection details are correct.".dimmed()
-            );
-        }
-        WaypointError::CleanDisabled => {
-            eprintln!(
-                "{}",
-                "Hint: Pass --allow-clean flag or set clean_enabled = true in waypoint.toml."
-                    .dimmed()
-            );
-        }
-        WaypointError::ChecksumMismatch { .. } => {
-            eprintln!(
-                "{}",
-                "Hint: Run 'waypoint repair' to update checksums, or restore the original migration file."
-                    .dimmed()
-            );
-        }
-        WaypointError::OutOfOrder { .. } => {
-            eprintln!(
-                "{}",
-                "Hint: Use --out-of-order flag to allow out-of-order migrations.".dimmed()
-            );
-        }
+    let guidance = match error {
+        WaypointError::ConfigError(_) => Some(hint(Msg::HintConfig, &[])),
+        WaypointError::DatabaseError(_) => Some(hint(Msg::HintDatabase, &[])),
+        WaypointError::ConnectionFailed { kind, .. } => Some(hint(
+            match kind {
+                ConnectionErrorKind::Dns => Msg::HintConnectionDns,
+                ConnectionErrorKind::Tls => Msg::HintConnectionTls,
+                ConnectionErrorKind::Auth => Msg::HintConnectionAuth,
+                ConnectionErrorKind::Timeout => Msg::HintConnectionTimeout,
+                ConnectionErrorKind::Refused => Msg::HintConnectionRefused,
+                ConnectionErrorKind::Other => Msg::HintDatabase,
+            },
+            &[],
+        )),
+        WaypointError::CleanDisabled => Some(hint(Msg::HintCleanDisabled, &[])),
+        WaypointError::ChecksumMismatch { .. } => Some(hint(Msg::HintChecksumMismatch, &[])),
+        WaypointError::OutOfOrder { .. } => Some(hint(Msg::HintOutOfOrder, &[])),
         WaypointError::UndoMissing { version } => {
-            eprintln!(
-                "{}",
-                format!(
-                    "Hint: Create a U{version}__<description>.sql file, or enable [reversals] for auto-generated undo."
-                )
-                .dimmed()
-            );
-        }
-        WaypointError::MigrationBlocked { .. } => {
-            eprintln!(
-                "{}",
-                "Hint: Use --force to override DANGER blocks, or add '-- waypoint:safety-override' to the migration."
-                    .dimmed()
-            );
-        }
-        WaypointError::GuardFailed { .. } => {
-            eprintln!(
-                "{}",
-                "Hint: Check guard conditions in your migration directives (-- waypoint:require / -- waypoint:ensure)."
-                    .dimmed()
-            );
-        }
-        WaypointError::DriftDetected { .. } => {
-            eprintln!(
-                "{}",
-                "Hint: Run 'waypoint diff' to generate a migration that resolves this drift."
-                    .dimmed()
-            );
-        }
-        WaypointError::LintFailed { .. } => {
-            eprintln!(
-                "{}",
-                "Hint: Fix the issues or add rule IDs to [lint] disabled_rules in waypoint.toml."
-                    .dimmed()
-            );
+            Some(hint(Msg::HintUndoMissing, &[("version", version)]))
         }
+        WaypointError::MigrationBlocked { .. } => Some(hint(Msg::HintMigrationBlocked, &[])),
+        WaypointError::GuardFailed { .. } => Some(hint(Msg::HintGuardFailed, &[])),
+        WaypointError::DriftDetected { .. } => Some(hint(Msg::HintDriftDetected, &[])),
+        WaypointError::LintFailed { .. } => Some(hint(Msg::HintLintFailed, &[])),
         WaypointError::NonTransactionalStatement { .. } => {
-            eprintln!(
-                "{}",
-                "Hint: Remove --transaction to apply migrations individually, or rewrite the migration to avoid CONCURRENTLY/VACUUM/etc."
-                    .dimmed()
-            );
-        }
-        WaypointError::ConnectionLost { .. } => {
-            eprintln!(
-                "{}",
-                "Hint: Run 'waypoint info' to check the current migration state.".dimmed()
-            );
+            Some(hint(Msg::HintNonTransactional, &[]))
         }
+        WaypointError::PostCheckFailed {
+            rolled_back: true, ..
+        } => Some(hint(Msg::HintPostCheckRolledBack, &[])),
+        WaypointError::PostCheckFailed { .. } => Some(hint(Msg::HintPostCheckFailed, &[])),
+        WaypointError::ConnectionLost { .. } => Some(hint(Msg::HintConnectionLost, &[])),
         WaypointError::PlaceholderNotFound { key, .. } => {
-            eprintln!(
-                "{}",
-                format!("Hint: Define placeholder '{}' in [placeholders] section of waypoint.toml or as an environment variable.", key).dimmed()
-            );
+            Some(hint(Msg::HintPlaceholderNotFound, &[("key", key)]))
         }
         WaypointError::MigrationFailed { script, .. } => {
-            eprintln!(
-                "{}",
-                format!(
-                    "Hint: Fix the SQL error in '{}', then run 'waypoint repair' if needed.",
-                    script
-                )
-                .dimmed()
-            );
+            Some(hint(Msg::HintMigrationFailed, &[("script", script)]))
         }
         WaypointError::HookFailed { script, .. } => {
-            eprintln!(
-                "{}",
-                format!("Hint: Check the hook file '{}' for SQL errors.", script).dimmed()
-            );
+            Some(hint(Msg::HintHookFailed, &[("script", script)]))
         }
         WaypointError::UndoFailed { script, .. } => {
-            eprintln!(
-                "{}",
-                format!("Hint: Fix the SQL error in undo script '{}'.", script).dimmed()
-            );
-        }
-        WaypointError::ValidationFailed(_) => {
-            eprintln!(
-                "{}",
-                "Hint: Run 'waypoint validate' for details, then 'waypoint repair' to fix."
-                    .dimmed()
-            );
-        }
-        WaypointError::DependencyCycle { .. } => {
-            eprintln!(
-                "{}",
-                "Hint: Check '-- waypoint:depends' directives for circular references.".dimmed()
-            );
-        }
-        WaypointError::MissingDependency { .. } => {
-            eprintln!(
-                "{}",
-                "Hint: Ensure the referenced migration version exists in your migration locations."
-                    .dimmed()
-            );
-        }
-        WaypointError::InvalidDirective { .. } => {
-            eprintln!(
-                "{}",
-                "Hint: Check the '-- waypoint:' directive syntax in the migration file header."
-                    .dimmed()
-            );
-        }
-        WaypointError::PreflightFailed { .. } => {
-            eprintln!(
-                "{}",
-                "Hint: Use --skip-preflight to bypass, or resolve the database health issues."
-                    .dimmed()
-            );
-        }
-        WaypointError::ConflictsDetected { .. } => {
-            eprintln!(
-                "{}",
-                "Hint: Resolve migration version conflicts between branches before merging."
-                    .dimmed()
-            );
-        }
-        WaypointError::LockError(_) => {
-            eprintln!(
-                "{}",
-                "Hint: Another migration may be running. Wait and retry, or check pg_locks."
-                    .dimmed()
-            );
-        }
-        WaypointError::SimulationFailed { .. } => {
-            eprintln!(
-                "{}",
-                "Hint: Fix the SQL errors shown above before running the actual migration."
-                    .dimmed()
-            );
-        }
-        WaypointError::BaselineExists => {
-            eprintln!(
-                "{}",
-                "Hint: A baseline already exists. Use 'waypoint info' to see the current state."
-                    .dimmed()
-            );
-        }
-        WaypointError::DatabaseNotFound { .. } => {
-            eprintln!(
-                "{}",
-                "Hint: Check the database name in --database flag or [[databases]] config."
-                    .dimmed()
-            );
-        }
-        WaypointError::MigrationParseError(_) => {
-            eprintln!(
-                "{}",
-                "Hint: Check migration filenames follow the pattern V{version}__{description}.sql."
-                    .dimmed()
-            );
+            Some(hint(Msg::HintUndoFailed, &[("script", script)]))
         }
+        WaypointError::ValidationFailed(_) => Some(hint(Msg::HintValidationFailed, &[])),
+        WaypointError::DependencyCycle { .. } => Some(hint(Msg::HintDependencyCycle, &[])),
+        WaypointError::MissingDependency { .. } => Some(hint(Msg::HintMissingDependency, &[])),
+        WaypointError::InvalidDirective { .. } => Some(hint(Msg::HintInvalidDirective, &[])),
+        WaypointError::PreflightFailed { .. } => Some(hint(Msg::HintPreflightFailed, &[])),
+        WaypointError::ConflictsDetected { .. } => Some(hint(Msg::HintConflictsDetected, &[])),
+        WaypointError::LockError(_) => Some(hint(Msg::HintLockError, &[])),
+        WaypointError::SimulationFailed { .. } => Some(hint(Msg::HintSimulationFailed, &[])),
+        WaypointError::BaselineExists => Some(hint(Msg::HintBaselineExists, &[])),
+        WaypointError::DatabaseNotFound { .. } => Some(hint(Msg::HintDatabaseNotFound, &[])),
+        WaypointError::MigrationParseError(_) => Some(hint(Msg::HintMigrationParse, &[])),
         WaypointError::MultiDbDependencyCycle { .. } | WaypointError::MultiDbError { .. } => {
-            eprintln!(
-                "{}",
-                "Hint: Check [[databases]] dependency configuration in waypoint.toml.".dimmed()
-            );
+            Some(hint(Msg::HintMultiDb, &[]))
         }
+        WaypointError::IncompatibleHistory { .. } => Some(hint(Msg::HintIncompatibleHistory, &[])),
+        WaypointError::InputRequired { .. } => Some(hint(Msg::HintInputRequired, &[])),
         // Remaining errors with no specific guidance
         WaypointError::UpdateError(_)
         | WaypointError::DiffFailed { .. }
         | WaypointError::SnapshotError { .. }
         | WaypointError::GitError(_)
         | WaypointError::AdvisorError(_)
-        | WaypointError::IoError(_) => {}
+        | WaypointError::IoError(_) => None,
         #[cfg(feature = "mysql")]
-        WaypointError::MysqlError(_) => {}
+        WaypointError::MysqlError(_) => None,
+    };
+    if let Some(guidance) = guidance {
+        eprintln!("{}", guidance.dimmed());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_every_subcommand_may_have_a_config_section() {
+        for sub in Cli::command().get_subcommands() {
+            assert!(
+                waypoint_core::config::COMMAND_NAMES.contains(&sub.get_name()),
+                "{} is missing from config::COMMAND_NAMES",
+                sub.get_name()
+            );
+        }
     }
 }
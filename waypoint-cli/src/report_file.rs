@@ -0,0 +1,44 @@
+//! `--report-file` (or `migrations.report_file` / `WAYPOINT_REPORT_FILE`):
+//! the full JSON report of the command that ran, written atomically so a
+//! later orchestration step can read it without capturing stdout.
+//!
+//! Any report left by a previous invocation is removed up front, so a command
+//! that fails before producing a report never leaves a stale one behind.
+
+use std::sync::OnceLock;
+
+use serde::Serialize;
+
+static PATH: OnceLock<String> = OnceLock::new();
+
+/// Set the report path for this invocation and remove any previous report.
+pub fn init(path: &str) {
+    if PATH.set(path.to_string()).is_err() {
+        return;
+    }
+    match std::fs::remove_file(path) {
+        Ok(()) => log::debug!("Removed previous report file; path={}", path),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+        Err(e) => log::warn!(
+            "Failed to remove previous report file; path={}, error={}",
+            path,
+            e
+        ),
+    }
+}
+
+/// Write `report` to the configured report file, if any.
+///
+/// Failures are logged but never change the exit code, like the status file.
+pub fn write<T: Serialize + ?Sized>(report: &T) {
+    let Some(path) = PATH.get() else {
+        return;
+    };
+    let json = serde_json::to_string_pretty(report).expect("JSON serialization failed");
+    let tmp = format!("{}.tmp", path);
+    if let Err(e) =
+        std::fs::write(&tmp, format!("{}\n", json)).and_then(|()| std::fs::rename(&tmp, path))
+    {
+        log::error!("Failed to write report file; path={}, error={}", path, e);
+    }
+}
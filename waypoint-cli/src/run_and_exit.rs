@@ -0,0 +1,183 @@
+//! One-shot migrate for init containers and Docker entrypoints.
+//! Configuration comes only from `WAYPOINT_*` environment variables, the
+//! database is retried until it accepts connections, and a JSON summary is
+//! written to `--summary-file` (or `WAYPOINT_SUMMARY_FILE`), else to fd 3 when
+//! the caller has opened it. `--status-file` (or `WAYPOINT_STATUS_FILE`)
+//! additionally records the resulting state for an external controller, and
+//! `--report-file` (or `WAYPOINT_REPORT_FILE`) the full migrate report.
+
+use std::env;
+#[cfg(unix)]
+use std::fs::File;
+#[cfg(unix)]
+use std::sync::OnceLock;
+
+use serde::Serialize;
+use waypoint_core::config::WaypointConfig;
+use waypoint_core::error::WaypointError;
+use waypoint_core::{MigrateReport, MigrationInfo, Waypoint};
+
+use crate::lineage;
+use crate::output;
+use crate::report_file;
+use crate::status_file::{self, LastError};
+
+/// Connect retries used when `WAYPOINT_CONNECT_RETRIES` is not set.
+const DEFAULT_CONNECT_RETRIES: u32 = 20;
+
+/// Overall connect deadline used when `WAYPOINT_CONNECT_DEADLINE` is not set.
+const DEFAULT_CONNECT_DEADLINE_SECS: u32 = 300;
+
+/// fd 3 as inherited from the caller, claimed by `claim_fd3`.
+#[cfg(unix)]
+static FD3: OnceLock<File> = OnceLock::new();
+
+/// Take ownership of fd 3 if the caller passed it in.
+///
+/// Must run before the tokio runtime starts: the runtime's own descriptors
+/// would otherwise take the number 3, and the summary would be written into
+/// one of them.
+pub fn claim_fd3() {
+    #[cfg(unix)]
+    {
+        use std::os::fd::FromRawFd;
+        // SAFETY: F_GETFD only inspects the descriptor table; fd 3 is owned
+        // by nothing else in the process this early, so wrapping it is sound.
+        if unsafe { libc::fcntl(3, libc::F_GETFD) } != -1 {
+            let _ = FD3.set(unsafe { File::from_raw_fd(3) });
+        }
+    }
+}
+
+/// Machine-readable outcome written for the orchestrator.
+#[derive(Serialize)]
+struct RunSummary {
+    success: bool,
+    exit_code: i32,
+    migrations_applied: usize,
+    total_time_ms: i32,
+    applied: Vec<String>,
+    validation_warnings: Vec<String>,
+    error: Option<String>,
+}
+
+/// Run `run-and-exit`: migrate using env-only config and write the summary.
+pub async fn run(
+    summary_file: Option<&str>,
+    status_file: Option<&str>,
+    json_output: bool,
+    quiet: bool,
+) -> Result<(), WaypointError> {
+    let env_status = env::var("WAYPOINT_STATUS_FILE").ok();
+    let status_file = status_file.or(env_status.as_deref());
+
+    let (result, infos) = migrate(status_file.is_some()).await;
+    // Failed post-checks leave the migrations applied but the deploy unhealthy.
+    let post_check_error = result
+        .as_ref()
+        .ok()
+        .and_then(|r| r.ensure_post_checks_passed().err());
+    let failure = result.as_ref().err().or(post_check_error.as_ref());
+
+    if let Some(path) = status_file {
+        let last_error = failure.map(LastError::from);
+        status_file::write(path, infos.as_deref(), false, last_error.as_ref());
+    }
+
+    let summary = match &result {
+        Ok(report) => RunSummary {
+            success: failure.is_none(),
+            exit_code: failure.map_or(0, crate::exit_code),
+            migrations_applied: report.migrations_applied,
+            total_time_ms: report.total_time_ms,
+            applied: report.details.iter().map(|d| d.script.clone()).collect(),
+            validation_warnings: report.validation_warnings.clone(),
+            error: failure.map(|e| e.to_string()),
+        },
+        Err(e) => RunSummary {
+            success: false,
+            exit_code: crate::exit_code(e),
+            migrations_applied: 0,
+            total_time_ms: 0,
+            applied: Vec::new(),
+            validation_warnings: Vec::new(),
+            error: Some(e.to_string()),
+        },
+    };
+    let env_file = env::var("WAYPOINT_SUMMARY_FILE").ok();
+    write_summary(summary_file.or(env_file.as_deref()), &summary);
+
+    let report = result?;
+    report_file::write(&report);
+    if json_output {
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&report).expect("JSON serialization failed")
+        );
+    } else if !quiet {
+        output::print_migrate_summary(&report);
+    }
+    match post_check_error {
+        Some(e) => Err(e),
+        None => Ok(()),
+    }
+}
+
+/// Migrate, optionally re-reading migration state afterwards for the status
+/// file. State is `None` when the database could not be reached.
+async fn migrate(
+    with_info: bool,
+) -> (
+    Result<MigrateReport, WaypointError>,
+    Option<Vec<MigrationInfo>>,
+) {
+    let wp = match connect().await {
+        Ok(wp) => wp,
+        Err(e) => return (Err(e), None),
+    };
+    let result = lineage::traced(&wp.config, wp.migrate(None)).await;
+    let infos = if with_info {
+        wp.info().await.ok()
+    } else {
+        None
+    };
+    (result, infos)
+}
+
+async fn connect() -> Result<Waypoint, WaypointError> {
+    let mut config = WaypointConfig::from_env()?;
+    if env::var_os("WAYPOINT_CONNECT_RETRIES").is_none() {
+        config.database.connect_retries = DEFAULT_CONNECT_RETRIES;
+    }
+    if env::var_os("WAYPOINT_CONNECT_DEADLINE").is_none() {
+        config.database.connect_deadline_secs = DEFAULT_CONNECT_DEADLINE_SECS;
+    }
+    Waypoint::new(config).await
+}
+
+/// Write the summary to `path`, or to fd 3 if no path is given and the caller
+/// opened it (see `claim_fd3`).
+///
+/// Failures are logged but never change the exit code: the migration outcome
+/// is what the orchestrator acts on.
+fn write_summary(path: Option<&str>, summary: &RunSummary) {
+    let json = serde_json::to_string(summary).expect("JSON serialization failed");
+
+    if let Some(path) = path {
+        if let Err(e) = std::fs::write(path, format!("{}\n", json)) {
+            log::error!("Failed to write run summary; path={}, error={}", path, e);
+        }
+        return;
+    }
+
+    #[cfg(unix)]
+    match FD3.get() {
+        Some(mut fd3) => {
+            use std::io::Write;
+            if let Err(e) = writeln!(fd3, "{}", json) {
+                log::error!("Failed to write run summary to fd 3; error={}", e);
+            }
+        }
+        None => log::debug!("fd 3 is not open; skipping run summary"),
+    }
+}
@@ -74,7 +74,7 @@ fn platform_target() -> Result<(&'static str, &'static str), WaypointError> {
 }
 
 /// Download a release tarball and atomically replace the current binary.
-fn download_and_replace(version: &str) -> Result<(), WaypointError> {
+fn download_and_replace(version: &str, quiet: bool) -> Result<(), WaypointError> {
     let (os, arch) = platform_target()?;
     let tag = if version.starts_with('v') {
         version.to_string()
@@ -84,7 +84,9 @@ fn download_and_replace(version: &str) -> Result<(), WaypointError> {
     let tarball_name = format!("waypoint-{tag}-{os}-{arch}.tar.gz");
     let url = format!("https://github.com/{REPO}/releases/download/{tag}/{tarball_name}");
 
-    eprintln!("Downloading {}...", url);
+    if !quiet {
+        eprintln!("Downloading {}...", url);
+    }
 
     let mut resp = ureq::get(&url)
         .header("User-Agent", "waypoint-self-update")
@@ -206,7 +208,10 @@ fn fallback_install_sh() -> Result<(), WaypointError> {
 }
 
 /// Check for and optionally install the latest waypoint release.
-pub fn self_update(check_only: bool, json_output: bool) -> Result<(), WaypointError> {
+///
+/// With `quiet`, an up-to-date binary prints nothing and an available update
+/// prints a single line.
+pub fn self_update(check_only: bool, json_output: bool, quiet: bool) -> Result<(), WaypointError> {
     let current = current_version()?;
     let release = fetch_latest_release()?;
     let latest = parse_version(&release.tag_name)?;
@@ -234,7 +239,7 @@ pub fn self_update(check_only: bool, json_output: bool) -> Result<(), WaypointEr
                     "message": "Already up to date.",
                 })
             );
-        } else {
+        } else if !quiet {
             eprintln!(
                 "{} You are already on the latest version ({}).",
                 "✓".green().bold(),
@@ -252,18 +257,22 @@ pub fn self_update(check_only: bool, json_output: bool) -> Result<(), WaypointEr
                 current.to_string().dimmed(),
                 latest.to_string().green().bold()
             );
-            eprintln!("Run {} to update.", "waypoint self-update".bold());
+            if !quiet {
+                eprintln!("Run {} to update.", "waypoint self-update".bold());
+            }
         }
         return Ok(());
     }
 
-    eprintln!(
-        "Updating waypoint {} → {}...",
-        current.to_string().dimmed(),
-        latest.to_string().green().bold()
-    );
+    if !quiet {
+        eprintln!(
+            "Updating waypoint {} → {}...",
+            current.to_string().dimmed(),
+            latest.to_string().green().bold()
+        );
+    }
 
-    match download_and_replace(&latest.to_string()) {
+    match download_and_replace(&latest.to_string(), quiet) {
         Ok(()) => {
             if json_output {
                 println!(
@@ -275,7 +284,7 @@ pub fn self_update(check_only: bool, json_output: bool) -> Result<(), WaypointEr
                         "message": format!("Successfully updated to {}.", latest),
                     })
                 );
-            } else {
+            } else if !quiet {
                 eprintln!("{} Successfully updated to {}.", "✓".green().bold(), latest);
             }
         }
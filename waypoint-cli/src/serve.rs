@@ -0,0 +1,540 @@
+//! Long-running HTTP API for operator/controller-driven migrations.
+//! Exposes status and pending migrations, a token-protected trigger for
+//! migrate, and a server-sent-events stream of progress for the active run.
+
+use std::collections::HashSet;
+use std::convert::Infallible;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use axum::extract::State;
+use axum::http::{header, HeaderMap, StatusCode};
+use axum::response::sse::{Event, KeepAlive, Sse};
+use axum::response::{IntoResponse, Response};
+use axum::routing::{get, post};
+use axum::{Json, Router};
+use futures_util::stream::{self, Stream};
+use serde::Serialize;
+use tokio::sync::broadcast;
+use waypoint_core::config::WaypointConfig;
+use waypoint_core::error::WaypointError;
+#[cfg(feature = "postgres")]
+use waypoint_core::monitor::{Monitor, SessionActivity};
+use waypoint_core::{MigrationInfo, MigrationState, Waypoint};
+
+use crate::lineage;
+use crate::status_file::{self, LastError};
+
+/// How often the progress poller re-reads the history table during a run.
+const PROGRESS_POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Upper bound on connecting for a read (`/status`, `/pending`, the status
+/// file). Reads make a single attempt so an unreachable database answers
+/// 503 promptly instead of waiting out the configured retry policy.
+const READ_CONNECT_TIMEOUT_SECS: u32 = 5;
+
+/// Shared server state.
+struct ServerState {
+    config: WaypointConfig,
+    /// `config` with a single, short connect attempt, used for reads.
+    read_config: WaypointConfig,
+    /// Bearer token required by `POST /migrate`; trigger is disabled when unset.
+    token: Option<String>,
+    force: bool,
+    /// Operator-readable status file, rewritten at startup and around each run.
+    status_file: Option<String>,
+    /// Observe the migrating session over a second connection and stream
+    /// `activity` events.
+    monitor: bool,
+    run: Mutex<RunState>,
+    events: broadcast::Sender<ProgressEvent>,
+}
+
+#[derive(Default)]
+struct RunState {
+    running: bool,
+    last_run: Option<RunRecord>,
+    last_error: Option<LastError>,
+}
+
+/// Outcome of the most recent migrate triggered through the API.
+#[derive(Debug, Clone, Serialize)]
+struct RunRecord {
+    started_at: String,
+    finished_at: Option<String>,
+    success: Option<bool>,
+    migrations_applied: usize,
+    error: Option<String>,
+}
+
+/// Event emitted on the progress stream.
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+enum ProgressEvent {
+    Idle {
+        last_run: Option<RunRecord>,
+    },
+    Started,
+    Applied {
+        version: Option<String>,
+        script: String,
+    },
+    Finished {
+        migrations_applied: usize,
+        total_time_ms: i32,
+    },
+    Failed {
+        error: String,
+    },
+    /// What the migrating session is doing, sent whenever it changes.
+    #[cfg(feature = "postgres")]
+    Activity(SessionActivity),
+}
+
+impl ProgressEvent {
+    fn is_terminal(&self) -> bool {
+        matches!(
+            self,
+            ProgressEvent::Idle { .. }
+                | ProgressEvent::Finished { .. }
+                | ProgressEvent::Failed { .. }
+        )
+    }
+}
+
+#[derive(Serialize)]
+struct StatusResponse {
+    running: bool,
+    last_run: Option<RunRecord>,
+    migrations: Vec<MigrationInfo>,
+}
+
+/// Run the HTTP server until the process is terminated.
+pub async fn run(
+    config: WaypointConfig,
+    listen: &str,
+    status_file: Option<String>,
+    force: bool,
+    monitor: bool,
+) -> Result<(), WaypointError> {
+    if config.multi_database.is_some() {
+        return Err(WaypointError::ConfigError(
+            "serve does not support multi-database configurations".to_string(),
+        ));
+    }
+
+    let token = std::env::var("WAYPOINT_SERVE_TOKEN")
+        .ok()
+        .filter(|t| !t.is_empty());
+    if token.is_none() {
+        log::warn!("WAYPOINT_SERVE_TOKEN is not set; POST /migrate is disabled");
+    }
+
+    let (events, _) = broadcast::channel(64);
+    let state = Arc::new(ServerState {
+        read_config: read_config(&config),
+        config,
+        token,
+        force,
+        status_file,
+        monitor,
+        run: Mutex::new(RunState::default()),
+        events,
+    });
+    publish_status(&state).await;
+
+    let app = Router::new()
+        .route("/health", get(|| async { "ok" }))
+        .route("/status", get(status))
+        .route("/pending", get(pending))
+        .route("/migrate", post(trigger_migrate))
+        .route("/migrate/progress", get(progress))
+        .with_state(state);
+
+    let listener = tokio::net::TcpListener::bind(listen)
+        .await
+        .map_err(WaypointError::IoError)?;
+    log::info!("Serving HTTP API; listen={}", listen);
+    axum::serve(listener, app)
+        .await
+        .map_err(WaypointError::IoError)
+}
+
+async fn status(State(state): State<Arc<ServerState>>) -> Response {
+    let migrations = match load_info(&state.read_config).await {
+        Ok(infos) => infos,
+        Err(e) => return error_response(&e),
+    };
+    let run = state.run.lock().expect("run state lock poisoned");
+    Json(StatusResponse {
+        running: run.running,
+        last_run: run.last_run.clone(),
+        migrations,
+    })
+    .into_response()
+}
+
+async fn pending(State(state): State<Arc<ServerState>>) -> Response {
+    match load_info(&state.read_config).await {
+        Ok(infos) => Json(
+            infos
+                .into_iter()
+                .filter(|i| matches!(i.state, MigrationState::Pending | MigrationState::Outdated))
+                .collect::<Vec<_>>(),
+        )
+        .into_response(),
+        Err(e) => error_response(&e),
+    }
+}
+
+async fn trigger_migrate(State(state): State<Arc<ServerState>>, headers: HeaderMap) -> Response {
+    let Some(expected) = state.token.as_deref() else {
+        return message(StatusCode::FORBIDDEN, "migrate trigger is disabled");
+    };
+    let provided = headers
+        .get(header::AUTHORIZATION)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.strip_prefix("Bearer "));
+    if !provided.is_some_and(|p| tokens_match(p, expected)) {
+        return message(StatusCode::UNAUTHORIZED, "invalid or missing bearer token");
+    }
+
+    {
+        let mut run = state.run.lock().expect("run state lock poisoned");
+        if run.running {
+            return message(StatusCode::CONFLICT, "a migrate run is already in progress");
+        }
+        run.running = true;
+        run.last_run = Some(RunRecord {
+            started_at: chrono::Utc::now().to_rfc3339(),
+            finished_at: None,
+            success: None,
+            migrations_applied: 0,
+            error: None,
+        });
+    }
+
+    tokio::spawn(async move {
+        publish_status(&state).await;
+        run_migrate(state).await;
+    });
+    message(StatusCode::ACCEPTED, "migrate started")
+}
+
+async fn progress(
+    State(state): State<Arc<ServerState>>,
+) -> Sse<impl Stream<Item = Result<Event, Infallible>>> {
+    // Subscribe before checking `running` so no event between the two is lost.
+    let rx = state.events.subscribe();
+    let initial = {
+        let run = state.run.lock().expect("run state lock poisoned");
+        (!run.running).then(|| ProgressEvent::Idle {
+            last_run: run.last_run.clone(),
+        })
+    };
+
+    let events = stream::unfold(
+        (rx, initial, false),
+        |(mut rx, mut initial, done)| async move {
+            if done {
+                return None;
+            }
+            let event = match initial.take() {
+                Some(event) => event,
+                None => loop {
+                    match rx.recv().await {
+                        Ok(event) => break event,
+                        Err(broadcast::error::RecvError::Lagged(_)) => continue,
+                        Err(broadcast::error::RecvError::Closed) => return None,
+                    }
+                },
+            };
+            let done = event.is_terminal();
+            let sse = Event::default()
+                .json_data(&event)
+                .expect("progress event serialization failed");
+            Some((Ok(sse), (rx, initial, done)))
+        },
+    );
+
+    Sse::new(events).keep_alive(KeepAlive::default())
+}
+
+/// Execute a migrate run, publishing progress as history rows appear.
+async fn run_migrate(state: Arc<ServerState>) {
+    let _ = state.events.send(ProgressEvent::Started);
+
+    let seen = applied_keys(&load_info(&state.read_config).await.unwrap_or_default());
+    let (stop_tx, stop_rx) = tokio::sync::oneshot::channel();
+    let poller = tokio::spawn(poll_progress(state.clone(), seen, stop_rx));
+
+    let result = match Waypoint::new(state.config.clone()).await {
+        Ok(wp) => lineage::traced(&wp.config, wp.migrate_with_options(None, state.force)).await,
+        Err(e) => Err(e),
+    };
+
+    let _ = stop_tx.send(());
+    let _ = poller.await;
+
+    let post_check_error = result
+        .as_ref()
+        .ok()
+        .and_then(|r| r.ensure_post_checks_passed().err());
+    let failure = result.as_ref().err().or(post_check_error.as_ref());
+
+    let event = match failure {
+        Some(e) => {
+            log::error!("Migrate triggered via API failed; error={}", e);
+            ProgressEvent::Failed {
+                error: e.to_string(),
+            }
+        }
+        None => ProgressEvent::Finished {
+            migrations_applied: result.as_ref().map_or(0, |r| r.migrations_applied),
+            total_time_ms: result.as_ref().map_or(0, |r| r.total_time_ms),
+        },
+    };
+
+    {
+        let mut run = state.run.lock().expect("run state lock poisoned");
+        run.running = false;
+        if let Some(record) = run.last_run.as_mut() {
+            record.finished_at = Some(chrono::Utc::now().to_rfc3339());
+            record.success = Some(failure.is_none());
+            record.migrations_applied = result.as_ref().map_or(0, |r| r.migrations_applied);
+            record.error = failure.map(|e| e.to_string());
+        }
+        run.last_error = failure.map(LastError::from);
+    }
+    publish_status(&state).await;
+    let _ = state.events.send(event);
+}
+
+/// Rewrite the status file, if configured, from a fresh read of migration state.
+async fn publish_status(state: &ServerState) {
+    let Some(path) = state.status_file.as_deref() else {
+        return;
+    };
+    let infos = load_info(&state.read_config).await.ok();
+    let (running, last_error) = {
+        let run = state.run.lock().expect("run state lock poisoned");
+        (run.running, run.last_error.clone())
+    };
+    status_file::write(path, infos.as_deref(), running, last_error.as_ref());
+}
+
+/// Re-read history on a separate connection and emit an event per newly
+/// applied migration. Each migration commits its own history row, so they
+/// become visible here while the run is still in progress. With `--monitor`,
+/// also observe the session holding the migration lock and emit an
+/// `activity` event whenever what it is doing changes.
+async fn poll_progress(
+    state: Arc<ServerState>,
+    mut seen: HashSet<(String, Option<String>)>,
+    mut stop: tokio::sync::oneshot::Receiver<()>,
+) {
+    let wp = match Waypoint::new(state.config.clone()).await {
+        Ok(wp) => wp,
+        Err(e) => {
+            log::warn!("Progress poller could not connect; error={}", e);
+            return;
+        }
+    };
+
+    #[cfg(feature = "postgres")]
+    let monitor = if state.monitor {
+        Monitor::connect(&state.config)
+            .await
+            .map_err(|e| {
+                log::warn!(
+                    "Progress poller could not open a monitoring connection; error={}",
+                    e
+                )
+            })
+            .ok()
+    } else {
+        None
+    };
+    #[cfg(not(feature = "postgres"))]
+    if state.monitor {
+        log::warn!("--monitor needs PostgreSQL support; ignoring");
+    }
+    #[cfg(feature = "postgres")]
+    let mut last_activity: Option<SessionActivity> = None;
+
+    loop {
+        let stopping = tokio::select! {
+            _ = &mut stop => true,
+            _ = tokio::time::sleep(PROGRESS_POLL_INTERVAL) => false,
+        };
+
+        if let Ok(infos) = wp.info().await {
+            for info in infos.iter().filter(|i| i.state == MigrationState::Applied) {
+                if seen.insert(applied_key(info)) {
+                    let _ = state.events.send(ProgressEvent::Applied {
+                        version: info.version.clone(),
+                        script: info.script.clone(),
+                    });
+                }
+            }
+        }
+
+        #[cfg(feature = "postgres")]
+        if let Some(monitor) = &monitor {
+            let activity = match monitor.migrating_session(&state.config.migrations).await {
+                Ok(Some(pid)) => monitor.observe(pid).await.ok().flatten(),
+                _ => None,
+            };
+            if let Some(current) = activity
+                .as_ref()
+                .filter(|a| Some(*a) != last_activity.as_ref())
+            {
+                let _ = state.events.send(ProgressEvent::Activity(current.clone()));
+            }
+            last_activity = activity;
+        }
+
+        if stopping {
+            return;
+        }
+    }
+}
+
+/// `config` narrowed to one connect attempt bounded by `READ_CONNECT_TIMEOUT_SECS`.
+fn read_config(config: &WaypointConfig) -> WaypointConfig {
+    let mut read = config.clone();
+    let db = &mut read.database;
+    db.connect_retries = 0;
+    db.connect_timeout_secs = match db.connect_timeout_secs {
+        0 => READ_CONNECT_TIMEOUT_SECS,
+        secs => secs.min(READ_CONNECT_TIMEOUT_SECS),
+    };
+    db.connect_deadline_secs = db.connect_timeout_secs;
+    read
+}
+
+async fn load_info(config: &WaypointConfig) -> Result<Vec<MigrationInfo>, WaypointError> {
+    Waypoint::new(config.clone()).await?.info().await
+}
+
+fn applied_keys(infos: &[MigrationInfo]) -> HashSet<(String, Option<String>)> {
+    infos
+        .iter()
+        .filter(|i| i.state == MigrationState::Applied)
+        .map(applied_key)
+        .collect()
+}
+
+/// Identity of an applied history row; `installed_on` distinguishes
+/// re-applications of a repeatable migration.
+fn applied_key(info: &MigrationInfo) -> (String, Option<String>) {
+    (
+        info.script.clone(),
+        info.installed_on.map(|t| t.to_rfc3339()),
+    )
+}
+
+/// Compare tokens without short-circuiting on the first differing byte.
+fn tokens_match(provided: &str, expected: &str) -> bool {
+    let (a, b) = (provided.as_bytes(), expected.as_bytes());
+    a.len() == b.len() && a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+fn message(status: StatusCode, msg: &str) -> Response {
+    (status, Json(serde_json::json!({ "message": msg }))).into_response()
+}
+
+fn error_response(e: &WaypointError) -> Response {
+    let status = match e {
+        WaypointError::ConnectionFailed { .. } | WaypointError::ConnectionLost { .. } => {
+            StatusCode::SERVICE_UNAVAILABLE
+        }
+        _ => StatusCode::INTERNAL_SERVER_ERROR,
+    };
+    (status, Json(serde_json::json!({ "error": e.to_string() }))).into_response()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn state(token: Option<&str>) -> Arc<ServerState> {
+        let config = WaypointConfig::default();
+        Arc::new(ServerState {
+            read_config: read_config(&config),
+            config,
+            token: token.map(str::to_string),
+            force: false,
+            status_file: None,
+            monitor: false,
+            run: Mutex::new(RunState::default()),
+            events: broadcast::channel(4).0,
+        })
+    }
+
+    fn bearer(token: &str) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            header::AUTHORIZATION,
+            format!("Bearer {}", token).parse().unwrap(),
+        );
+        headers
+    }
+
+    #[test]
+    fn test_tokens_match() {
+        assert!(tokens_match("s3cret", "s3cret"));
+        assert!(!tokens_match("s3creT", "s3cret"));
+        assert!(!tokens_match("s3cre", "s3cret"));
+        assert!(!tokens_match("", "s3cret"));
+    }
+
+    #[test]
+    fn test_read_config_makes_a_single_short_attempt() {
+        let mut config = WaypointConfig::default();
+        config.database.connect_retries = 10;
+        config.database.connect_timeout_secs = 30;
+        config.database.connect_deadline_secs = 300;
+        let read = read_config(&config);
+        assert_eq!(read.database.connect_retries, 0);
+        assert_eq!(
+            read.database.connect_timeout_secs,
+            READ_CONNECT_TIMEOUT_SECS
+        );
+        assert_eq!(
+            read.database.connect_deadline_secs,
+            READ_CONNECT_TIMEOUT_SECS
+        );
+
+        config.database.connect_timeout_secs = 2;
+        assert_eq!(read_config(&config).database.connect_timeout_secs, 2);
+        config.database.connect_timeout_secs = 0;
+        assert_eq!(
+            read_config(&config).database.connect_timeout_secs,
+            READ_CONNECT_TIMEOUT_SECS
+        );
+    }
+
+    #[tokio::test]
+    async fn test_trigger_rejects_missing_or_wrong_token() {
+        let response = trigger_migrate(State(state(None)), bearer("s3cret")).await;
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+
+        let state = state(Some("s3cret"));
+        let response = trigger_migrate(State(state.clone()), HeaderMap::new()).await;
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+        let response = trigger_migrate(State(state.clone()), bearer("wrong")).await;
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+        assert!(!state.run.lock().unwrap().running);
+    }
+
+    #[tokio::test]
+    async fn test_trigger_conflicts_with_running_migrate() {
+        let state = state(Some("s3cret"));
+        state.run.lock().unwrap().running = true;
+
+        let response = trigger_migrate(State(state.clone()), bearer("s3cret")).await;
+        assert_eq!(response.status(), StatusCode::CONFLICT);
+        assert!(state.run.lock().unwrap().last_run.is_none());
+    }
+}
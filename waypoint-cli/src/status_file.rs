@@ -0,0 +1,173 @@
+//! Machine-readable migration status file for Kubernetes operators and other
+//! external controllers. The schema (`waypoint.tensorbee.io/v1`,
+//! `MigrationStatus`) is stable: fields are only ever added.
+
+use serde::{Deserialize, Serialize};
+use waypoint_core::error::WaypointError;
+use waypoint_core::migration::MigrationVersion;
+use waypoint_core::{MigrationInfo, MigrationState};
+
+const API_VERSION: &str = "waypoint.tensorbee.io/v1";
+const KIND: &str = "MigrationStatus";
+
+/// Top-level status document.
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct MigrationStatus {
+    api_version: String,
+    kind: String,
+    observed_at: String,
+    /// Highest applied (or baselined) version; `None` if unknown or empty.
+    current_version: Option<String>,
+    /// Pending plus outdated migrations; `None` if the database was unreachable.
+    pending_count: Option<usize>,
+    last_error: Option<LastError>,
+    conditions: Vec<Condition>,
+}
+
+/// Most recent failure, with a stable machine-readable reason.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LastError {
+    pub reason: String,
+    pub message: String,
+}
+
+impl From<&WaypointError> for LastError {
+    fn from(e: &WaypointError) -> Self {
+        let reason = match e {
+            WaypointError::ConfigError(_) => "ConfigError",
+            WaypointError::ConnectionFailed { .. } | WaypointError::ConnectionLost { .. } => {
+                "DatabaseUnavailable"
+            }
+            WaypointError::ValidationFailed(_) => "ValidationFailed",
+            WaypointError::LockError(_) => "LockUnavailable",
+            WaypointError::PreflightFailed { .. } => "PreflightFailed",
+            WaypointError::GuardFailed { .. } => "GuardFailed",
+            WaypointError::PostCheckFailed { .. } => "PostCheckFailed",
+            WaypointError::MigrationBlocked { .. } => "MigrationBlocked",
+            WaypointError::IncompatibleHistory { .. } => "IncompatibleHistory",
+            WaypointError::InputRequired { .. } => "InputRequired",
+            WaypointError::MigrationFailed { .. } | WaypointError::HookFailed { .. } => {
+                "MigrationFailed"
+            }
+            _ => "Error",
+        };
+        LastError {
+            reason: reason.to_string(),
+            message: e.to_string(),
+        }
+    }
+}
+
+/// Kubernetes-style status condition.
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Condition {
+    #[serde(rename = "type")]
+    type_: String,
+    status: String,
+    reason: String,
+    message: String,
+    last_transition_time: String,
+}
+
+/// Write the status file atomically (temp file + rename).
+///
+/// `infos` is `None` when the database could not be queried. Condition
+/// transition times are carried over from the previous file when a
+/// condition's status has not changed. Failures are logged, not returned: the
+/// status file must never change the outcome of a run.
+pub fn write(
+    path: &str,
+    infos: Option<&[MigrationInfo]>,
+    progressing: bool,
+    last_error: Option<&LastError>,
+) {
+    let now = chrono::Utc::now().to_rfc3339();
+    let previous: Option<MigrationStatus> = std::fs::read_to_string(path)
+        .ok()
+        .and_then(|s| serde_json::from_str(&s).ok());
+
+    let current_version = infos.and_then(|infos| {
+        infos
+            .iter()
+            .filter(|i| matches!(i.state, MigrationState::Applied | MigrationState::Baseline))
+            .filter_map(|i| i.version.as_deref())
+            .filter_map(|v| MigrationVersion::parse(v).ok())
+            .max()
+            .map(|v| v.to_string())
+    });
+    let pending_count = infos.map(|infos| {
+        infos
+            .iter()
+            .filter(|i| matches!(i.state, MigrationState::Pending | MigrationState::Outdated))
+            .count()
+    });
+
+    let (ready, ready_reason, ready_message) = match (last_error, pending_count) {
+        (Some(e), _) => (false, e.reason.clone(), e.message.clone()),
+        (None, None) => (false, "Unknown".into(), "database state unknown".into()),
+        (None, Some(0)) => (true, "UpToDate".into(), "all migrations applied".into()),
+        (None, Some(n)) => (
+            false,
+            "PendingMigrations".into(),
+            format!("{} migration(s) pending", n),
+        ),
+    };
+    let conditions = vec![
+        condition("Ready", ready, ready_reason, ready_message),
+        condition(
+            "Progressing",
+            progressing,
+            if progressing { "Migrating" } else { "Idle" }.into(),
+            if progressing {
+                "migrate run in progress"
+            } else {
+                "no migrate run in progress"
+            }
+            .into(),
+        ),
+        condition(
+            "Degraded",
+            last_error.is_some(),
+            last_error.map_or("NoError".into(), |e| e.reason.clone()),
+            last_error.map_or(String::new(), |e| e.message.clone()),
+        ),
+    ]
+    .into_iter()
+    .map(|mut c| {
+        c.last_transition_time = previous
+            .as_ref()
+            .and_then(|p| p.conditions.iter().find(|o| o.type_ == c.type_))
+            .filter(|o| o.status == c.status)
+            .map_or_else(|| now.clone(), |o| o.last_transition_time.clone());
+        c
+    })
+    .collect();
+
+    let status = MigrationStatus {
+        api_version: API_VERSION.to_string(),
+        kind: KIND.to_string(),
+        observed_at: now,
+        current_version,
+        pending_count,
+        last_error: last_error.cloned(),
+        conditions,
+    };
+
+    let json = serde_json::to_string_pretty(&status).expect("JSON serialization failed");
+    let tmp = format!("{}.tmp", path);
+    if let Err(e) = std::fs::write(&tmp, json).and_then(|()| std::fs::rename(&tmp, path)) {
+        log::error!("Failed to write status file; path={}, error={}", path, e);
+    }
+}
+
+fn condition(type_: &str, status: bool, reason: String, message: String) -> Condition {
+    Condition {
+        type_: type_.to_string(),
+        status: if status { "True" } else { "False" }.to_string(),
+        reason,
+        message,
+        last_transition_time: String::new(),
+    }
+}
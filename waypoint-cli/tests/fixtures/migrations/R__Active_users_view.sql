@@ -0,0 +1,2 @@
+CREATE OR REPLACE VIEW active_users AS
+SELECT id, name, email FROM users WHERE active;
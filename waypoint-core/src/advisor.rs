@@ -8,7 +8,7 @@
 //! Rule IDs are namespaced per engine so JSON consumers can ignore the
 //! dialect — they share the same [`Advisory`] / [`AdvisorReport`] types.
 
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 
 use crate::db::DbClient;
 use crate::dialect::DialectKind;
@@ -33,7 +33,7 @@ pub struct AdvisorConfig {
 }
 
 /// Severity of an advisory.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
 pub enum AdvisorySeverity {
     Info,
@@ -52,7 +52,7 @@ impl std::fmt::Display for AdvisorySeverity {
 }
 
 /// A single advisory finding.
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Advisory {
     /// Rule ID (e.g., "A001").
     pub rule_id: String,
@@ -69,8 +69,11 @@ pub struct Advisory {
 }
 
 /// Report from the schema advisor.
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AdvisorReport {
+    /// Report format version; see [`crate::REPORT_VERSION`].
+    #[serde(default)]
+    pub report_version: u32,
     /// Schema that was analyzed.
     pub schema: String,
     /// All advisory findings.
@@ -136,6 +139,7 @@ mod tests {
     #[test]
     fn test_generate_fix_sql_empty() {
         let report = AdvisorReport {
+            report_version: crate::REPORT_VERSION,
             schema: "public".to_string(),
             advisories: vec![],
             warning_count: 0,
@@ -148,6 +152,7 @@ mod tests {
     #[test]
     fn test_generate_fix_sql_with_advisories() {
         let report = AdvisorReport {
+            report_version: crate::REPORT_VERSION,
             schema: "public".to_string(),
             advisories: vec![
                 Advisory {
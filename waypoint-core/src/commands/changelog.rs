@@ -5,7 +5,7 @@
 
 use std::path::PathBuf;
 
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 
 use crate::error::Result;
 use crate::migration::{scan_migrations, MigrationKind, MigrationVersion};
@@ -33,7 +33,7 @@ impl ChangelogFormat {
 }
 
 /// Changes for a single migration version.
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct VersionChanges {
     /// Version string, or None for repeatable migrations.
     pub version: Option<String>,
@@ -46,8 +46,11 @@ pub struct VersionChanges {
 }
 
 /// Complete changelog report.
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct ChangelogReport {
+    /// Report format version; see [`crate::REPORT_VERSION`].
+    #[serde(default)]
+    pub report_version: u32,
     /// Per-version change entries in version order.
     pub versions: Vec<VersionChanges>,
     /// Total number of DDL changes across all versions.
@@ -107,6 +110,7 @@ pub fn execute(
     }
 
     Ok(ChangelogReport {
+        report_version: crate::REPORT_VERSION,
         versions,
         total_changes,
     })
@@ -228,6 +232,7 @@ mod tests {
     #[test]
     fn test_render_markdown() {
         let report = ChangelogReport {
+            report_version: crate::REPORT_VERSION,
             versions: vec![VersionChanges {
                 version: Some("1".to_string()),
                 description: "Create users".to_string(),
@@ -7,14 +7,14 @@ use std::collections::HashSet;
 use std::path::PathBuf;
 use std::process::Command;
 
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 
 use crate::error::{Result, WaypointError};
 use crate::migration::parse_migration_filename;
 use crate::sql_parser::extract_ddl_operations;
 
 /// Type of conflict detected.
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum ConflictType {
     /// Two branches define the same migration version number.
     VersionCollision,
@@ -32,7 +32,7 @@ impl std::fmt::Display for ConflictType {
 }
 
 /// A single conflict finding.
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Conflict {
     /// Category of the detected conflict.
     pub conflict_type: ConflictType,
@@ -43,8 +43,11 @@ pub struct Conflict {
 }
 
 /// Conflict detection report.
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct ConflictReport {
+    /// Report format version; see [`crate::REPORT_VERSION`].
+    #[serde(default)]
+    pub report_version: u32,
     /// All conflicts detected between branches.
     pub conflicts: Vec<Conflict>,
     /// Whether any conflicts were detected.
@@ -95,6 +98,7 @@ pub fn execute(locations: &[PathBuf], base_branch: &str) -> Result<ConflictRepor
     let has_conflicts = !conflicts.is_empty();
 
     Ok(ConflictReport {
+        report_version: crate::REPORT_VERSION,
         conflicts,
         has_conflicts,
         base_branch: base_branch.to_string(),
@@ -26,15 +26,15 @@ pub async fn execute(
         return Err(WaypointError::CleanDisabled);
     }
 
-    let table = &config.migrations.table;
+    let lock = db::pg_lock_name(&config.migrations)?;
 
     // Acquire advisory lock to prevent concurrent operations
-    db::acquire_advisory_lock(client, table).await?;
+    db::acquire_advisory_lock(client, &lock).await?;
 
     let result = execute_inner_pg(client, config).await;
 
     // Always release the lock
-    if let Err(e) = db::release_advisory_lock(client, table).await {
+    if let Err(e) = db::release_advisory_lock(client, &lock).await {
         log::error!("Failed to release advisory lock: {}", e);
     }
 
@@ -51,8 +51,8 @@ pub async fn execute_db(
         return Err(WaypointError::CleanDisabled);
     }
 
-    let table = &config.migrations.table;
-    client.acquire_lock(table).await?;
+    let lock = client.lock_name(&config.migrations).await?;
+    client.acquire_lock(&lock).await?;
 
     let result = match client.dialect_kind() {
         #[cfg(feature = "postgres")]
@@ -69,7 +69,7 @@ pub async fn execute_db(
         )),
     };
 
-    if let Err(e) = client.release_lock(table).await {
+    if let Err(e) = client.release_lock(&lock).await {
         log::error!("Failed to release advisory lock: {}", e);
     }
 
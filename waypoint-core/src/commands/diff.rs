@@ -1,6 +1,6 @@
 //! Compare live database schema against a target and generate migration SQL.
 
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 
 #[cfg(feature = "postgres")]
 use tokio_postgres::Client;
@@ -18,8 +18,11 @@ pub enum DiffTarget {
 }
 
 /// Report produced by the diff command.
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct DiffReport {
+    /// Report format version; see [`crate::REPORT_VERSION`].
+    #[serde(default)]
+    pub report_version: u32,
     /// List of individual schema differences found.
     pub diffs: Vec<SchemaDiff>,
     /// DDL SQL statements generated to reconcile the differences.
@@ -51,6 +54,7 @@ pub async fn execute(
     let has_changes = !diffs.is_empty();
 
     Ok(DiffReport {
+        report_version: crate::REPORT_VERSION,
         diffs,
         generated_sql,
         has_changes,
@@ -99,6 +103,7 @@ pub async fn execute_db(
     let has_changes = !diffs.is_empty();
 
     Ok(DiffReport {
+        report_version: crate::REPORT_VERSION,
         diffs,
         generated_sql,
         has_changes,
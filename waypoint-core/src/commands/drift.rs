@@ -3,7 +3,7 @@
 //! Creates a temporary schema (PostgreSQL) or database (MySQL), applies all
 //! migrations to it, then compares it against the live schema to detect drift.
 
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 
 #[cfg(feature = "postgres")]
 use tokio_postgres::Client;
@@ -20,7 +20,7 @@ use crate::placeholder::build_placeholders;
 use crate::schema::{self, SchemaDiff};
 
 /// Type of drift detected.
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum DriftType {
     /// An object exists in the live database but not in the expected migration state.
     ExtraObject,
@@ -41,7 +41,7 @@ impl std::fmt::Display for DriftType {
 }
 
 /// A single drift finding.
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DriftEntry {
     /// Category of drift (extra, missing, or modified).
     pub drift_type: DriftType,
@@ -52,8 +52,11 @@ pub struct DriftEntry {
 }
 
 /// Drift detection report.
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct DriftReport {
+    /// Report format version; see [`crate::REPORT_VERSION`].
+    #[serde(default)]
+    pub report_version: u32,
     /// All drift findings detected.
     pub drifts: Vec<DriftEntry>,
     /// Whether any drift was detected.
@@ -231,6 +234,7 @@ async fn run_drift_check(
     let has_drift = !drifts.is_empty();
 
     Ok(DriftReport {
+        report_version: crate::REPORT_VERSION,
         drifts,
         has_drift,
         schema: schema_name.to_string(),
@@ -362,6 +366,7 @@ async fn run_drift_check_mysql(
     let drifts = diffs_to_drift_entries(&diffs, table);
 
     Ok(DriftReport {
+        report_version: crate::REPORT_VERSION,
         has_drift: !drifts.is_empty(),
         drifts,
         schema: schema_name.to_string(),
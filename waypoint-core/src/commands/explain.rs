@@ -3,42 +3,60 @@
 //! Runs EXPLAIN on each DML statement within a rolled-back transaction
 //! to show execution plans and identify potential issues.
 
-use serde::Serialize;
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
 
 #[cfg(feature = "postgres")]
 use tokio_postgres::Client;
 
-use crate::commands::info::{self, MigrationState};
+use crate::commands::info::{self, MigrationInfo, MigrationState};
+use crate::commands::migrate::{repeatable_statuses, should_run_in_environment, RepeatableStatus};
 use crate::config::WaypointConfig;
 use crate::db::DbClient;
 use crate::dialect::DialectKind;
 use crate::error::Result;
 #[cfg(any(not(feature = "postgres"), not(feature = "mysql")))]
 use crate::error::WaypointError;
+use crate::migration::{MigrationVersion, ResolvedMigration};
 use crate::placeholder::{build_placeholders, replace_placeholders};
+use crate::reversal::{draft_undo, UndoDraft};
 #[cfg(feature = "postgres")]
 use crate::sql_parser::split_statements;
 
 /// EXPLAIN report for all pending migrations.
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct ExplainReport {
+    /// Report format version; see [`crate::REPORT_VERSION`].
+    #[serde(default)]
+    pub report_version: u32,
     /// Per-migration EXPLAIN analysis results.
     pub migrations: Vec<MigrationExplain>,
+    /// Every repeatable migration in scope, with whether migrate would
+    /// re-apply it and why.
+    #[serde(default)]
+    pub repeatables: Vec<RepeatableStatus>,
 }
 
 /// EXPLAIN analysis for a single migration.
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct MigrationExplain {
     /// Filename of the migration script.
     pub script: String,
     /// Version string, or None for repeatable migrations.
     pub version: Option<String>,
+    /// Tables and views the migration creates, alters, drops or writes to.
+    #[serde(default)]
+    pub touched_tables: Vec<String>,
     /// EXPLAIN results for each statement in the migration.
     pub statements: Vec<StatementExplain>,
+    /// Draft undo for a versioned migration that has no `U` script yet.
+    #[serde(default)]
+    pub undo_draft: Option<UndoDraft>,
 }
 
 /// EXPLAIN analysis for a single statement.
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct StatementExplain {
     /// Truncated preview of the SQL statement (up to 80 characters).
     pub statement_preview: String,
@@ -54,6 +72,57 @@ pub struct StatementExplain {
     pub is_ddl: bool,
 }
 
+/// Repeatable re-apply decisions, derived from the history rows in `infos`.
+fn repeatables_in_scope(
+    infos: &[MigrationInfo],
+    resolved: &[ResolvedMigration],
+    config: &WaypointConfig,
+) -> Result<Vec<RepeatableStatus>> {
+    // Later history rows overwrite earlier ones, so each script maps to the
+    // checksum it was last applied with.
+    let applied_scripts: HashMap<String, Option<i32>> = infos
+        .iter()
+        .filter(|i| i.version.is_none() && i.installed_on.is_some())
+        .filter(|i| !matches!(i.state, MigrationState::Failed | MigrationState::Baseline))
+        .map(|i| (i.script.clone(), i.checksum))
+        .collect();
+    // Pending versions are the ones migrate would apply ahead of the repeatables.
+    let available: Vec<MigrationVersion> = infos
+        .iter()
+        .filter(|i| matches!(i.state, MigrationState::Applied | MigrationState::Pending))
+        .filter_map(|i| MigrationVersion::parse(i.version.as_deref()?).ok())
+        .collect();
+    let baseline = infos
+        .iter()
+        .filter(|i| i.state == MigrationState::Baseline)
+        .find_map(|i| MigrationVersion::parse(i.version.as_deref()?).ok());
+    let environment = config.migrations.environment.as_deref();
+    repeatable_statuses(
+        resolved.iter().filter(|m| {
+            m.version().is_none()
+                && !m.is_undo()
+                && should_run_in_environment(&m.directives, environment)
+        }),
+        &applied_scripts,
+        &available,
+        baseline.as_ref(),
+    )
+}
+
+/// Draft an undo from the file's SQL (placeholders left in place, as in a
+/// `U` file) for a versioned migration without a `U` script.
+fn undo_draft_for(
+    migration: &ResolvedMigration,
+    resolved: &[ResolvedMigration],
+    dialect: DialectKind,
+) -> Option<UndoDraft> {
+    let version = migration.version().filter(|_| migration.is_versioned())?;
+    let has_undo = resolved
+        .iter()
+        .any(|m| m.is_undo() && m.version() == Some(version));
+    (!has_undo).then(|| draft_undo(&migration.sql, dialect))
+}
+
 /// Execute explain analysis for pending migrations (PostgreSQL legacy entry).
 #[cfg(feature = "postgres")]
 pub async fn execute(client: &Client, config: &WaypointConfig) -> Result<ExplainReport> {
@@ -170,11 +239,17 @@ pub async fn execute(client: &Client, config: &WaypointConfig) -> Result<Explain
         migrations.push(MigrationExplain {
             script: info.script.clone(),
             version: info.version.clone(),
+            touched_tables: crate::sql_parser::touched_tables(&sql),
             statements,
+            undo_draft: migration.and_then(|m| undo_draft_for(m, &resolved, DialectKind::Postgres)),
         });
     }
 
-    Ok(ExplainReport { migrations })
+    Ok(ExplainReport {
+        report_version: crate::REPORT_VERSION,
+        migrations,
+        repeatables: repeatables_in_scope(&infos, &resolved, config)?,
+    })
 }
 
 /// Execute explain analysis for pending migrations (dialect-aware entry).
@@ -312,11 +387,17 @@ async fn execute_mysql(client: &DbClient, config: &WaypointConfig) -> Result<Exp
         migrations.push(MigrationExplain {
             script: info.script.clone(),
             version: info.version.clone(),
+            touched_tables: crate::sql_parser::touched_tables(&sql),
             statements,
+            undo_draft: migration.and_then(|m| undo_draft_for(m, &resolved, DialectKind::Mysql)),
         });
     }
 
-    Ok(ExplainReport { migrations })
+    Ok(ExplainReport {
+        report_version: crate::REPORT_VERSION,
+        migrations,
+        repeatables: repeatables_in_scope(&infos, &resolved, config)?,
+    })
 }
 
 /// Extract row estimates and access-type warnings from a MySQL EXPLAIN
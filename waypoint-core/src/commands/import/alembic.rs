@@ -0,0 +1,546 @@
+//! Alembic revisions (`versions/*.py`).
+//!
+//! Revisions are ordered by their `down_revision` links, parents first, so a
+//! merge revision follows every revision it merges. Within `upgrade()`,
+//! `op.execute` with literal SQL is copied, and `op.drop_table`,
+//! `op.drop_column`, `op.drop_index`, `op.create_index` and
+//! `op.rename_table` with literal arguments are translated. Other `op.*`
+//! calls, such as `op.create_table` with SQLAlchemy column types, become
+//! TODO lines.
+
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+
+use super::{read_source, Converted};
+use crate::error::{Result, WaypointError};
+
+/// A parsed revision file.
+struct Revision {
+    id: String,
+    down: Vec<String>,
+    message: String,
+    upgrade: String,
+    path: PathBuf,
+}
+
+/// Convert every revision in `dir`, in apply order.
+pub(super) fn convert(dir: &Path) -> Result<Vec<Converted>> {
+    let mut paths: Vec<PathBuf> = std::fs::read_dir(dir)
+        .map_err(|e| {
+            WaypointError::IoError(std::io::Error::new(
+                e.kind(),
+                format!("Failed to read '{}': {}", dir.display(), e),
+            ))
+        })?
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|p| p.extension().is_some_and(|ext| ext == "py"))
+        .collect();
+    paths.sort();
+
+    let mut revisions = Vec::new();
+    for path in paths {
+        let source = read_source(&path)?;
+        if let Some(revision) = parse_revision(&source, &path) {
+            revisions.push(revision);
+        }
+    }
+
+    Ok(apply_order(revisions)?
+        .into_iter()
+        .map(|r| {
+            let mut c = Converted {
+                source_id: r.id,
+                origin: r.path,
+                description: r.message,
+                ..Default::default()
+            };
+            for (name, args) in op_calls(&r.upgrade) {
+                match translate(&name, &args) {
+                    Some(sql) => c.push_sql(&sql),
+                    None => c.push_todo(&format!("op.{}({})", name, args)),
+                }
+            }
+            c
+        })
+        .collect())
+}
+
+/// Parse a revision file; `None` if it has no `revision = ...` line.
+fn parse_revision(source: &str, path: &Path) -> Option<Revision> {
+    let id = assignment(source, "revision")?.into_iter().next()?;
+    let down = assignment(source, "down_revision").unwrap_or_default();
+
+    let message = docstring(source)
+        .and_then(|d| {
+            d.lines()
+                .map(str::trim)
+                .find(|l| !l.is_empty())
+                .map(str::to_string)
+        })
+        .or_else(|| {
+            // Alembic names files `<revision>_<slug>.py`.
+            let stem = path.file_stem()?.to_str()?;
+            stem.split_once('_').map(|(_, slug)| slug.to_string())
+        })
+        .unwrap_or_else(|| format!("revision {}", id));
+
+    let upgrade = function_body(source, "upgrade").unwrap_or_default();
+    Some(Revision {
+        id,
+        down,
+        message,
+        upgrade,
+        path: path.to_path_buf(),
+    })
+}
+
+/// Values of a top-level `name = ...` (or `name: Type = ...`) assignment:
+/// a string, a tuple of strings, or `None` (empty).
+fn assignment(source: &str, name: &str) -> Option<Vec<String>> {
+    source.lines().find_map(|line| {
+        let rest = line.strip_prefix(name)?;
+        if !rest.starts_with([' ', '=', ':']) {
+            return None;
+        }
+        let (_, value) = rest.split_once('=')?;
+        let value = value.trim().trim_start_matches(['(', '[']);
+        let value = value.trim_end_matches([')', ']']);
+        if value.trim() == "None" {
+            return Some(Vec::new());
+        }
+        Some(
+            split_args(value)
+                .into_iter()
+                .filter_map(|v| match parse_arg(v) {
+                    Arg::Str(s) => Some(s),
+                    _ => None,
+                })
+                .collect(),
+        )
+    })
+}
+
+/// The module docstring, if the file starts with one.
+fn docstring(source: &str) -> Option<String> {
+    let start = source.trim_start();
+    let (value, _) = read_string(start, 0)?;
+    Some(value)
+}
+
+/// Lines of the top-level `def name(...)` body.
+fn function_body(source: &str, name: &str) -> Option<String> {
+    let header = format!("def {}(", name);
+    let mut lines = source.lines().skip_while(|l| !l.starts_with(&header));
+    lines.next()?;
+    let body: Vec<&str> = lines
+        .take_while(|l| l.trim().is_empty() || l.starts_with([' ', '\t']))
+        .collect();
+    Some(body.join("\n"))
+}
+
+/// Parents-first order; ties keep file order.
+fn apply_order(revisions: Vec<Revision>) -> Result<Vec<Revision>> {
+    let known: HashSet<&str> = revisions.iter().map(|r| r.id.as_str()).collect();
+    for r in &revisions {
+        if let Some(missing) = r.down.iter().find(|d| !known.contains(d.as_str())) {
+            return Err(WaypointError::MigrationParseError(format!(
+                "Alembic revision {} ({}) revises unknown revision {}",
+                r.id,
+                r.path.display(),
+                missing
+            )));
+        }
+    }
+
+    let mut placed: HashSet<String> = HashSet::new();
+    let mut remaining: Vec<Revision> = revisions;
+    let mut ordered = Vec::with_capacity(remaining.len());
+    while !remaining.is_empty() {
+        let ready = remaining
+            .iter()
+            .position(|r| r.down.iter().all(|d| placed.contains(d)))
+            .ok_or_else(|| {
+                WaypointError::MigrationParseError(format!(
+                    "Alembic revisions form a cycle: {}",
+                    remaining
+                        .iter()
+                        .map(|r| r.id.as_str())
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                ))
+            })?;
+        let revision = remaining.remove(ready);
+        placed.insert(revision.id.clone());
+        ordered.push(revision);
+    }
+    Ok(ordered)
+}
+
+/// A call argument, as far as the converter understands it.
+#[derive(Debug, PartialEq)]
+enum Arg {
+    Str(String),
+    List(Vec<String>),
+    Kw(String, String),
+    Other,
+}
+
+/// Turn one `op.<name>(<args>)` call into SQL.
+fn translate(name: &str, args: &str) -> Option<String> {
+    let mut positional = Vec::new();
+    let mut keywords: HashMap<String, String> = HashMap::new();
+    let mut columns = None;
+    for piece in split_args(args) {
+        match parse_arg(piece) {
+            Arg::Str(s) => positional.push(s),
+            Arg::List(items) => columns = Some(items),
+            Arg::Kw(k, v) => {
+                keywords.insert(k, v);
+            }
+            Arg::Other => {
+                // op.execute(sa.text("...")) is literal SQL too.
+                let inner = piece
+                    .trim()
+                    .strip_prefix("sa.text(")
+                    .or_else(|| piece.trim().strip_prefix("text("))?
+                    .strip_suffix(')')?;
+                match parse_arg(inner) {
+                    Arg::Str(s) if name == "execute" => positional.push(s),
+                    _ => return None,
+                }
+            }
+        }
+    }
+
+    let schema = match keywords.remove("schema") {
+        Some(v) => match parse_arg(&v) {
+            Arg::Str(s) => format!("{}.", s),
+            _ => return None,
+        },
+        None => String::new(),
+    };
+    let unique = match keywords.remove("unique").as_deref() {
+        None | Some("False") => false,
+        Some("True") if name == "create_index" => true,
+        Some(_) => return None,
+    };
+    if name == "drop_index" {
+        keywords.remove("table_name");
+    }
+    if !keywords.is_empty() {
+        return None;
+    }
+
+    match (name, positional.as_slice(), columns) {
+        ("execute", [sql], None) => Some(sql.clone()),
+        ("drop_table", [table], None) => Some(format!("DROP TABLE {}{}", schema, table)),
+        ("drop_column", [table, column], None) => Some(format!(
+            "ALTER TABLE {}{} DROP COLUMN {}",
+            schema, table, column
+        )),
+        ("drop_index", [index] | [index, _], None) => {
+            Some(format!("DROP INDEX {}{}", schema, index))
+        }
+        ("create_index", [index, table], Some(cols)) => Some(format!(
+            "CREATE {}INDEX {} ON {}{} ({})",
+            if unique { "UNIQUE " } else { "" },
+            index,
+            schema,
+            table,
+            cols.join(", ")
+        )),
+        ("rename_table", [old, new], None) => {
+            Some(format!("ALTER TABLE {}{} RENAME TO {}", schema, old, new))
+        }
+        _ => None,
+    }
+}
+
+fn parse_arg(piece: &str) -> Arg {
+    let piece = piece.trim();
+    if let Some((key, value)) = piece.split_once('=') {
+        let key = key.trim();
+        if !key.is_empty()
+            && key.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+            && !value.starts_with('=')
+        {
+            return Arg::Kw(key.to_string(), value.trim().to_string());
+        }
+    }
+    if let Some(inner) = piece
+        .strip_prefix('[')
+        .and_then(|p| p.strip_suffix(']'))
+        .or_else(|| piece.strip_prefix('(').and_then(|p| p.strip_suffix(')')))
+    {
+        let mut items = Vec::new();
+        for item in split_args(inner) {
+            match parse_arg(item) {
+                Arg::Str(s) => items.push(s),
+                _ => return Arg::Other,
+            }
+        }
+        return Arg::List(items);
+    }
+
+    // One or more adjacent literals, concatenated as Python does.
+    let mut value = String::new();
+    let mut i = 0;
+    let mut any = false;
+    while i < piece.len() {
+        let rest = &piece[i..];
+        let skipped = rest.len() - rest.trim_start().len();
+        i += skipped;
+        if i >= piece.len() {
+            break;
+        }
+        match read_string(piece, i) {
+            Some((s, end)) => {
+                value.push_str(&s);
+                i = end;
+                any = true;
+            }
+            None => return Arg::Other,
+        }
+    }
+    if any {
+        Arg::Str(value)
+    } else {
+        Arg::Other
+    }
+}
+
+/// `op.<name>(...)` calls in source order, with their argument text.
+/// Comments and strings are skipped, so commented-out calls don't count.
+fn op_calls(body: &str) -> Vec<(String, String)> {
+    let bytes = body.as_bytes();
+    let mut calls = Vec::new();
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'#' => {
+                i = body[i..].find('\n').map_or(bytes.len(), |n| i + n);
+            }
+            b'\'' | b'"' => {
+                i = read_string(body, i).map_or(bytes.len(), |(_, end)| end);
+            }
+            _ if body[i..].starts_with("op.")
+                && (i == 0 || !is_ident_byte(bytes[i - 1]) && bytes[i - 1] != b'.') =>
+            {
+                let name_start = i + 3;
+                let name_end = body[name_start..]
+                    .find(|c: char| !(c.is_ascii_alphanumeric() || c == '_'))
+                    .map_or(bytes.len(), |n| name_start + n);
+                let open =
+                    name_end + (body[name_end..].len() - body[name_end..].trim_start().len());
+                if bytes.get(open) != Some(&b'(') {
+                    i = name_end;
+                    continue;
+                }
+                let close = matching_paren(body, open);
+                calls.push((
+                    body[name_start..name_end].to_string(),
+                    body[open + 1..close.min(bytes.len())].trim().to_string(),
+                ));
+                i = close + 1;
+            }
+            _ => i += 1,
+        }
+    }
+    calls
+}
+
+fn is_ident_byte(b: u8) -> bool {
+    b.is_ascii_alphanumeric() || b == b'_'
+}
+
+/// Index of the `)` closing the `(` at `open`, or the end of `s`.
+fn matching_paren(s: &str, open: usize) -> usize {
+    let bytes = s.as_bytes();
+    let mut depth = 0;
+    let mut i = open;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'(' | b'[' | b'{' => depth += 1,
+            b')' | b']' | b'}' => {
+                depth -= 1;
+                if depth == 0 {
+                    return i;
+                }
+            }
+            b'#' => {
+                i = s[i..].find('\n').map_or(bytes.len(), |n| i + n);
+                continue;
+            }
+            b'\'' | b'"' => {
+                i = read_string(s, i).map_or(bytes.len(), |(_, end)| end);
+                continue;
+            }
+            _ => {}
+        }
+        i += 1;
+    }
+    bytes.len()
+}
+
+/// Split on commas outside brackets and strings.
+fn split_args(s: &str) -> Vec<&str> {
+    let bytes = s.as_bytes();
+    let mut parts = Vec::new();
+    let (mut depth, mut start, mut i) = (0i32, 0, 0);
+    while i < bytes.len() {
+        match bytes[i] {
+            b'(' | b'[' | b'{' => depth += 1,
+            b')' | b']' | b'}' => depth -= 1,
+            b',' if depth == 0 => {
+                parts.push(s[start..i].trim());
+                start = i + 1;
+            }
+            b'\'' | b'"' => {
+                i = read_string(s, i).map_or(bytes.len(), |(_, end)| end);
+                continue;
+            }
+            _ => {}
+        }
+        i += 1;
+    }
+    let last = s[start..].trim();
+    if !last.is_empty() {
+        parts.push(last);
+    }
+    parts
+}
+
+/// Read a Python string literal (with optional `r`/`b`/`u`/`f` prefix) at
+/// byte `i`. Returns its value and the index just past it.
+fn read_string(s: &str, i: usize) -> Option<(String, usize)> {
+    let bytes = s.as_bytes();
+    let mut j = i;
+    let mut raw = false;
+    while j < bytes.len() && j - i < 2 && b"rRbBuUfF".contains(&bytes[j]) {
+        raw |= bytes[j] == b'r' || bytes[j] == b'R';
+        j += 1;
+    }
+    let quote = *bytes.get(j)?;
+    if quote != b'\'' && quote != b'"' {
+        return None;
+    }
+    let triple = bytes.get(j + 1) == Some(&quote) && bytes.get(j + 2) == Some(&quote);
+    let delim = if triple { &s[j..j + 3] } else { &s[j..j + 1] };
+    let start = j + delim.len();
+
+    let mut value = String::new();
+    let mut chars = s[start..].char_indices();
+    while let Some((k, c)) = chars.next() {
+        let at = start + k;
+        if s[at..].starts_with(delim) {
+            return Some((value, at + delim.len()));
+        }
+        if c == '\\' {
+            let (_, next) = chars.next()?;
+            if raw {
+                value.push('\\');
+                value.push(next);
+                continue;
+            }
+            match next {
+                'n' => value.push('\n'),
+                't' => value.push('\t'),
+                '\n' => {}
+                '\\' | '\'' | '"' => value.push(next),
+                other => {
+                    value.push('\\');
+                    value.push(other);
+                }
+            }
+        } else if c == '\n' && !triple {
+            return None;
+        } else {
+            value.push(c);
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const REVISION: &str = r#""""add users
+
+Revision ID: 1a2b
+Revises: 0f0f
+"""
+from alembic import op
+import sqlalchemy as sa
+
+revision: str = '1a2b'
+down_revision: Union[str, None] = "0f0f"
+
+
+def upgrade() -> None:
+    # op.drop_table('ignored')
+    op.execute("""
+        CREATE TABLE users (id BIGINT PRIMARY KEY, note TEXT DEFAULT 'a,b')
+    """)
+    op.create_index('users_note_idx', 'users', ['note'], unique=True)
+    op.add_column('users', sa.Column('email', sa.String(255)))
+    op.execute(sa.text("UPDATE users SET note = 'x'"))
+
+
+def downgrade() -> None:
+    op.drop_table('users')
+"#;
+
+    #[test]
+    fn test_parse_revision() {
+        let r = parse_revision(REVISION, Path::new("versions/1a2b_add_users.py")).unwrap();
+        assert_eq!(r.id, "1a2b");
+        assert_eq!(r.down, vec!["0f0f"]);
+        assert_eq!(r.message, "add users");
+        assert!(!r.upgrade.contains("downgrade"));
+
+        let calls = op_calls(&r.upgrade);
+        let names: Vec<&str> = calls.iter().map(|(n, _)| n.as_str()).collect();
+        assert_eq!(
+            names,
+            vec!["execute", "create_index", "add_column", "execute"]
+        );
+        assert_eq!(
+            translate(&calls[0].0, &calls[0].1).unwrap().trim(),
+            "CREATE TABLE users (id BIGINT PRIMARY KEY, note TEXT DEFAULT 'a,b')"
+        );
+        assert_eq!(
+            translate(&calls[1].0, &calls[1].1).unwrap(),
+            "CREATE UNIQUE INDEX users_note_idx ON users (note)"
+        );
+        assert_eq!(translate(&calls[2].0, &calls[2].1), None);
+        assert_eq!(
+            translate(&calls[3].0, &calls[3].1).unwrap(),
+            "UPDATE users SET note = 'x'"
+        );
+    }
+
+    #[test]
+    fn test_merge_revisions_follow_their_parents() {
+        let rev = |id: &str, down: &[&str]| Revision {
+            id: id.to_string(),
+            down: down.iter().map(|d| d.to_string()).collect(),
+            message: String::new(),
+            upgrade: String::new(),
+            path: PathBuf::from(format!("{}.py", id)),
+        };
+        let ordered = apply_order(vec![
+            rev("merge", &["b", "c"]),
+            rev("c", &["a"]),
+            rev("b", &["a"]),
+            rev("a", &[]),
+        ])
+        .unwrap();
+        let ids: Vec<&str> = ordered.iter().map(|r| r.id.as_str()).collect();
+        assert_eq!(ids, vec!["a", "c", "b", "merge"]);
+        assert_eq!(
+            assignment("down_revision = ('b', 'c')\n", "down_revision").unwrap(),
+            vec!["b", "c"]
+        );
+    }
+}
@@ -0,0 +1,723 @@
+//! Liquibase changelogs: XML, YAML, and formatted SQL.
+//!
+//! ChangeSets are taken in changelog order, following `include` into nested
+//! changelogs. `sql`, `createProcedure` and `sqlFile` changes are copied;
+//! every other change type becomes a TODO line naming it. Rollback blocks and
+//! preconditions are skipped. A simple `context` list carries over as a
+//! `-- waypoint:env` directive. ChangeSets whose `dbms` excludes the
+//! configured database are left out, and `runOnChange` changeSets become
+//! repeatable migrations.
+//!
+//! The parsers cover the shapes Liquibase itself writes and documents, not
+//! arbitrary XML or YAML.
+
+use std::path::{Path, PathBuf};
+use std::sync::LazyLock;
+
+use regex_lite::Regex;
+
+use super::{read_source, Converted};
+use crate::dialect::DialectKind;
+use crate::error::{Result, WaypointError};
+
+/// Change types whose body is SQL.
+const SQL_CHANGES: &[&str] = &["sql", "createProcedure"];
+
+static XML_ITEM_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?s)<changeSet\b([^>]*)>(.*?)</changeSet>|<(include|includeAll)\b([^>]*?)/?>")
+        .unwrap()
+});
+
+static XML_ATTR_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r#"([\w:]+)\s*=\s*(?:"([^"]*)"|'([^']*)')"#).unwrap());
+
+static XML_COMMENT_RE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"(?s)<!--.*?-->").unwrap());
+
+static XML_SKIPPED_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?s)<rollback\b.*?</rollback>|<rollback\b[^>]*/>|<preConditions\b.*?</preConditions>|<validCheckSum\b.*?</validCheckSum>")
+        .unwrap()
+});
+
+static XML_CHANGESET_COMMENT_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?s)<comment>(.*?)</comment>").unwrap());
+
+static FORMATTED_CHANGESET_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?i)^--\s*changeset\s+([^:\s]+):(\S+)(.*)$").unwrap());
+
+/// Convert the changelog at `path` for `dialect`, following includes.
+pub(super) fn convert(path: &Path, dialect: DialectKind) -> Result<Vec<Converted>> {
+    let mut out = Vec::new();
+    convert_into(path, dialect, &mut out, 0)?;
+    Ok(out)
+}
+
+fn convert_into(
+    path: &Path,
+    dialect: DialectKind,
+    out: &mut Vec<Converted>,
+    depth: usize,
+) -> Result<()> {
+    if depth > 32 {
+        return Err(WaypointError::MigrationParseError(format!(
+            "Liquibase includes nest too deeply at '{}'",
+            path.display()
+        )));
+    }
+    let source = read_source(path)?;
+    let ext = path
+        .extension()
+        .and_then(|e| e.to_str())
+        .unwrap_or("")
+        .to_ascii_lowercase();
+    let items = match ext.as_str() {
+        "xml" => parse_xml(&source),
+        "yaml" | "yml" => parse_yaml(&source),
+        "sql" => parse_formatted_sql(&source),
+        _ => {
+            return Err(WaypointError::MigrationParseError(format!(
+                "Unsupported Liquibase changelog '{}'; expected .xml, .yaml, .yml or .sql",
+                path.display()
+            )))
+        }
+    };
+
+    for item in items {
+        match item {
+            Item::ChangeSet(cs) => {
+                if cs
+                    .dbms
+                    .as_deref()
+                    .is_some_and(|d| !dbms_matches(d, dialect))
+                {
+                    log::info!(
+                        target: crate::logging::MIGRATE,
+                        "Skipping Liquibase changeSet for another database; id={}, author={}, dbms={}",
+                        cs.id,
+                        cs.author,
+                        cs.dbms.as_deref().unwrap_or_default()
+                    );
+                    continue;
+                }
+                out.push(cs.convert(path));
+            }
+            Item::Include { file, relative } => {
+                let included = resolve(path, &file, relative);
+                convert_into(&included, dialect, out, depth + 1)?;
+            }
+            Item::IncludeAll(dir) => {
+                log::warn!(
+                    target: crate::logging::MIGRATE,
+                    "Liquibase includeAll is not followed; import that directory's changelogs separately; path={}",
+                    dir
+                );
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Whether a `dbms` list such as `postgresql, mariadb` or `!h2` admits
+/// `dialect`. Negated entries exclude; otherwise some entry (or `all`) has to
+/// name it.
+fn dbms_matches(dbms: &str, dialect: DialectKind) -> bool {
+    let names: &[&str] = match dialect {
+        DialectKind::Postgres => &["postgresql"],
+        DialectKind::Mysql => &["mysql", "mariadb"],
+    };
+    let mut listed = false;
+    let mut matched = false;
+    for entry in dbms.split(',').map(|e| e.trim().to_ascii_lowercase()) {
+        if entry.is_empty() {
+            continue;
+        }
+        if let Some(excluded) = entry.strip_prefix('!') {
+            if names.contains(&excluded.trim()) {
+                return false;
+            }
+        } else {
+            listed = true;
+            matched |= entry == "all" || names.contains(&entry.as_str());
+        }
+    }
+    !listed || matched
+}
+
+/// Resolve a path from a changelog: next to the changelog when it says so or
+/// when the file exists there, else as given.
+fn resolve(changelog: &Path, file: &str, relative: bool) -> PathBuf {
+    let beside = changelog
+        .parent()
+        .unwrap_or_else(|| Path::new(""))
+        .join(file);
+    if relative || beside.exists() {
+        beside
+    } else {
+        PathBuf::from(file)
+    }
+}
+
+enum Item {
+    ChangeSet(ChangeSet),
+    Include { file: String, relative: bool },
+    IncludeAll(String),
+}
+
+#[derive(Debug, Default, PartialEq)]
+struct ChangeSet {
+    id: String,
+    author: String,
+    comment: Option<String>,
+    context: Option<String>,
+    dbms: Option<String>,
+    run_on_change: bool,
+    changes: Vec<Change>,
+}
+
+#[derive(Debug, PartialEq)]
+enum Change {
+    Sql(String),
+    SqlFile { path: String, relative: bool },
+    Unsupported(String),
+}
+
+impl ChangeSet {
+    fn convert(self, changelog: &Path) -> Converted {
+        let description = self.comment.clone().unwrap_or_else(|| {
+            if self.id.chars().any(|c| c.is_alphabetic()) {
+                self.id.clone()
+            } else {
+                format!("changeset {}", self.id)
+            }
+        });
+        let env = self
+            .context
+            .as_deref()
+            .filter(|c| {
+                c.chars()
+                    .all(|ch| ch.is_alphanumeric() || matches!(ch, '_' | '-' | ',' | ' '))
+            })
+            .map(|c| {
+                c.split(',')
+                    .map(|e| e.trim().to_string())
+                    .filter(|e| !e.is_empty())
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        let mut c = Converted {
+            source_id: format!("{}:{}", self.id, self.author),
+            origin: changelog.to_path_buf(),
+            description,
+            env,
+            repeatable: self.run_on_change,
+            ..Default::default()
+        };
+        for change in self.changes {
+            match change {
+                Change::Sql(sql) => c.push_sql(&sql),
+                Change::SqlFile { path, relative } => {
+                    match std::fs::read_to_string(resolve(changelog, &path, relative)) {
+                        Ok(sql) => c.push_sql(&sql),
+                        Err(e) => c.push_todo(&format!("sqlFile {} (unreadable: {})", path, e)),
+                    }
+                }
+                Change::Unsupported(what) => c.push_todo(&what),
+            }
+        }
+        c
+    }
+}
+
+// ── XML ─────────────────────────────────────────────────────────────────────
+
+fn parse_xml(source: &str) -> Vec<Item> {
+    let source = XML_COMMENT_RE.replace_all(source, "");
+    XML_ITEM_RE
+        .captures_iter(&source)
+        .map(|caps| match caps.get(3).map(|m| m.as_str()) {
+            Some(tag) => {
+                let attrs = xml_attrs(&caps[4]);
+                let file = attr(&attrs, "file")
+                    .or_else(|| attr(&attrs, "path"))
+                    .unwrap_or_default();
+                if tag == "includeAll" {
+                    Item::IncludeAll(file)
+                } else {
+                    Item::Include {
+                        relative: attr(&attrs, "relativeToChangelogFile").as_deref()
+                            == Some("true"),
+                        file,
+                    }
+                }
+            }
+            None => Item::ChangeSet(xml_changeset(&caps[1], &caps[2])),
+        })
+        .collect()
+}
+
+fn xml_changeset(attrs: &str, body: &str) -> ChangeSet {
+    let attrs = xml_attrs(attrs);
+    let body = XML_SKIPPED_RE.replace_all(body, "");
+    let comment = XML_CHANGESET_COMMENT_RE
+        .captures(&body)
+        .map(|c| unescape_xml(c[1].trim()));
+    let body = XML_CHANGESET_COMMENT_RE.replace_all(&body, "");
+
+    let mut changes = Vec::new();
+    let mut rest: &str = &body;
+    while let Some(open) = rest.find('<') {
+        rest = &rest[open..];
+        let Some(tag_end) = rest.find('>') else { break };
+        let start_tag = &rest[..=tag_end];
+        let name: String = start_tag[1..]
+            .chars()
+            .take_while(|c| c.is_alphanumeric() || *c == '_' || *c == ':')
+            .collect();
+        if name.is_empty() {
+            rest = &rest[1..];
+            continue;
+        }
+        let self_closing = start_tag.ends_with("/>");
+        let (content, next) = if self_closing {
+            ("", tag_end + 1)
+        } else {
+            let close = format!("</{}>", name);
+            match rest.find(&close) {
+                Some(end) => (&rest[tag_end + 1..end], end + close.len()),
+                None => ("", tag_end + 1),
+            }
+        };
+        let element_attrs = xml_attrs(start_tag);
+
+        if SQL_CHANGES.contains(&name.as_str()) {
+            changes.push(Change::Sql(xml_text(content)));
+        } else if name == "sqlFile" {
+            changes.push(Change::SqlFile {
+                path: attr(&element_attrs, "path").unwrap_or_default(),
+                relative: attr(&element_attrs, "relativeToChangelogFile").as_deref()
+                    == Some("true"),
+            });
+        } else {
+            changes.push(Change::Unsupported(start_tag.to_string()));
+        }
+        rest = &rest[next..];
+    }
+
+    ChangeSet {
+        id: attr(&attrs, "id").unwrap_or_default(),
+        author: attr(&attrs, "author").unwrap_or_default(),
+        comment,
+        context: attr(&attrs, "context").or_else(|| attr(&attrs, "contextFilter")),
+        dbms: attr(&attrs, "dbms"),
+        run_on_change: attr(&attrs, "runOnChange").as_deref() == Some("true"),
+        changes,
+    }
+}
+
+fn xml_attrs(s: &str) -> Vec<(String, String)> {
+    XML_ATTR_RE
+        .captures_iter(s)
+        .map(|c| {
+            let value = c.get(2).or_else(|| c.get(3)).map_or("", |m| m.as_str());
+            (c[1].to_string(), unescape_xml(value))
+        })
+        .collect()
+}
+
+fn attr(attrs: &[(String, String)], name: &str) -> Option<String> {
+    attrs
+        .iter()
+        .find(|(k, _)| k == name)
+        .map(|(_, v)| v.clone())
+}
+
+/// Element text: CDATA sections verbatim, the rest with entities decoded.
+fn xml_text(content: &str) -> String {
+    let mut out = String::new();
+    let mut rest = content;
+    while let Some(start) = rest.find("<![CDATA[") {
+        out.push_str(&unescape_xml(&rest[..start]));
+        let after = &rest[start + 9..];
+        let end = after.find("]]>").unwrap_or(after.len());
+        out.push_str(&after[..end]);
+        rest = after.get(end + 3..).unwrap_or("");
+    }
+    out.push_str(&unescape_xml(rest));
+    out
+}
+
+fn unescape_xml(s: &str) -> String {
+    s.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&")
+}
+
+// ── YAML ────────────────────────────────────────────────────────────────────
+
+/// A line with its indentation. Blank and comment-only lines are kept, so
+/// block scalars read back verbatim, but never delimit a block.
+struct Line<'a> {
+    indent: usize,
+    text: &'a str,
+}
+
+impl Line<'_> {
+    fn is_structural(&self) -> bool {
+        let text = self.text.trim_start();
+        !text.is_empty() && !text.starts_with('#')
+    }
+}
+
+fn parse_yaml(source: &str) -> Vec<Item> {
+    let lines: Vec<Line> = source
+        .lines()
+        .map(|l| Line {
+            indent: l.len() - l.trim_start().len(),
+            text: l.trim_end(),
+        })
+        .collect();
+
+    let mut items = Vec::new();
+    let mut i = 0;
+    while i < lines.len() {
+        let line = &lines[i];
+        let entry = line
+            .is_structural()
+            .then(|| line.text.trim_start().strip_prefix("- ").map(str::trim))
+            .flatten();
+        match entry {
+            Some("changeSet:") => {
+                let block_end = block_end(&lines, i);
+                items.push(Item::ChangeSet(yaml_changeset(&lines[i + 1..block_end])));
+                i = block_end;
+            }
+            Some("include:") | Some("includeAll:") => {
+                let block_end = block_end(&lines, i);
+                let block = &lines[i + 1..block_end];
+                let file = yaml_value(block, "file")
+                    .or_else(|| yaml_value(block, "path"))
+                    .unwrap_or_default();
+                items.push(if entry == Some("includeAll:") {
+                    Item::IncludeAll(file)
+                } else {
+                    Item::Include {
+                        relative: yaml_value(block, "relativeToChangelogFile").as_deref()
+                            == Some("true"),
+                        file,
+                    }
+                });
+                i = block_end;
+            }
+            _ => i += 1,
+        }
+    }
+    items
+}
+
+/// Index past the lines nested under `lines[start]`.
+fn block_end(lines: &[Line], start: usize) -> usize {
+    let indent = lines[start].indent;
+    lines[start + 1..]
+        .iter()
+        .position(|l| l.is_structural() && l.indent <= indent)
+        .map_or(lines.len(), |n| start + 1 + n)
+}
+
+/// Indentation of the first structural line of `block`.
+fn top_indent(block: &[Line]) -> Option<usize> {
+    block.iter().find(|l| l.is_structural()).map(|l| l.indent)
+}
+
+/// Index of `key:` at the top level of `block`.
+fn key_position(block: &[Line], key: &str) -> Option<usize> {
+    let top = top_indent(block)?;
+    block.iter().position(|l| {
+        l.is_structural()
+            && l.indent == top
+            && l.text
+                .trim_start()
+                .strip_prefix(key)
+                .is_some_and(|r| r.starts_with(':'))
+    })
+}
+
+/// The scalar value of `key` at the top level of `block`.
+fn yaml_value(block: &[Line], key: &str) -> Option<String> {
+    let at = key_position(block, key)?;
+    Some(yaml_scalar(&block[at].text.trim_start()[key.len() + 1..]))
+}
+
+fn yaml_scalar(s: &str) -> String {
+    let s = s.trim();
+    if s.len() >= 2
+        && ((s.starts_with('"') && s.ends_with('"')) || (s.starts_with('\'') && s.ends_with('\'')))
+    {
+        s[1..s.len() - 1].to_string()
+    } else {
+        s.to_string()
+    }
+}
+
+fn yaml_changeset(block: &[Line]) -> ChangeSet {
+    let mut cs = ChangeSet {
+        id: yaml_value(block, "id").unwrap_or_default(),
+        author: yaml_value(block, "author").unwrap_or_default(),
+        comment: yaml_value(block, "comment"),
+        context: yaml_value(block, "context").or_else(|| yaml_value(block, "contextFilter")),
+        dbms: yaml_value(block, "dbms"),
+        run_on_change: yaml_value(block, "runOnChange").as_deref() == Some("true"),
+        changes: Vec::new(),
+    };
+    let Some(start) = key_position(block, "changes") else {
+        return cs;
+    };
+    let end = block_end(block, start);
+
+    let changes = &block[start + 1..end];
+    let mut i = 0;
+    while i < changes.len() {
+        if !changes[i].is_structural() {
+            i += 1;
+            continue;
+        }
+        let item_end = block_end(changes, i);
+        let text = changes[i].text.trim_start();
+        if let Some(name) = text
+            .strip_prefix("- ")
+            .and_then(|t| t.trim().strip_suffix(':'))
+        {
+            let body = &changes[i + 1..item_end];
+            if SQL_CHANGES.contains(&name) {
+                cs.changes
+                    .push(Change::Sql(yaml_text(body, "sql").unwrap_or_default()));
+            } else if name == "sqlFile" {
+                cs.changes.push(Change::SqlFile {
+                    path: yaml_value(body, "path").unwrap_or_default(),
+                    relative: yaml_value(body, "relativeToChangelogFile").as_deref()
+                        == Some("true"),
+                });
+            } else {
+                cs.changes.push(Change::Unsupported(format!("{}:", name)));
+            }
+        }
+        i = item_end;
+    }
+    cs
+}
+
+/// `key` as a plain scalar or a `|` / `>` block scalar. Block scalar lines
+/// are everything indented past `key`, blank and `#` lines included.
+fn yaml_text(block: &[Line], key: &str) -> Option<String> {
+    let at = key_position(block, key)?;
+    let value = block[at].text.trim_start()[key.len() + 1..].trim();
+    if !value.starts_with(['|', '>']) {
+        return Some(yaml_scalar(value));
+    }
+    let folded = value.starts_with('>');
+    let key_indent = block[at].indent;
+    let mut lines: Vec<&Line> = block[at + 1..]
+        .iter()
+        .take_while(|l| l.text.trim().is_empty() || l.indent > key_indent)
+        .collect();
+    while lines.last().is_some_and(|l| l.text.trim().is_empty()) {
+        lines.pop();
+    }
+    let indent = lines
+        .iter()
+        .filter(|l| !l.text.trim().is_empty())
+        .map(|l| l.indent)
+        .min()
+        .unwrap_or(0);
+
+    let mut text = String::new();
+    for (n, line) in lines.iter().enumerate() {
+        let content = line.text.get(indent..).unwrap_or("");
+        if !folded {
+            if n > 0 {
+                text.push('\n');
+            }
+        } else if content.is_empty() {
+            // Folding joins lines with a space; a blank line is a line break.
+            text.push('\n');
+        } else if n > 0 && !lines[n - 1].text.trim().is_empty() {
+            text.push(' ');
+        }
+        text.push_str(content);
+    }
+    Some(text)
+}
+
+// ── Formatted SQL ───────────────────────────────────────────────────────────
+
+fn parse_formatted_sql(source: &str) -> Vec<Item> {
+    let mut items = Vec::new();
+    let mut current: Option<(ChangeSet, String)> = None;
+    for line in source.lines() {
+        if let Some(caps) = FORMATTED_CHANGESET_RE.captures(line.trim()) {
+            if let Some((mut cs, sql)) = current.take() {
+                cs.changes.push(Change::Sql(sql));
+                items.push(Item::ChangeSet(cs));
+            }
+            let attr = |names: &[&str]| {
+                caps[3].split_whitespace().find_map(|attr| {
+                    names
+                        .iter()
+                        .find_map(|name| attr.strip_prefix(name)?.strip_prefix(':'))
+                        .map(str::to_string)
+                })
+            };
+            current = Some((
+                ChangeSet {
+                    author: caps[1].to_string(),
+                    id: caps[2].to_string(),
+                    context: attr(&["context", "contextFilter"]),
+                    dbms: attr(&["dbms"]),
+                    run_on_change: attr(&["runOnChange"]).as_deref() == Some("true"),
+                    ..Default::default()
+                },
+                String::new(),
+            ));
+            continue;
+        }
+        let Some((cs, sql)) = current.as_mut() else {
+            continue;
+        };
+        let trimmed = line.trim_start();
+        if let Some(comment) = trimmed.strip_prefix("--comment:") {
+            cs.comment = Some(comment.trim().to_string());
+        } else if trimmed.starts_with("--rollback") || trimmed.starts_with("--precondition") {
+            continue;
+        } else {
+            sql.push_str(line);
+            sql.push('\n');
+        }
+    }
+    if let Some((mut cs, sql)) = current {
+        cs.changes.push(Change::Sql(sql));
+        items.push(Item::ChangeSet(cs));
+    }
+    items
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn changesets(items: Vec<Item>) -> Vec<ChangeSet> {
+        items
+            .into_iter()
+            .filter_map(|i| match i {
+                Item::ChangeSet(cs) => Some(cs),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_parse_xml_changelog() {
+        let xml = r#"<?xml version="1.0" encoding="UTF-8"?>
+<databaseChangeLog xmlns="http://www.liquibase.org/xml/ns/dbchangelog">
+    <!-- <changeSet id="old" author="x"></changeSet> -->
+    <changeSet id="1" author="alice" context="dev, staging">
+        <comment>Create users</comment>
+        <sql><![CDATA[CREATE TABLE users (id INT CHECK (id > 0))]]></sql>
+        <rollback><sql>DROP TABLE users</sql></rollback>
+    </changeSet>
+    <include file="more.xml" relativeToChangelogFile="true"/>
+    <changeSet id="add-email" author="bob">
+        <addColumn tableName="users"><column name="email" type="text"/></addColumn>
+        <sql>UPDATE users SET id = id &amp; 1</sql>
+    </changeSet>
+</databaseChangeLog>"#;
+        let items = parse_xml(xml);
+        assert!(matches!(&items[1], Item::Include { file, relative: true } if file == "more.xml"));
+        let sets = changesets(items);
+        assert_eq!(sets.len(), 2);
+        assert_eq!(sets[0].comment.as_deref(), Some("Create users"));
+        assert_eq!(sets[0].context.as_deref(), Some("dev, staging"));
+        assert_eq!(
+            sets[0].changes,
+            vec![Change::Sql(
+                "CREATE TABLE users (id INT CHECK (id > 0))".to_string()
+            )]
+        );
+        assert_eq!(
+            sets[1].changes,
+            vec![
+                Change::Unsupported(r#"<addColumn tableName="users">"#.to_string()),
+                Change::Sql("UPDATE users SET id = id & 1".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_yaml_changelog() {
+        let yaml = r#"databaseChangeLog:
+  - changeSet:
+      id: 1
+      author: alice
+      changes:
+        - sql:
+            sql: |
+              CREATE TABLE users (
+                id INT
+              );
+        - createIndex:
+            tableName: users
+      rollback:
+        - sql:
+            sql: DROP TABLE users
+  - include:
+      file: next.yaml
+  - changeSet:
+      id: "2"
+      author: bob
+      context: prod
+      changes:
+        - sqlFile:
+            path: sql/two.sql
+"#;
+        let items = parse_yaml(yaml);
+        assert_eq!(items.len(), 3);
+        let sets = changesets(items);
+        assert_eq!(
+            sets[0].changes,
+            vec![
+                Change::Sql("CREATE TABLE users (\n  id INT\n);".to_string()),
+                Change::Unsupported("createIndex:".to_string()),
+            ]
+        );
+        assert_eq!(sets[1].id, "2");
+        assert_eq!(sets[1].context.as_deref(), Some("prod"));
+        assert_eq!(
+            sets[1].changes,
+            vec![Change::SqlFile {
+                path: "sql/two.sql".to_string(),
+                relative: false
+            }]
+        );
+    }
+
+    #[test]
+    fn test_parse_formatted_sql_changelog() {
+        let sql = "--liquibase formatted sql\n\n\
+                   --changeset alice:1 context:dev\n\
+                   --comment: Create users\n\
+                   CREATE TABLE users (id INT);\n\
+                   --rollback DROP TABLE users;\n\n\
+                   --changeset bob:2\n\
+                   ALTER TABLE users ADD email TEXT;\n";
+        let sets = changesets(parse_formatted_sql(sql));
+        assert_eq!(sets.len(), 2);
+        assert_eq!(sets[0].author, "alice");
+        assert_eq!(sets[0].context.as_deref(), Some("dev"));
+        assert_eq!(sets[0].comment.as_deref(), Some("Create users"));
+        assert_eq!(
+            sets[0].changes,
+            vec![Change::Sql("CREATE TABLE users (id INT);\n\n".to_string())]
+        );
+        assert_eq!(sets[1].id, "2");
+    }
+}
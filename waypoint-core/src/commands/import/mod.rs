@@ -0,0 +1,371 @@
+//! Convert another tool's migration history into waypoint files
+//! (`waypoint import`).
+//!
+//! Each Alembic revision or Liquibase changeSet becomes one
+//! `V{version}__{description}.sql` file, in the order the source tool
+//! applies them, numbered after the highest version already in the output
+//! location. A Liquibase `runOnChange` changeSet becomes an unnumbered
+//! `R__{description}.sql` repeatable instead. SQL the source holds verbatim (`op.execute`, `<sql>`,
+//! `sqlFile`) is copied as is, and a handful of simple operations are
+//! translated. Anything else is left in the file as a `-- TODO` line so the
+//! conversion never silently drops a change.
+//!
+//! Next to the files, `import-map.json` maps every source revision or
+//! changeSet to its waypoint version. A database already managed by the old
+//! tool is adopted with `waypoint baseline --baseline-version <version>`,
+//! using the version its current revision maps to.
+
+mod alembic;
+mod liquibase;
+
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::config::WaypointConfig;
+use crate::error::{Result, WaypointError};
+use crate::migration::scan_migrations;
+
+/// Name of the mapping file written to the output directory.
+pub const MAPPING_FILE: &str = "import-map.json";
+
+/// Migration tool to import from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ImportSource {
+    /// An Alembic `versions/` directory.
+    Alembic,
+    /// A Liquibase XML or YAML changelog.
+    Liquibase,
+}
+
+impl std::str::FromStr for ImportSource {
+    type Err = WaypointError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_ascii_lowercase().as_str() {
+            "alembic" => Ok(ImportSource::Alembic),
+            "liquibase" => Ok(ImportSource::Liquibase),
+            other => Err(WaypointError::ConfigError(format!(
+                "Unknown import source '{}'; expected alembic or liquibase",
+                other
+            ))),
+        }
+    }
+}
+
+impl std::fmt::Display for ImportSource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ImportSource::Alembic => write!(f, "alembic"),
+            ImportSource::Liquibase => write!(f, "liquibase"),
+        }
+    }
+}
+
+/// Report produced by `waypoint import`; also the contents of
+/// [`MAPPING_FILE`].
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ImportReport {
+    /// Report format version; see [`crate::REPORT_VERSION`].
+    #[serde(default)]
+    pub report_version: u32,
+    /// Tool the history came from.
+    pub source: ImportSource,
+    /// Directory the files were written to.
+    pub output: String,
+    /// One entry per source revision or changeSet, in apply order.
+    pub migrations: Vec<ImportedMigration>,
+    /// Total `-- TODO` lines left for manual conversion.
+    pub todo_count: usize,
+}
+
+/// A source revision or changeSet and the file it became.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ImportedMigration {
+    /// Alembic revision id, or Liquibase `id:author`.
+    pub source_id: String,
+    /// File the revision or changeSet was read from.
+    pub origin: String,
+    /// Waypoint version it was given; `None` for a repeatable migration.
+    pub version: Option<String>,
+    /// Name of the written migration.
+    pub script: String,
+    /// `-- TODO` lines in the written migration.
+    pub todos: usize,
+}
+
+/// A revision or changeSet converted to SQL, not yet numbered.
+#[derive(Debug, Default)]
+struct Converted {
+    source_id: String,
+    origin: PathBuf,
+    description: String,
+    /// `-- waypoint:env` values carried over from the source.
+    env: Vec<String>,
+    /// Re-applied whenever it changes (Liquibase `runOnChange`).
+    repeatable: bool,
+    statements: Vec<String>,
+    todos: usize,
+}
+
+impl Converted {
+    fn push_sql(&mut self, sql: &str) {
+        let sql = sql.trim();
+        if sql.is_empty() {
+            return;
+        }
+        if sql.ends_with(';') {
+            self.statements.push(sql.to_string());
+        } else {
+            self.statements.push(format!("{};", sql));
+        }
+    }
+
+    fn push_todo(&mut self, what: &str) {
+        let preview: String = what
+            .split_whitespace()
+            .collect::<Vec<_>>()
+            .join(" ")
+            .chars()
+            .take(100)
+            .collect();
+        self.statements
+            .push(format!("-- TODO: convert manually: {}", preview));
+        self.todos += 1;
+    }
+}
+
+/// Convert the history at `path` into migration files in `output`, or the
+/// first configured location.
+pub fn execute(
+    config: &WaypointConfig,
+    source: ImportSource,
+    path: &Path,
+    output: Option<&Path>,
+) -> Result<ImportReport> {
+    let output = match output {
+        Some(dir) => dir.to_path_buf(),
+        None => config
+            .migrations
+            .locations
+            .first()
+            .cloned()
+            .ok_or_else(|| {
+                WaypointError::ConfigError("No migration location configured".to_string())
+            })?,
+    };
+
+    let converted = match source {
+        ImportSource::Alembic => alembic::convert(path)?,
+        ImportSource::Liquibase => liquibase::convert(path, config.dialect_kind())?,
+    };
+    if converted.is_empty() {
+        return Err(WaypointError::MigrationParseError(format!(
+            "No {} migrations found in '{}'",
+            source,
+            path.display()
+        )));
+    }
+
+    std::fs::create_dir_all(&output)?;
+    let first_version = scan_migrations(std::slice::from_ref(&output))?
+        .iter()
+        .filter_map(|m| m.version())
+        .max()
+        .and_then(|v| v.segments.first().copied())
+        .unwrap_or(0)
+        + 1;
+
+    // Check every name first so a clash leaves no partial import behind.
+    let mut planned: Vec<(Option<String>, String)> = Vec::with_capacity(converted.len());
+    let mut next_version = first_version;
+    for c in &converted {
+        let (version, script) = if c.repeatable {
+            let description = super::new::file_description(&c.description)
+                .unwrap_or_else(|_| format!("Imported_{}", planned.len() + 1));
+            (None, format!("R__{}.sql", description))
+        } else {
+            let version = next_version.to_string();
+            next_version += 1;
+            let description = super::new::file_description(&c.description)
+                .unwrap_or_else(|_| format!("Imported_{}", version));
+            let script = format!("V{}__{}.sql", version, description);
+            (Some(version), script)
+        };
+        if output.join(&script).exists() || planned.iter().any(|(_, s)| *s == script) {
+            return Err(WaypointError::IoError(std::io::Error::new(
+                std::io::ErrorKind::AlreadyExists,
+                format!("'{}' already exists", output.join(&script).display()),
+            )));
+        }
+        planned.push((version, script));
+    }
+
+    let mut migrations = Vec::with_capacity(converted.len());
+    for (c, (version, script)) in converted.iter().zip(planned) {
+        super::new::create_file(&output.join(&script), &render_file(source, c))?;
+        migrations.push(ImportedMigration {
+            source_id: c.source_id.clone(),
+            origin: c.origin.display().to_string(),
+            version,
+            script,
+            todos: c.todos,
+        });
+    }
+
+    let report = ImportReport {
+        report_version: crate::REPORT_VERSION,
+        source,
+        output: output.display().to_string(),
+        todo_count: migrations.iter().map(|m| m.todos).sum(),
+        migrations,
+    };
+    let mapping = serde_json::to_string_pretty(&report).map_err(|e| {
+        WaypointError::IoError(std::io::Error::new(std::io::ErrorKind::InvalidData, e))
+    })?;
+    std::fs::write(output.join(MAPPING_FILE), mapping + "\n")?;
+    Ok(report)
+}
+
+/// File contents for one converted migration.
+fn render_file(source: ImportSource, c: &Converted) -> String {
+    let mut out = format!(
+        "-- Imported from {} {} ({})\n",
+        source,
+        c.source_id,
+        c.origin.display()
+    );
+    if !c.env.is_empty() {
+        out.push_str(&format!("-- waypoint:env {}\n", c.env.join(",")));
+    }
+    out.push('\n');
+    if c.statements.is_empty() {
+        out.push_str("-- No schema changes.\n");
+    }
+    for stmt in &c.statements {
+        out.push_str(stmt);
+        out.push_str("\n\n");
+    }
+    out.truncate(out.trim_end().len());
+    out.push('\n');
+    out
+}
+
+/// Read a file, naming it in the error.
+fn read_source(path: &Path) -> Result<String> {
+    std::fs::read_to_string(path).map_err(|e| {
+        WaypointError::IoError(std::io::Error::new(
+            e.kind(),
+            format!("Failed to read '{}': {}", path.display(), e),
+        ))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fixture(path: &str) -> PathBuf {
+        Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("tests/fixtures/import")
+            .join(path)
+    }
+
+    fn import(
+        config: &WaypointConfig,
+        source: ImportSource,
+        path: &str,
+    ) -> (ImportReport, tempfile::TempDir) {
+        let out = tempfile::tempdir().unwrap();
+        let report = execute(config, source, &fixture(path), Some(out.path())).unwrap();
+        (report, out)
+    }
+
+    fn scripts(report: &ImportReport) -> Vec<&str> {
+        report
+            .migrations
+            .iter()
+            .map(|m| m.script.as_str())
+            .collect()
+    }
+
+    #[test]
+    fn test_liquibase_yaml_block_scalars_and_run_on_change() {
+        let (report, out) = import(
+            &WaypointConfig::default(),
+            ImportSource::Liquibase,
+            "liquibase/changelog.yaml",
+        );
+        assert_eq!(
+            scripts(&report),
+            vec![
+                "V1__create_users.sql",
+                "V2__pg_extension.sql",
+                "R__active_users_view.sql"
+            ]
+        );
+        assert_eq!(report.migrations[2].version, None);
+
+        // Blank and `#` lines inside a `|` block are part of the SQL.
+        let users = std::fs::read_to_string(out.path().join("V1__create_users.sql")).unwrap();
+        assert!(
+            users.contains(
+                "CREATE TABLE users (\n  id INT\n);\n\n# kept: a comment line inside the block\nCREATE INDEX"
+            ),
+            "{}",
+            users
+        );
+        // A `>` block folds into one line.
+        let view = std::fs::read_to_string(out.path().join("R__active_users_view.sql")).unwrap();
+        assert!(
+            view.contains("CREATE OR REPLACE VIEW active_users AS SELECT id FROM users;"),
+            "{}",
+            view
+        );
+        assert_eq!(report.todo_count, 1);
+    }
+
+    #[test]
+    fn test_liquibase_dbms_follows_the_configured_database() {
+        let mut config = WaypointConfig::default();
+        config.database.url = Some("mysql://app@localhost/app".to_string());
+        let (report, _out) = import(&config, ImportSource::Liquibase, "liquibase/changelog.yaml");
+        assert_eq!(
+            scripts(&report),
+            vec![
+                "V1__create_users.sql",
+                "V2__mysql_engine.sql",
+                "V3__not_on_postgres.sql",
+                "R__active_users_view.sql"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_alembic_follows_down_revision_chain() {
+        let (report, out) = import(&WaypointConfig::default(), ImportSource::Alembic, "alembic");
+        let ids: Vec<&str> = report
+            .migrations
+            .iter()
+            .map(|m| m.source_id.as_str())
+            .collect();
+        // File order is bb22, aa11, cc33; the chain is aa11 <- bb22 <- cc33.
+        assert_eq!(ids, vec!["aa11", "bb22", "cc33"]);
+        assert_eq!(
+            scripts(&report),
+            vec![
+                "V1__create_users.sql",
+                "V2__add_email.sql",
+                "V3__index_email.sql"
+            ]
+        );
+        let index = std::fs::read_to_string(out.path().join("V3__index_email.sql")).unwrap();
+        assert!(
+            index.contains("CREATE INDEX users_email_idx ON users (email);"),
+            "{}",
+            index
+        );
+        assert!(out.path().join(MAPPING_FILE).exists());
+    }
+}
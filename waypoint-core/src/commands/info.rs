@@ -3,7 +3,7 @@
 use std::collections::HashMap;
 
 use chrono::{DateTime, Utc};
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 
 #[cfg(feature = "postgres")]
 use tokio_postgres::Client;
@@ -77,6 +77,68 @@ pub struct MigrationInfo {
     pub checksum: Option<i32>,
 }
 
+/// The most recent successful entry in the schema history, for freshness
+/// monitoring (`waypoint info --last`).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LastApplied {
+    /// Version, or `None` for a repeatable migration.
+    pub version: Option<String>,
+    /// Description of the migration.
+    pub description: String,
+    /// Filename of the migration script.
+    pub script: String,
+    /// History type, e.g. `"SQL"`, `"SQL_REPEATABLE"` or `"UNDO_SQL"`.
+    pub migration_type: String,
+    /// When it was applied.
+    pub installed_on: DateTime<Utc>,
+    /// Database user or custom identifier that applied it.
+    pub installed_by: String,
+}
+
+/// Report produced by `waypoint info --last`.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct LastAppliedReport {
+    /// Report format version; see [`crate::REPORT_VERSION`].
+    #[serde(default)]
+    pub report_version: u32,
+    /// `None` when nothing has been applied yet.
+    pub last_applied: Option<LastApplied>,
+}
+
+/// Read the most recent successful history entry, without scanning the
+/// migration files. `None` when the history is absent or has no successful
+/// entry.
+pub async fn last_applied_db(
+    client: &DbClient,
+    config: &WaypointConfig,
+) -> Result<Option<LastApplied>> {
+    let schema = client.resolve_schema(&config.migrations.schema).await?;
+    let table = &config.migrations.table;
+
+    if !history::history_table_exists_db(client, &schema, table).await? {
+        return Ok(None);
+    }
+    history::verify_history_meta_db(client, &schema, table).await?;
+    let applied = history::get_applied_migrations_db(client, &schema, table).await?;
+    Ok(latest_success(applied))
+}
+
+/// The successful row with the highest `installed_rank`.
+fn latest_success(applied: Vec<AppliedMigration>) -> Option<LastApplied> {
+    applied
+        .into_iter()
+        .filter(|am| am.success)
+        .max_by_key(|am| am.installed_rank)
+        .map(|am| LastApplied {
+            version: am.version,
+            description: am.description,
+            script: am.script,
+            migration_type: am.migration_type,
+            installed_on: am.installed_on,
+            installed_by: am.installed_by,
+        })
+}
+
 /// Execute the info command (PostgreSQL legacy entry).
 #[cfg(feature = "postgres")]
 pub async fn execute(client: &Client, config: &WaypointConfig) -> Result<Vec<MigrationInfo>> {
@@ -87,6 +149,7 @@ pub async fn execute(client: &Client, config: &WaypointConfig) -> Result<Vec<Mig
         let resolved = scan_migrations(&config.migrations.locations)?;
         return Ok(pending_only(resolved));
     }
+    history::verify_history_meta(client, schema, table).await?;
     let applied = history::get_applied_migrations(client, schema, table).await?;
     let resolved = scan_migrations(&config.migrations.locations)?;
     Ok(merge(applied, resolved))
@@ -102,6 +165,7 @@ pub async fn execute_db(client: &DbClient, config: &WaypointConfig) -> Result<Ve
         let resolved = scan_migrations(&config.migrations.locations)?;
         return Ok(pending_only(resolved));
     }
+    history::verify_history_meta_db(client, schema, table).await?;
     let applied = history::get_applied_migrations_db(client, schema, table).await?;
     let resolved = scan_migrations(&config.migrations.locations)?;
     Ok(merge(applied, resolved))
@@ -5,14 +5,14 @@
 
 use std::path::PathBuf;
 
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 
 use crate::error::Result;
 use crate::migration::scan_migrations;
 use crate::sql_parser::{extract_ddl_operations, split_statements, DdlOperation};
 
 /// Severity level for a lint issue.
-#[derive(Debug, Clone, Serialize, PartialEq, Eq, PartialOrd, Ord)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord)]
 pub enum LintSeverity {
     /// A critical issue that will likely cause migration failure.
     Error,
@@ -33,7 +33,7 @@ impl std::fmt::Display for LintSeverity {
 }
 
 /// A single lint finding.
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct LintIssue {
     /// Unique identifier of the lint rule (e.g. "W001", "E001").
     pub rule_id: String,
@@ -50,8 +50,11 @@ pub struct LintIssue {
 }
 
 /// Aggregate lint report.
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct LintReport {
+    /// Report format version; see [`crate::REPORT_VERSION`].
+    #[serde(default)]
+    pub report_version: u32,
     /// All lint issues found across checked files.
     pub issues: Vec<LintIssue>,
     /// Total number of migration files that were checked.
@@ -312,6 +315,7 @@ pub fn execute(locations: &[PathBuf], disabled_rules: &[String]) -> Result<LintR
         .count();
 
     Ok(LintReport {
+        report_version: crate::REPORT_VERSION,
         issues,
         files_checked,
         error_count,
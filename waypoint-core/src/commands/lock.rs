@@ -0,0 +1,440 @@
+//! Inspect and forcibly release the migration lock (`lock status` / `lock release`).
+//!
+//! The lock is session-scoped on both engines (a PostgreSQL advisory lock or a
+//! MySQL named lock), so it can only be released by ending the session that
+//! holds it. After a crashed run that session is usually an idle connection
+//! left behind by a pooler or a hung process.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+#[cfg(feature = "postgres")]
+use tokio_postgres::Client;
+
+use crate::config::WaypointConfig;
+use crate::db::DbClient;
+use crate::dialect::DialectKind;
+use crate::error::{Result, WaypointError};
+use crate::logging;
+
+/// State of the migration lock for the configured history table.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct LockReport {
+    /// Report format version; see [`crate::REPORT_VERSION`].
+    #[serde(default)]
+    pub report_version: u32,
+    /// History table the lock is keyed on.
+    pub table: String,
+    /// Advisory lock id (PostgreSQL) or lock name (MySQL).
+    pub lock_key: String,
+    /// Sessions holding or waiting for the lock, holders first.
+    pub holders: Vec<LockHolder>,
+    /// Sessions terminated by `lock release --force`.
+    #[serde(default)]
+    pub terminated: Vec<i64>,
+}
+
+impl LockReport {
+    /// Whether any session currently holds the lock.
+    pub fn is_held(&self) -> bool {
+        self.holders.iter().any(|h| h.granted)
+    }
+}
+
+/// A session holding or waiting for the migration lock.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LockHolder {
+    /// Backend PID (PostgreSQL) or connection id (MySQL).
+    pub pid: i64,
+    /// Whether the session holds the lock, as opposed to waiting for it.
+    pub granted: bool,
+    /// `application_name` of the session, if set (PostgreSQL).
+    pub application_name: Option<String>,
+    /// Database user of the session.
+    pub user: Option<String>,
+    /// Client address or host of the session.
+    pub client_addr: Option<String>,
+    /// When the session connected (PostgreSQL).
+    pub backend_start: Option<DateTime<Utc>>,
+    /// Session state, e.g. `idle` or `active`.
+    pub state: Option<String>,
+    /// Seconds the session has been idle; `None` while it is running a query.
+    #[serde(default)]
+    pub idle_secs: Option<i64>,
+    /// Last (or current) query of the session.
+    pub query: Option<String>,
+}
+
+/// Report who holds the migration lock (dialect-aware entry).
+pub async fn status_db(client: &DbClient, config: &WaypointConfig) -> Result<LockReport> {
+    let table = &config.migrations.table;
+    let name = client.lock_name(&config.migrations).await?;
+    match client.dialect_kind() {
+        #[cfg(feature = "postgres")]
+        DialectKind::Postgres => status_pg(client.as_postgres()?, table, &name).await,
+        #[cfg(not(feature = "postgres"))]
+        DialectKind::Postgres => Err(WaypointError::ConfigError(
+            "PostgreSQL support is not compiled in (enable the `postgres` feature)".into(),
+        )),
+        #[cfg(feature = "mysql")]
+        DialectKind::Mysql => status_mysql(client, table, &name).await,
+        #[cfg(not(feature = "mysql"))]
+        DialectKind::Mysql => Err(WaypointError::ConfigError(
+            "MySQL support is not compiled in (enable the `mysql` feature)".into(),
+        )),
+    }
+}
+
+/// Release the migration lock by terminating the session holding it.
+///
+/// Without `force` this only succeeds when the lock is free; a held lock is
+/// reported as a [`WaypointError::LockError`] naming the holder.
+pub async fn release_db(
+    client: &DbClient,
+    config: &WaypointConfig,
+    force: bool,
+) -> Result<LockReport> {
+    let mut report = status_db(client, config).await?;
+    let holders: Vec<LockHolder> = report
+        .holders
+        .iter()
+        .filter(|h| h.granted)
+        .cloned()
+        .collect();
+    if holders.is_empty() {
+        log::info!(target: logging::LOCK, "Migration lock is not held; table={}", report.table);
+        return Ok(report);
+    }
+    if !force {
+        return Err(WaypointError::LockError(format!(
+            "Migration lock is held by {}; re-run with --force to terminate the session",
+            describe(&holders[0])
+        )));
+    }
+
+    for holder in &holders {
+        log::warn!(target: logging::LOCK,
+            "Terminating session holding the migration lock; pid={}, application_name={}, table={}",
+            holder.pid,
+            holder.application_name.as_deref().unwrap_or(""),
+            report.table
+        );
+        terminate(client, holder.pid).await?;
+        report.terminated.push(holder.pid);
+    }
+    Ok(report)
+}
+
+fn describe(holder: &LockHolder) -> String {
+    let mut s = format!("pid {}", holder.pid);
+    if let Some(app) = holder.application_name.as_deref().filter(|a| !a.is_empty()) {
+        s.push_str(&format!(" ({})", app));
+    }
+    if let Some(addr) = holder.client_addr.as_deref() {
+        s.push_str(&format!(" from {}", addr));
+    }
+    if let Some(idle) = holder.idle_secs {
+        s.push_str(&format!(", idle for {}", format_idle(idle)));
+    }
+    if let Some(query) = holder
+        .query
+        .as_deref()
+        .map(str::trim)
+        .filter(|q| !q.is_empty())
+    {
+        let preview: String = query.chars().take(120).collect();
+        let ellipsis = if preview.len() < query.len() {
+            "..."
+        } else {
+            ""
+        };
+        s.push_str(&format!(", last query: {}{}", preview, ellipsis));
+    }
+    s
+}
+
+fn format_idle(secs: i64) -> String {
+    match secs {
+        s if s >= 3600 => format!("{}h{}m", s / 3600, (s % 3600) / 60),
+        s if s >= 60 => format!("{}m", s / 60),
+        s => format!("{}s", s),
+    }
+}
+
+/// The granted holder idle for at least `stale_lock_minutes`, if any.
+fn stale_holder<'a>(report: &'a LockReport, config: &WaypointConfig) -> Option<&'a LockHolder> {
+    let minutes = config.migrations.stale_lock_minutes;
+    if minutes == 0 {
+        return None;
+    }
+    report.holders.iter().find(|h| {
+        h.granted
+            && h.idle_secs
+                .is_some_and(|idle| idle >= i64::from(minutes) * 60)
+    })
+}
+
+/// Decide what to do about a lock held by someone else: fail with a
+/// diagnosis when the holder is stale, or return its pid to terminate when
+/// `steal_lock` is set. `None` means wait for the holder as usual.
+fn stale_lock_decision(report: &LockReport, config: &WaypointConfig) -> Result<Option<i64>> {
+    let Some(holder) = stale_holder(report, config) else {
+        if let Some(holder) = report.holders.iter().find(|h| h.granted) {
+            log::info!(target: logging::LOCK, "Waiting for migration lock; holder={}", describe(holder));
+        }
+        return Ok(None);
+    };
+    if !config.migrations.steal_lock {
+        return Err(WaypointError::LockError(format!(
+            "Migration lock is held by {}. The holder has been idle longer than \
+             stale_lock_minutes ({}) and is probably left over from a crashed run; \
+             release it with 'waypoint lock release --force' or re-run with --steal-lock",
+            describe(holder),
+            config.migrations.stale_lock_minutes
+        )));
+    }
+    log::warn!(target: logging::LOCK,
+        "Stealing migration lock from stale session; holder={}",
+        describe(holder)
+    );
+    Ok(Some(holder.pid))
+}
+
+/// Acquire the migration lock for migrate (PostgreSQL), returning the lock
+/// name to release it by.
+///
+/// Waits for an active holder as before, but a holder idle for longer than
+/// `stale_lock_minutes` is diagnosed in the error, or terminated when
+/// `steal_lock` is set.
+#[cfg(feature = "postgres")]
+pub(crate) async fn acquire_for_migrate(
+    client: &Client,
+    config: &WaypointConfig,
+) -> Result<String> {
+    let table = &config.migrations.table;
+    let name = crate::db::pg_lock_name(&config.migrations)?;
+    if config.migrations.stale_lock_minutes > 0 {
+        let lock_id = crate::db::advisory_lock_id(&name);
+        let row = client
+            .query_one("SELECT pg_try_advisory_lock($1)", &[&lock_id])
+            .await
+            .map_err(|e| WaypointError::LockError(format!("Failed to try advisory lock: {}", e)))?;
+        if row.get::<_, bool>(0) {
+            return Ok(name);
+        }
+        let report = status_pg(client, table, &name).await?;
+        if let Some(pid) = stale_lock_decision(&report, config)? {
+            terminate_pg(client, pid).await?;
+        }
+    }
+    crate::db::acquire_advisory_lock(client, &name).await?;
+    Ok(name)
+}
+
+/// Acquire the migration lock for migrate (MySQL); see [`acquire_for_migrate`].
+#[cfg(feature = "mysql")]
+pub(crate) async fn acquire_for_migrate_mysql(
+    client: &DbClient,
+    config: &WaypointConfig,
+) -> Result<String> {
+    let table = &config.migrations.table;
+    let name = client.lock_name(&config.migrations).await?;
+    if config.migrations.stale_lock_minutes > 0 {
+        if client.acquire_lock_with_timeout(&name, 0).await.is_ok() {
+            return Ok(name);
+        }
+        let report = status_mysql(client, table, &name).await?;
+        if let Some(pid) = stale_lock_decision(&report, config)? {
+            terminate(client, pid).await?;
+        }
+    }
+    client.acquire_lock(&name).await?;
+    Ok(name)
+}
+
+async fn terminate(client: &DbClient, pid: i64) -> Result<()> {
+    match client.dialect_kind() {
+        #[cfg(feature = "postgres")]
+        DialectKind::Postgres => terminate_pg(client.as_postgres()?, pid).await,
+        #[cfg(feature = "mysql")]
+        DialectKind::Mysql => {
+            use mysql_async::prelude::*;
+            let mut conn = client.as_mysql()?.get_conn().await?;
+            conn.query_drop(format!("KILL {}", pid)).await?;
+            Ok(())
+        }
+        #[cfg(not(feature = "postgres"))]
+        DialectKind::Postgres => Err(WaypointError::ConfigError(
+            "PostgreSQL support is not compiled in (enable the `postgres` feature)".into(),
+        )),
+        #[cfg(not(feature = "mysql"))]
+        DialectKind::Mysql => Err(WaypointError::ConfigError(
+            "MySQL support is not compiled in (enable the `mysql` feature)".into(),
+        )),
+    }
+}
+
+#[cfg(feature = "postgres")]
+async fn terminate_pg(client: &Client, pid: i64) -> Result<()> {
+    let pid = i32::try_from(pid)
+        .map_err(|_| WaypointError::LockError(format!("Invalid backend pid {}", pid)))?;
+    let row = client
+        .query_one("SELECT pg_terminate_backend($1)", &[&pid])
+        .await?;
+    if !row.get::<_, bool>(0) {
+        return Err(WaypointError::LockError(format!(
+            "Could not terminate backend {} (it may have already exited)",
+            pid
+        )));
+    }
+    Ok(())
+}
+
+#[cfg(feature = "postgres")]
+async fn status_pg(client: &Client, table: &str, name: &str) -> Result<LockReport> {
+    let lock_id = crate::db::advisory_lock_id(name);
+    // A bigint advisory key is split across classid (high 32 bits) and objid
+    // (low 32 bits), with objsubid = 1.
+    let rows = client
+        .query(
+            "SELECT l.pid::bigint, l.granted, a.application_name, a.usename::text, \
+                    host(a.client_addr), a.backend_start, a.state, a.query, \
+                    CASE WHEN a.state LIKE 'idle%' \
+                         THEN extract(epoch FROM now() - a.state_change)::bigint END \
+             FROM pg_locks l LEFT JOIN pg_stat_activity a ON a.pid = l.pid \
+             WHERE l.locktype = 'advisory' AND l.objsubid = 1 \
+               AND l.database = (SELECT oid FROM pg_database WHERE datname = current_database()) \
+               AND l.classid = ($1::bigint >> 32)::oid \
+               AND l.objid = ($1::bigint & 4294967295)::oid \
+             ORDER BY l.granted DESC, l.pid",
+            &[&lock_id],
+        )
+        .await?;
+    Ok(LockReport {
+        report_version: crate::REPORT_VERSION,
+        table: table.to_string(),
+        lock_key: lock_id.to_string(),
+        holders: rows
+            .iter()
+            .map(|r| LockHolder {
+                pid: r.get(0),
+                granted: r.get(1),
+                application_name: r.get(2),
+                user: r.get(3),
+                client_addr: r.get(4),
+                backend_start: r.get(5),
+                state: r.get(6),
+                query: r.get(7),
+                idle_secs: r.get(8),
+            })
+            .collect(),
+        terminated: Vec::new(),
+    })
+}
+
+/// MySQL only exposes the holder of a named lock (`IS_USED_LOCK`), not the
+/// sessions waiting for it.
+#[cfg(feature = "mysql")]
+async fn status_mysql(client: &DbClient, table: &str, name: &str) -> Result<LockReport> {
+    use mysql_async::prelude::*;
+
+    let key = crate::db::mysql_lock_key(name);
+    let mut conn = client.as_mysql()?.get_conn().await?;
+    let holder: Option<Option<u64>> = conn.exec_first("SELECT IS_USED_LOCK(?)", (&key,)).await?;
+    let mut holders = Vec::new();
+    if let Some(id) = holder.flatten() {
+        let row: Option<mysql_async::Row> = conn
+            .exec_first(
+                "SELECT USER, HOST, COMMAND, INFO, CAST(IF(COMMAND = 'Sleep', TIME, NULL) AS CHAR) \
+                 FROM information_schema.PROCESSLIST WHERE ID = ?",
+                (id,),
+            )
+            .await?;
+        let column = |i: usize| {
+            row.as_ref()
+                .and_then(|r| r.get_opt::<Option<String>, _>(i)?.ok()?)
+        };
+        let (user, host, command, info) = (column(0), column(1), column(2), column(3));
+        let idle_secs = column(4).and_then(|t| t.parse().ok());
+        holders.push(LockHolder {
+            pid: id as i64,
+            granted: true,
+            application_name: None,
+            user,
+            client_addr: host,
+            backend_start: None,
+            state: command,
+            query: info,
+            idle_secs,
+        });
+    }
+    Ok(LockReport {
+        report_version: crate::REPORT_VERSION,
+        table: table.to_string(),
+        lock_key: key,
+        holders,
+        terminated: Vec::new(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn holder(pid: i64, granted: bool) -> LockHolder {
+        LockHolder {
+            pid,
+            granted,
+            application_name: Some("waypoint".into()),
+            user: None,
+            client_addr: Some("10.0.0.5".into()),
+            backend_start: None,
+            state: Some("idle".into()),
+            query: None,
+            idle_secs: Some(1800),
+        }
+    }
+
+    #[test]
+    fn test_is_held_ignores_waiters() {
+        let mut report = LockReport {
+            report_version: crate::REPORT_VERSION,
+            table: "waypoint_schema_history".into(),
+            lock_key: "1".into(),
+            holders: vec![holder(42, false)],
+            terminated: Vec::new(),
+        };
+        assert!(!report.is_held());
+        report.holders.insert(0, holder(7, true));
+        assert!(report.is_held());
+        assert_eq!(
+            describe(&report.holders[0]),
+            "pid 7 (waypoint) from 10.0.0.5, idle for 30m"
+        );
+    }
+
+    #[test]
+    fn test_stale_holder_threshold() {
+        let report = LockReport {
+            report_version: crate::REPORT_VERSION,
+            table: "waypoint_schema_history".into(),
+            lock_key: "1".into(),
+            holders: vec![holder(7, true)],
+            terminated: Vec::new(),
+        };
+        let mut config = WaypointConfig::default();
+        config.migrations.stale_lock_minutes = 30;
+        assert_eq!(stale_holder(&report, &config).map(|h| h.pid), Some(7));
+        config.migrations.stale_lock_minutes = 31;
+        assert!(stale_holder(&report, &config).is_none());
+        config.migrations.stale_lock_minutes = 0;
+        assert!(stale_holder(&report, &config).is_none());
+
+        config.migrations.stale_lock_minutes = 10;
+        let err = stale_lock_decision(&report, &config).unwrap_err();
+        assert!(err.to_string().contains("--steal-lock"), "{}", err);
+        config.migrations.steal_lock = true;
+        assert_eq!(stale_lock_decision(&report, &config).unwrap(), Some(7));
+        assert_eq!(format_idle(3725), "1h2m");
+    }
+}
@@ -8,10 +8,19 @@
 //! downstream callers (and the library `Waypoint` façade) can keep using
 //! the historical paths under `crate::commands::migrate::*`.
 
-use serde::Serialize;
+use std::collections::HashMap;
 
+use serde::{Deserialize, Serialize};
+
+use crate::ddl_capture::CapturedDdl;
 use crate::directive::MigrationDirectives;
-use crate::error::WaypointError;
+use crate::error::{Result, WaypointError};
+use crate::logging;
+use crate::migration::{MigrationVersion, ResolvedMigration};
+use crate::ownership::OwnershipTransfer;
+use crate::placeholder::replace_placeholders;
+use crate::post_check::{PostCheckResult, PostCheckRollback};
+use crate::table_stats::TableSizeChange;
 
 // ── Re-exports of the engine-specific entry points ──────────────────────────
 //
@@ -28,8 +37,11 @@ pub use crate::engines::postgres::migrate::{execute, execute_with_options};
 // ── Engine-agnostic public types ────────────────────────────────────────────
 
 /// Report returned after a migrate operation.
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct MigrateReport {
+    /// Report format version; see [`crate::REPORT_VERSION`].
+    #[serde(default)]
+    pub report_version: u32,
     /// Number of migrations that were applied in this run.
     pub migrations_applied: usize,
     /// Total execution time of all migrations in milliseconds.
@@ -40,10 +52,70 @@ pub struct MigrateReport {
     pub hooks_executed: usize,
     /// Total execution time of all hooks in milliseconds.
     pub hooks_time_ms: i32,
+    /// Results of `[[post_checks]]` run after the migrations succeeded.
+    #[serde(default)]
+    pub post_checks: Vec<PostCheckResult>,
+    /// Undo of this run after failed post-checks, when
+    /// `rollback_on_post_check_failure` is enabled and anything was applied.
+    pub post_check_rollback: Option<PostCheckRollback>,
+    /// Size changes of the tables this run touched, when
+    /// `collect_table_sizes` is enabled.
+    #[serde(default)]
+    pub table_sizes: Vec<TableSizeChange>,
+    /// DDL recorded by event triggers during the run, when `capture_ddl`
+    /// is enabled (PostgreSQL).
+    #[serde(default)]
+    pub captured_ddl: Vec<CapturedDdl>,
+    /// Objects handed to `transfer_ownership_to` after the run.
+    #[serde(default)]
+    pub ownership_transfer: Option<OwnershipTransfer>,
+    /// Non-fatal findings from `validate_on_migrate`, such as applied
+    /// migrations whose files are no longer on disk.
+    #[serde(default)]
+    pub validation_warnings: Vec<String>,
+    /// Whether each repeatable migration was applied this run, and why.
+    #[serde(default)]
+    pub repeatables: Vec<RepeatableStatus>,
+}
+
+impl MigrateReport {
+    /// Return [`WaypointError::PostCheckFailed`] if any post-check failed.
+    ///
+    /// Migrate itself returns `Ok` when post-checks fail, since the
+    /// migrations were committed (and possibly rolled back since); callers
+    /// that gate a deploy on the checks should call this.
+    pub fn ensure_post_checks_passed(&self) -> Result<()> {
+        let failed: Vec<_> = self.post_checks.iter().filter(|c| !c.passed).collect();
+        if failed.is_empty() {
+            return Ok(());
+        }
+        let mut details = failed
+            .iter()
+            .map(|c| format!("{}: {}", c.name, c.detail))
+            .collect::<Vec<_>>()
+            .join("; ");
+        if let Some(rollback) = &self.post_check_rollback {
+            match &rollback.error {
+                None => details.push_str(&format!(
+                    "; rolled back version(s) {}",
+                    rollback.versions_undone.join(", ")
+                )),
+                Some(e) => details.push_str(&format!("; rollback failed: {}", e)),
+            }
+        }
+        Err(WaypointError::PostCheckFailed {
+            count: failed.len(),
+            details,
+            rolled_back: self
+                .post_check_rollback
+                .as_ref()
+                .is_some_and(PostCheckRollback::succeeded),
+        })
+    }
 }
 
 /// Details of a single applied migration within a migrate run.
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct MigrateDetail {
     /// Version string, or None for repeatable migrations.
     pub version: Option<String>,
@@ -53,10 +125,165 @@ pub struct MigrateDetail {
     pub script: String,
     /// Execution time of this migration in milliseconds.
     pub execution_time_ms: i32,
+    /// Tables and views the migration creates, alters, drops or writes to.
+    #[serde(default)]
+    pub touched_tables: Vec<String>,
+}
+
+/// Apply decision for one repeatable migration.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RepeatableStatus {
+    /// Filename of the repeatable migration.
+    pub script: String,
+    /// Why it was (or was not) applied.
+    pub reason: RepeatableReason,
+}
+
+impl RepeatableStatus {
+    /// Whether the migration is applied (new or changed).
+    pub fn applies(&self) -> bool {
+        matches!(
+            self.reason,
+            RepeatableReason::New { .. } | RepeatableReason::Changed { .. }
+        )
+    }
+}
+
+/// Why a repeatable migration is applied or skipped.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum RepeatableReason {
+    /// Never applied before.
+    New { checksum: i32 },
+    /// The file changed since it was last applied.
+    Changed {
+        previous_checksum: Option<i32>,
+        checksum: i32,
+    },
+    /// Same checksum as the last application; skipped.
+    Unchanged { checksum: i32 },
+    /// New or changed, but held back by `waypoint:requires-version` until
+    /// that version has been applied.
+    Deferred {
+        requires_version: String,
+        checksum: i32,
+    },
+}
+
+impl std::fmt::Display for RepeatableReason {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RepeatableReason::New { .. } => write!(f, "new"),
+            RepeatableReason::Changed {
+                previous_checksum: Some(previous),
+                checksum,
+            } => write!(f, "changed (checksum {} -> {})", previous, checksum),
+            RepeatableReason::Changed {
+                previous_checksum: None,
+                ..
+            } => write!(f, "changed (no previous checksum)"),
+            RepeatableReason::Unchanged { .. } => write!(f, "unchanged"),
+            RepeatableReason::Deferred {
+                requires_version, ..
+            } => write!(f, "deferred (requires version {})", requires_version),
+        }
+    }
 }
 
 // ── Shared helpers used by both engine paths ────────────────────────────────
 
+/// Decide whether each repeatable migration needs (re-)applying, given the
+/// checksum of each script's latest successful application.
+///
+/// `available` holds the versions that are applied or about to be applied
+/// by the run; a `waypoint:requires-version` on anything else (and not
+/// covered by `baseline`) defers the migration.
+pub(crate) fn repeatable_statuses<'a>(
+    repeatables: impl IntoIterator<Item = &'a ResolvedMigration>,
+    applied_scripts: &HashMap<String, Option<i32>>,
+    available: &[MigrationVersion],
+    baseline: Option<&MigrationVersion>,
+) -> Result<Vec<RepeatableStatus>> {
+    repeatables
+        .into_iter()
+        .map(|m| {
+            let reason = match applied_scripts.get(&m.script) {
+                None => RepeatableReason::New {
+                    checksum: m.checksum,
+                },
+                Some(&previous) if previous == Some(m.checksum) => RepeatableReason::Unchanged {
+                    checksum: m.checksum,
+                },
+                Some(&previous) => RepeatableReason::Changed {
+                    previous_checksum: previous,
+                    checksum: m.checksum,
+                },
+            };
+            let reason = match &m.directives.requires_version {
+                Some(raw) if !matches!(reason, RepeatableReason::Unchanged { .. }) => {
+                    let required = crate::migration::requires_version(&m.script, raw)?;
+                    if available.contains(&required) || baseline.is_some_and(|b| &required <= b) {
+                        reason
+                    } else {
+                        RepeatableReason::Deferred {
+                            requires_version: raw.clone(),
+                            checksum: m.checksum,
+                        }
+                    }
+                }
+                _ => reason,
+            };
+            Ok(RepeatableStatus {
+                script: m.script.clone(),
+                reason,
+            })
+        })
+        .collect()
+}
+
+/// The repeatables to apply, in order, logging the decision for each.
+pub(crate) fn pending_repeatables<'a>(
+    repeatables: &[&'a ResolvedMigration],
+    statuses: &[RepeatableStatus],
+) -> Vec<&'a ResolvedMigration> {
+    repeatables
+        .iter()
+        .zip(statuses)
+        .filter_map(|(migration, status)| match &status.reason {
+            RepeatableReason::Unchanged { .. } => None,
+            RepeatableReason::Deferred {
+                requires_version, ..
+            } => {
+                log::info!(target: logging::MIGRATE,
+                    "Deferring repeatable migration; migration={}, requires_version={}",
+                    migration.script,
+                    requires_version
+                );
+                None
+            }
+            reason => {
+                log::info!(target: logging::MIGRATE,
+                    "Applying repeatable migration; migration={}, reason={}",
+                    migration.script,
+                    reason
+                );
+                Some(*migration)
+            }
+        })
+        .collect()
+}
+
+/// Relations a migration touches, parsed after placeholder substitution.
+pub(crate) fn touched_tables(
+    migration: &ResolvedMigration,
+    placeholders: &HashMap<String, String>,
+) -> Vec<String> {
+    match replace_placeholders(&migration.sql, placeholders) {
+        Ok(sql) => crate::sql_parser::touched_tables(&sql),
+        Err(_) => crate::sql_parser::touched_tables(&migration.sql),
+    }
+}
+
 /// Result of evaluating require-guard preconditions for a single migration.
 pub(crate) enum GuardAction {
     /// All preconditions passed; proceed with the migration.
@@ -91,6 +318,100 @@ pub(crate) fn should_run_in_environment(
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_migrate_report_reads_unversioned_json() {
+        // Layout written before `report_version` and the optional sections existed.
+        let json = r#"{
+            "migrations_applied": 1,
+            "total_time_ms": 12,
+            "details": [{
+                "version": "1",
+                "description": "Create users",
+                "script": "V1__Create_users.sql",
+                "execution_time_ms": 12
+            }],
+            "hooks_executed": 0,
+            "hooks_time_ms": 0
+        }"#;
+        let report: MigrateReport = serde_json::from_str(json).unwrap();
+        assert_eq!(report.report_version, 0);
+        assert_eq!(report.details[0].script, "V1__Create_users.sql");
+        assert!(report.details[0].touched_tables.is_empty());
+        assert!(report.post_checks.is_empty());
+        assert!(report.post_check_rollback.is_none());
+
+        let current = serde_json::to_string(&MigrateReport {
+            report_version: crate::REPORT_VERSION,
+            ..report
+        })
+        .unwrap();
+        let reread: MigrateReport = serde_json::from_str(&current).unwrap();
+        assert_eq!(reread.report_version, crate::REPORT_VERSION);
+        assert_eq!(reread.migrations_applied, 1);
+    }
+
+    #[test]
+    fn test_repeatable_statuses_reasons_and_deferral() {
+        let repeatable = |script: &str, checksum: i32| ResolvedMigration {
+            kind: crate::migration::MigrationKind::Repeatable,
+            description: script.to_string(),
+            script: script.to_string(),
+            checksum,
+            sql: String::new(),
+            directives: MigrationDirectives::default(),
+        };
+        let resolved = [
+            repeatable("R__new.sql", 1),
+            repeatable("R__same.sql", 2),
+            repeatable("R__edited.sql", 4),
+            repeatable("R__legacy.sql", 5),
+            ResolvedMigration {
+                directives: MigrationDirectives {
+                    requires_version: Some("3".to_string()),
+                    ..Default::default()
+                },
+                ..repeatable("R__later.sql", 6)
+            },
+            ResolvedMigration {
+                directives: MigrationDirectives {
+                    requires_version: Some("1.5".to_string()),
+                    ..Default::default()
+                },
+                ..repeatable("R__baselined.sql", 7)
+            },
+        ];
+        let applied: HashMap<String, Option<i32>> = [
+            ("R__same.sql".to_string(), Some(2)),
+            ("R__edited.sql".to_string(), Some(3)),
+            ("R__legacy.sql".to_string(), None),
+        ]
+        .into_iter()
+        .collect();
+
+        let available = [MigrationVersion::parse("2").unwrap()];
+        let baseline = MigrationVersion::parse("1.7").unwrap();
+        let statuses =
+            repeatable_statuses(&resolved, &applied, &available, Some(&baseline)).unwrap();
+        let reasons: Vec<String> = statuses.iter().map(|s| s.reason.to_string()).collect();
+        assert_eq!(
+            reasons,
+            vec![
+                "new",
+                "unchanged",
+                "changed (checksum 3 -> 4)",
+                "changed (no previous checksum)",
+                "deferred (requires version 3)",
+                "new"
+            ]
+        );
+        let applies: Vec<bool> = statuses.iter().map(RepeatableStatus::applies).collect();
+        assert_eq!(applies, vec![true, false, true, true, false, true]);
+        assert_eq!(
+            serde_json::to_value(&statuses[2].reason).unwrap(),
+            serde_json::json!({"kind": "changed", "previous_checksum": 3, "checksum": 4})
+        );
+    }
+
     #[test]
     fn test_should_run_in_environment_no_directives() {
         let directives = MigrationDirectives::default();
@@ -1,7 +1,8 @@
 //! Command implementations: migrate, info, validate, repair, baseline, clean,
 //! lint, changelog, diff, drift, snapshot, explain, check-conflicts, safety,
-//! advisor, simulate. The `preflight` command is exposed via
-//! [`crate::preflight::run_preflight_db`] directly (no command-wrapper module).
+//! advisor, simulate, lock, provision-template/clone, verify, new, import. The `preflight` command is
+//! exposed via [`crate::preflight::run_preflight_db`] directly (no
+//! command-wrapper module).
 
 pub mod advisor;
 pub mod baseline;
@@ -11,12 +12,19 @@ pub mod clean;
 pub mod diff;
 pub mod drift;
 pub mod explain;
+pub mod import;
 pub mod info;
 pub mod lint;
+pub mod lock;
 pub mod migrate;
+pub mod new;
 pub mod repair;
 pub mod safety;
 pub mod simulate;
 pub mod snapshot;
+#[cfg(feature = "postgres")]
+pub mod template;
 pub mod undo;
 pub mod validate;
+#[cfg(feature = "postgres")]
+pub mod verify;
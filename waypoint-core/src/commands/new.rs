@@ -0,0 +1,229 @@
+//! Scaffold migration files (`waypoint new`) and write draft undo scripts.
+//!
+//! `new` picks the next version after the highest one on disk and writes
+//! `V{version}__{description}.sql` into the first migration location. With
+//! `--with-undo` it also writes `U{version}__{description}.sql`, drafted by
+//! [`draft_undo`] from the migration's SQL. Existing files are never
+//! overwritten.
+
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::config::WaypointConfig;
+use crate::dialect::DialectKind;
+use crate::error::{Result, WaypointError};
+use crate::migration::{scan_migrations, MigrationVersion, ResolvedMigration};
+use crate::reversal::{draft_undo, UndoDraft};
+
+/// Versions at least this large are taken to be timestamps
+/// (`YYYYMMDDHHMMSS`), and the next one is the current time.
+const TIMESTAMP_VERSION_MIN: u64 = 100_000_000_000;
+
+/// Report produced by `waypoint new`.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct NewReport {
+    /// Report format version; see [`crate::REPORT_VERSION`].
+    #[serde(default)]
+    pub report_version: u32,
+    /// Version given to the new migration.
+    pub version: String,
+    /// Paths of the files written, migration first.
+    pub created: Vec<String>,
+    /// The draft undo, when `--with-undo` was given.
+    pub undo_draft: Option<UndoDraft>,
+}
+
+/// Create the next versioned migration, with `sql` as its body, and
+/// optionally its draft undo in `dialect`'s syntax.
+pub fn execute(
+    config: &WaypointConfig,
+    description: &str,
+    sql: Option<&str>,
+    with_undo: bool,
+    dialect: DialectKind,
+) -> Result<NewReport> {
+    let description = file_description(description)?;
+    let location = config.migrations.locations.first().ok_or_else(|| {
+        WaypointError::ConfigError("No migration location configured".to_string())
+    })?;
+    let resolved = scan_migrations(&config.migrations.locations)?;
+    let version = next_version(&resolved);
+
+    std::fs::create_dir_all(location)?;
+    let script = format!("V{}__{}.sql", version, description);
+    let sql = sql.unwrap_or("");
+    let mut created = vec![create_file(&location.join(&script), sql)?];
+
+    let undo_draft = if with_undo {
+        let draft = draft_undo(sql, dialect);
+        let undo_path = location.join(undo_script_name(&script));
+        created.push(create_file(
+            &undo_path,
+            &undo_file_contents(&script, &draft),
+        )?);
+        Some(draft)
+    } else {
+        None
+    };
+
+    Ok(NewReport {
+        report_version: crate::REPORT_VERSION,
+        version,
+        created: created.iter().map(|p| p.display().to_string()).collect(),
+        undo_draft,
+    })
+}
+
+/// Write the draft undo for the versioned migration `script` next to it.
+///
+/// Returns `None`, writing nothing, when the migration already has an undo
+/// script in any location.
+pub fn write_undo_draft(
+    locations: &[PathBuf],
+    script: &str,
+    draft: &UndoDraft,
+) -> Result<Option<PathBuf>> {
+    let undo_script = undo_script_name(script);
+    if locations.iter().any(|l| l.join(&undo_script).exists()) {
+        return Ok(None);
+    }
+    let location = locations
+        .iter()
+        .find(|l| l.join(script).is_file())
+        .ok_or_else(|| {
+            WaypointError::MigrationParseError(format!(
+                "Migration '{}' not found in any location",
+                script
+            ))
+        })?;
+    create_file(
+        &location.join(undo_script),
+        &undo_file_contents(script, draft),
+    )
+    .map(Some)
+}
+
+/// Contents of a draft `U__` file for `script`.
+pub fn undo_file_contents(script: &str, draft: &UndoDraft) -> String {
+    let mut out = format!(
+        "-- Undo for {}, drafted by waypoint from its DDL.\n\
+         -- Review before committing: dropping tables and columns discards their data.\n",
+        script
+    );
+    if !draft.is_complete() {
+        out.push_str("-- Statements marked TODO or WARNING need a manual undo.\n");
+    }
+    out.push('\n');
+    if draft.sql.is_empty() {
+        out.push_str("-- TODO: reverse the migration here.\n");
+    } else {
+        out.push_str(&draft.sql);
+        out.push('\n');
+    }
+    out
+}
+
+/// `V3__Add_users.sql` → `U3__Add_users.sql`.
+pub fn undo_script_name(script: &str) -> String {
+    format!("U{}", script.strip_prefix('V').unwrap_or(script))
+}
+
+/// One past the highest versioned or undo migration on disk, keeping to
+/// timestamps when the existing versions are timestamps.
+fn next_version(resolved: &[ResolvedMigration]) -> String {
+    let highest: Option<&MigrationVersion> = resolved.iter().filter_map(|m| m.version()).max();
+    match highest.and_then(|v| v.segments.first()) {
+        None => "1".to_string(),
+        Some(&major) if major >= TIMESTAMP_VERSION_MIN => {
+            chrono::Utc::now().format("%Y%m%d%H%M%S").to_string()
+        }
+        Some(&major) => (major + 1).to_string(),
+    }
+}
+
+/// Turn free text into a filename description: words joined by `_`, other
+/// characters dropped.
+pub(crate) fn file_description(description: &str) -> Result<String> {
+    let words: Vec<String> = description
+        .split(|c: char| c.is_whitespace() || c == '_' || c == '-')
+        .map(|w| {
+            w.chars()
+                .filter(|c| c.is_ascii_alphanumeric())
+                .collect::<String>()
+        })
+        .filter(|w| !w.is_empty())
+        .collect();
+    if words.is_empty() {
+        return Err(WaypointError::MigrationParseError(format!(
+            "Migration description '{}' has no letters or digits",
+            description
+        )));
+    }
+    Ok(words.join("_"))
+}
+
+/// Create `path` with `contents`, failing if it already exists.
+pub(crate) fn create_file(path: &Path, contents: &str) -> Result<PathBuf> {
+    let mut file = std::fs::OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .open(path)
+        .map_err(|e| {
+            WaypointError::IoError(std::io::Error::new(
+                e.kind(),
+                format!("Failed to create '{}': {}", path.display(), e),
+            ))
+        })?;
+    file.write_all(contents.as_bytes())?;
+    Ok(path.to_path_buf())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_new_with_undo_writes_draft() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("V7__Init.sql"), "SELECT 1;").unwrap();
+        let mut config = WaypointConfig::default();
+        config.migrations.locations = vec![dir.path().to_path_buf()];
+
+        let report = execute(
+            &config,
+            "add users table",
+            Some("CREATE TABLE users (id INT);\n"),
+            true,
+            DialectKind::Postgres,
+        )
+        .unwrap();
+        assert_eq!(report.version, "8");
+        assert!(report.created[0].ends_with("V8__add_users_table.sql"));
+        let undo = std::fs::read_to_string(dir.path().join("U8__add_users_table.sql")).unwrap();
+        assert!(undo.ends_with("\nDROP TABLE users;\n"), "{}", undo);
+
+        // Drafting again for the same migration leaves the undo alone.
+        let draft = draft_undo("CREATE TABLE other (id INT);", DialectKind::Postgres);
+        assert!(write_undo_draft(
+            &config.migrations.locations,
+            "V8__add_users_table.sql",
+            &draft
+        )
+        .unwrap()
+        .is_none());
+    }
+
+    #[test]
+    fn test_next_version_follows_timestamps() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("V20250101120000__Init.sql"), "").unwrap();
+        let resolved = scan_migrations(&[dir.path().to_path_buf()]).unwrap();
+        assert_eq!(next_version(&resolved).len(), 14);
+        assert_eq!(
+            file_description("Add users' e-mail").unwrap(),
+            "Add_users_e_mail"
+        );
+    }
+}
@@ -2,7 +2,7 @@
 
 use std::collections::HashMap;
 
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 
 #[cfg(feature = "postgres")]
 use tokio_postgres::Client;
@@ -16,8 +16,11 @@ use crate::history::{self, AppliedMigration};
 use crate::migration::{scan_migrations, ResolvedMigration};
 
 /// Report returned after a repair operation.
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct RepairReport {
+    /// Report format version; see [`crate::REPORT_VERSION`].
+    #[serde(default)]
+    pub report_version: u32,
     /// Number of failed migration entries removed from history.
     pub failed_removed: u64,
     /// Number of checksum values updated to match current files.
@@ -31,12 +34,13 @@ pub struct RepairReport {
 pub async fn execute(client: &Client, config: &WaypointConfig) -> Result<RepairReport> {
     let schema = &config.migrations.schema;
     let table = &config.migrations.table;
+    let lock = db::pg_lock_name(&config.migrations)?;
 
-    db::acquire_advisory_lock(client, table).await?;
+    db::acquire_advisory_lock(client, &lock).await?;
 
     let result = execute_inner_pg(client, config, schema, table).await;
 
-    if let Err(e) = db::release_advisory_lock(client, table).await {
+    if let Err(e) = db::release_advisory_lock(client, &lock).await {
         log::error!("Failed to release advisory lock: {}", e);
     }
 
@@ -56,12 +60,12 @@ async fn execute_inner_pg(
     let applied = history::get_applied_migrations(client, schema, table).await?;
     let resolved = scan_migrations(&config.migrations.locations)?;
 
-    let (mut details, checksums_to_apply) = compute_repair(&applied, &resolved);
+    let mut details = Vec::new();
     if failed_removed > 0 {
-        details.insert(0, format!("Removed {} failed migration(s)", failed_removed));
+        details.push(format!("Removed {} failed migration(s)", failed_removed));
     }
     let mut checksums_updated = 0;
-    for ck in checksums_to_apply {
+    for (detail, ck) in compute_repair(&applied, &resolved) {
         match ck {
             RepairChecksum::Versioned { version, new } => {
                 history::update_checksum(client, schema, table, &version, new).await?;
@@ -70,6 +74,7 @@ async fn execute_inner_pg(
                 history::update_repeatable_checksum(client, schema, table, &script, new).await?;
             }
         }
+        details.push(detail);
         checksums_updated += 1;
     }
 
@@ -80,6 +85,7 @@ async fn execute_inner_pg(
     );
 
     Ok(RepairReport {
+        report_version: crate::REPORT_VERSION,
         failed_removed,
         checksums_updated,
         details,
@@ -90,12 +96,13 @@ async fn execute_inner_pg(
 pub async fn execute_db(client: &DbClient, config: &WaypointConfig) -> Result<RepairReport> {
     let schema = client.resolve_schema(&config.migrations.schema).await?;
     let table = &config.migrations.table;
+    let lock = client.lock_name(&config.migrations).await?;
 
-    client.acquire_lock(table).await?;
+    client.acquire_lock(&lock).await?;
 
     let result = execute_inner_db(client, config, &schema, table).await;
 
-    if let Err(e) = client.release_lock(table).await {
+    if let Err(e) = client.release_lock(&lock).await {
         log::error!("Failed to release advisory lock: {}", e);
     }
 
@@ -114,12 +121,12 @@ async fn execute_inner_db(
     let applied = history::get_applied_migrations_db(client, schema, table).await?;
     let resolved = scan_migrations(&config.migrations.locations)?;
 
-    let (mut details, checksums_to_apply) = compute_repair(&applied, &resolved);
+    let mut details = Vec::new();
     if failed_removed > 0 {
-        details.insert(0, format!("Removed {} failed migration(s)", failed_removed));
+        details.push(format!("Removed {} failed migration(s)", failed_removed));
     }
     let mut checksums_updated = 0;
-    for ck in checksums_to_apply {
+    for (detail, ck) in compute_repair(&applied, &resolved) {
         match ck {
             RepairChecksum::Versioned { version, new } => {
                 history::update_checksum_db(client, schema, table, &version, new).await?;
@@ -128,6 +135,7 @@ async fn execute_inner_db(
                 history::update_repeatable_checksum_db(client, schema, table, &script, new).await?;
             }
         }
+        details.push(detail);
         checksums_updated += 1;
     }
 
@@ -138,6 +146,7 @@ async fn execute_inner_db(
     );
 
     Ok(RepairReport {
+        report_version: crate::REPORT_VERSION,
         failed_removed,
         checksums_updated,
         details,
@@ -149,10 +158,12 @@ enum RepairChecksum {
     Repeatable { script: String, new: i32 },
 }
 
+/// Checksum updates needed to match the files on disk, each with the
+/// detail line reported for it.
 fn compute_repair(
     applied: &[AppliedMigration],
     resolved: &[ResolvedMigration],
-) -> (Vec<String>, Vec<RepairChecksum>) {
+) -> Vec<(String, RepairChecksum)> {
     let resolved_by_version: HashMap<String, &ResolvedMigration> = resolved
         .iter()
         .filter(|m| m.is_versioned())
@@ -165,7 +176,6 @@ fn compute_repair(
         .map(|m| (m.script.clone(), m))
         .collect();
 
-    let mut details = Vec::new();
     let mut updates = Vec::new();
 
     for am in applied {
@@ -176,32 +186,89 @@ fn compute_repair(
         if let Some(ref version) = am.version {
             if let Some(resolved) = resolved_by_version.get(version) {
                 if am.checksum != Some(resolved.checksum) {
-                    details.push(format!(
-                        "Updated checksum for version {} ({} -> {})",
-                        version,
-                        am.checksum.unwrap_or(0),
-                        resolved.checksum
+                    updates.push((
+                        format!(
+                            "Updated checksum for version {} ({} -> {})",
+                            version,
+                            am.checksum.unwrap_or(0),
+                            resolved.checksum
+                        ),
+                        RepairChecksum::Versioned {
+                            version: version.clone(),
+                            new: resolved.checksum,
+                        },
                     ));
-                    updates.push(RepairChecksum::Versioned {
-                        version: version.clone(),
-                        new: resolved.checksum,
-                    });
                 }
             }
         } else if let Some(resolved) = resolved_by_script.get(&am.script) {
             if am.checksum != Some(resolved.checksum) {
-                details.push(format!(
-                    "Updated checksum for repeatable '{}' ({} -> {})",
-                    am.script,
-                    am.checksum.unwrap_or(0),
-                    resolved.checksum
+                updates.push((
+                    format!(
+                        "Updated checksum for repeatable '{}' ({} -> {})",
+                        am.script,
+                        am.checksum.unwrap_or(0),
+                        resolved.checksum
+                    ),
+                    RepairChecksum::Repeatable {
+                        script: am.script.clone(),
+                        new: resolved.checksum,
+                    },
                 ));
-                updates.push(RepairChecksum::Repeatable {
-                    script: am.script.clone(),
-                    new: resolved.checksum,
-                });
             }
         }
     }
-    (details, updates)
+    updates
+}
+
+/// Versioned entries of [`compute_repair`]. Repeatables are left out so that
+/// an edited repeatable is still re-applied by migrate.
+fn versioned_repairs(
+    applied: &[AppliedMigration],
+    resolved: &[ResolvedMigration],
+) -> Vec<(String, String, i32)> {
+    compute_repair(applied, resolved)
+        .into_iter()
+        .filter_map(|(detail, ck)| match ck {
+            RepairChecksum::Versioned { version, new } => Some((detail, version, new)),
+            RepairChecksum::Repeatable { .. } => None,
+        })
+        .collect()
+}
+
+/// Dev mode's auto-repair (PostgreSQL): accept edits to applied versioned
+/// migrations by updating their history checksums before validation.
+/// Returns one detail line per update. The caller holds the migration lock.
+#[cfg(feature = "postgres")]
+pub(crate) async fn auto_repair_checksums(
+    client: &Client,
+    schema: &str,
+    table: &str,
+    applied: &[AppliedMigration],
+    resolved: &[ResolvedMigration],
+) -> Result<Vec<String>> {
+    let mut details = Vec::new();
+    for (detail, version, new) in versioned_repairs(applied, resolved) {
+        history::update_checksum(client, schema, table, &version, new).await?;
+        log::warn!("Dev mode auto-repair; detail={}", detail);
+        details.push(format!("Dev mode: {}", detail));
+    }
+    Ok(details)
+}
+
+/// Dev mode's auto-repair (dialect-aware); see [`auto_repair_checksums`].
+#[cfg(feature = "mysql")]
+pub(crate) async fn auto_repair_checksums_db(
+    client: &DbClient,
+    schema: &str,
+    table: &str,
+    applied: &[AppliedMigration],
+    resolved: &[ResolvedMigration],
+) -> Result<Vec<String>> {
+    let mut details = Vec::new();
+    for (detail, version, new) in versioned_repairs(applied, resolved) {
+        history::update_checksum_db(client, schema, table, &version, new).await?;
+        log::warn!("Dev mode auto-repair; detail={}", detail);
+        details.push(format!("Dev mode: {}", detail));
+    }
+    Ok(details)
 }
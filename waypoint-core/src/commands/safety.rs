@@ -1,6 +1,6 @@
 //! Standalone `waypoint safety` command for analyzing migration files.
 
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 
 #[cfg(feature = "postgres")]
 use tokio_postgres::Client;
@@ -11,8 +11,11 @@ use crate::error::Result;
 use crate::safety;
 
 /// Report from the standalone safety analysis command.
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SafetyCommandReport {
+    /// Report format version; see [`crate::REPORT_VERSION`].
+    #[serde(default)]
+    pub report_version: u32,
     /// Per-file safety reports.
     pub reports: Vec<safety::SafetyReport>,
     /// Overall verdict across all files.
@@ -100,6 +103,7 @@ pub async fn execute(client: &Client, config: &WaypointConfig) -> Result<SafetyC
     }
 
     Ok(SafetyCommandReport {
+        report_version: crate::REPORT_VERSION,
         reports,
         overall_verdict: overall,
     })
@@ -147,6 +151,7 @@ pub async fn execute_db(client: &DbClient, config: &WaypointConfig) -> Result<Sa
     }
 
     Ok(SafetyCommandReport {
+        report_version: crate::REPORT_VERSION,
         reports,
         overall_verdict: overall,
     })
@@ -1,7 +1,7 @@
 //! Migration simulation: run pending migrations in a throwaway schema
 //! to prove they will succeed before applying to the real schema.
 
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 
 #[cfg(feature = "postgres")]
 use tokio_postgres::Client;
@@ -19,8 +19,11 @@ use crate::placeholder::{build_placeholders, replace_placeholders};
 use crate::schema;
 
 /// Report from a migration simulation.
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SimulationReport {
+    /// Report format version; see [`crate::REPORT_VERSION`].
+    #[serde(default)]
+    pub report_version: u32,
     /// Whether all pending migrations passed simulation.
     pub passed: bool,
     /// Number of migrations simulated.
@@ -37,7 +40,7 @@ pub struct SimulationReport {
 }
 
 /// An error encountered during simulation.
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SimulationError {
     /// The migration script that failed.
     pub script: String,
@@ -198,6 +201,7 @@ async fn run_simulation(
     }
 
     Ok(SimulationReport {
+        report_version: crate::REPORT_VERSION,
         passed: errors.is_empty(),
         migrations_simulated: simulated,
         temp_schema: temp_schema.to_string(),
@@ -415,6 +419,7 @@ async fn run_simulation_mysql(
     }
 
     Ok(SimulationReport {
+        report_version: crate::REPORT_VERSION,
         passed: errors.is_empty(),
         migrations_simulated: simulated,
         temp_schema: temp_db.to_string(),
@@ -5,7 +5,7 @@
 
 use std::path::PathBuf;
 
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 
 #[cfg(feature = "postgres")]
 use tokio_postgres::Client;
@@ -47,8 +47,11 @@ impl Default for SnapshotConfig {
 }
 
 /// Report from a snapshot operation.
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct SnapshotReport {
+    /// Report format version; see [`crate::REPORT_VERSION`].
+    #[serde(default)]
+    pub report_version: u32,
     /// Unique identifier for the snapshot (timestamp-based).
     pub snapshot_id: String,
     /// Filesystem path where the snapshot SQL file was written.
@@ -58,8 +61,11 @@ pub struct SnapshotReport {
 }
 
 /// Report from a restore operation.
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct RestoreReport {
+    /// Report format version; see [`crate::REPORT_VERSION`].
+    #[serde(default)]
+    pub report_version: u32,
     /// Identifier of the snapshot that was restored.
     pub snapshot_id: String,
     /// Number of schema objects successfully restored.
@@ -135,6 +141,7 @@ pub async fn execute_snapshot(
     prune_snapshots(dir, snapshot_config.max_snapshots)?;
 
     Ok(SnapshotReport {
+        report_version: crate::REPORT_VERSION,
         snapshot_id,
         snapshot_path: sql_path.display().to_string(),
         objects_captured,
@@ -203,6 +210,7 @@ pub async fn execute_restore(
     }
 
     Ok(RestoreReport {
+        report_version: crate::REPORT_VERSION,
         snapshot_id: snapshot_id.to_string(),
         objects_restored,
     })
@@ -396,6 +404,7 @@ async fn execute_snapshot_mysql(
     prune_snapshots(dir, snapshot_config.max_snapshots)?;
 
     Ok(SnapshotReport {
+        report_version: crate::REPORT_VERSION,
         snapshot_id,
         snapshot_path: sql_path.display().to_string(),
         objects_captured,
@@ -537,6 +546,7 @@ async fn execute_restore_mysql(
     }
 
     Ok(RestoreReport {
+        report_version: crate::REPORT_VERSION,
         snapshot_id: snapshot_id.to_string(),
         objects_restored,
     })
@@ -0,0 +1,194 @@
+//! Provision migrated template databases and clone new databases from them
+//! (PostgreSQL only).
+//!
+//! Both commands run on the configured connection, which acts as the
+//! maintenance connection: it must point at an existing database (e.g.
+//! `postgres`) as a role with `CREATEDB`. The template itself is migrated over
+//! a separate connection derived from the configured URL.
+
+use serde::{Deserialize, Serialize};
+use tokio_postgres::Client;
+
+use crate::config::WaypointConfig;
+use crate::db::{self, quote_ident, validate_identifier};
+use crate::error::{Result, WaypointError};
+use crate::Waypoint;
+
+/// Report returned after provisioning a template database.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ProvisionTemplateReport {
+    /// Report format version; see [`crate::REPORT_VERSION`].
+    #[serde(default)]
+    pub report_version: u32,
+    /// Name of the template database.
+    pub template: String,
+    /// Number of migrations applied to the template.
+    pub migrations_applied: usize,
+    /// Whether an existing database of the same name was dropped first.
+    pub replaced: bool,
+}
+
+/// Report returned after cloning a database from a template.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CloneReport {
+    /// Report format version; see [`crate::REPORT_VERSION`].
+    #[serde(default)]
+    pub report_version: u32,
+    /// Name of the newly created database.
+    pub database: String,
+    /// Name of the template it was copied from.
+    pub template: String,
+}
+
+/// Create `name`, apply all migrations to it, and mark it as a template.
+///
+/// Fails if `name` already exists unless `replace` is set, in which case the
+/// existing database is dropped (terminating its connections) first.
+pub async fn provision(
+    client: &Client,
+    config: &WaypointConfig,
+    name: &str,
+    replace: bool,
+) -> Result<ProvisionTemplateReport> {
+    validate_identifier(name)?;
+
+    let exists = database_exists(client, name).await?;
+    if exists {
+        if !replace {
+            return Err(WaypointError::ConfigError(format!(
+                "Database '{}' already exists. Use --replace to drop and re-provision it.",
+                name
+            )));
+        }
+        drop_database(client, name).await?;
+    }
+
+    client
+        .batch_execute(&format!("CREATE DATABASE {}", quote_ident(name)))
+        .await?;
+
+    // A half-migrated database left behind would block the next run, so it
+    // is dropped again if anything after the create fails.
+    let migrations_applied = match migrate_template(client, config, name).await {
+        Ok(applied) => applied,
+        Err(e) => {
+            if let Err(drop_err) = drop_database(client, name).await {
+                log::warn!(
+                    "Failed to drop template database after a failed provision; template={}, error={}",
+                    name,
+                    drop_err
+                );
+            }
+            return Err(e);
+        }
+    };
+
+    log::info!(
+        "Template database provisioned; template={}, migrations_applied={}, replaced={}",
+        name,
+        migrations_applied,
+        exists
+    );
+
+    Ok(ProvisionTemplateReport {
+        report_version: crate::REPORT_VERSION,
+        template: name.to_string(),
+        migrations_applied,
+        replaced: exists,
+    })
+}
+
+/// Apply all migrations to the freshly created `name` and mark it as a
+/// template.
+async fn migrate_template(client: &Client, config: &WaypointConfig, name: &str) -> Result<usize> {
+    let mut template_config = config.clone();
+    template_config.database.url = Some(db::with_database(&config.connection_string()?, name)?);
+    let migrations_applied = {
+        // Scoped so the template connection closes before it is marked as a
+        // template; PostgreSQL refuses to copy a database with active sessions.
+        let wp = Waypoint::new(template_config).await?;
+        wp.migrate(None).await?.migrations_applied
+    };
+
+    client
+        .batch_execute(&format!(
+            "ALTER DATABASE {} WITH IS_TEMPLATE true",
+            quote_ident(name)
+        ))
+        .await?;
+    Ok(migrations_applied)
+}
+
+/// Create `name` as a copy of the `template` database.
+pub async fn clone_from_template(
+    client: &Client,
+    template: &str,
+    name: &str,
+) -> Result<CloneReport> {
+    validate_identifier(template)?;
+    validate_identifier(name)?;
+
+    if !database_exists(client, template).await? {
+        return Err(WaypointError::ConfigError(format!(
+            "Template database '{}' does not exist",
+            template
+        )));
+    }
+
+    client
+        .batch_execute(&format!(
+            "CREATE DATABASE {} TEMPLATE {}",
+            quote_ident(name),
+            quote_ident(template)
+        ))
+        .await?;
+
+    log::info!("Database cloned; database={}, template={}", name, template);
+
+    Ok(CloneReport {
+        report_version: crate::REPORT_VERSION,
+        database: name.to_string(),
+        template: template.to_string(),
+    })
+}
+
+async fn database_exists(client: &Client, name: &str) -> Result<bool> {
+    let row = client
+        .query_one(
+            "SELECT EXISTS(SELECT 1 FROM pg_database WHERE datname = $1)",
+            &[&name],
+        )
+        .await?;
+    Ok(row.get(0))
+}
+
+/// Drop a database, clearing its template flag and terminating any remaining
+/// connections first.
+pub(crate) async fn drop_database(client: &Client, name: &str) -> Result<()> {
+    let is_template = client
+        .query_opt(
+            "SELECT datistemplate FROM pg_database WHERE datname = $1",
+            &[&name],
+        )
+        .await?
+        .is_some_and(|row| row.get::<_, bool>(0));
+    if is_template {
+        client
+            .batch_execute(&format!(
+                "ALTER DATABASE {} WITH IS_TEMPLATE false",
+                quote_ident(name)
+            ))
+            .await?;
+    }
+    client
+        .execute(
+            "SELECT pg_terminate_backend(pid) FROM pg_stat_activity \
+             WHERE datname = $1 AND pid <> pg_backend_pid()",
+            &[&name],
+        )
+        .await?;
+    client
+        .batch_execute(&format!("DROP DATABASE IF EXISTS {}", quote_ident(name)))
+        .await?;
+    Ok(())
+}
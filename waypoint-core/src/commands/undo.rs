@@ -3,7 +3,7 @@
 
 use std::collections::HashMap;
 
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 
 #[cfg(feature = "postgres")]
 use tokio_postgres::Client;
@@ -30,8 +30,11 @@ pub enum UndoTarget {
 }
 
 /// Report returned after an undo operation.
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct UndoReport {
+    /// Report format version; see [`crate::REPORT_VERSION`].
+    #[serde(default)]
+    pub report_version: u32,
     /// Number of migrations that were undone.
     pub migrations_undone: usize,
     /// Total execution time of all undo operations in milliseconds.
@@ -41,7 +44,7 @@ pub struct UndoReport {
 }
 
 /// Details of a single undone migration.
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct UndoDetail {
     /// Version string of the migration that was undone.
     pub version: String,
@@ -150,15 +153,15 @@ pub async fn execute(
     config: &WaypointConfig,
     target: UndoTarget,
 ) -> Result<UndoReport> {
-    let table = &config.migrations.table;
+    let lock = db::pg_lock_name(&config.migrations)?;
 
     // Acquire advisory lock
-    db::acquire_advisory_lock(client, table).await?;
+    db::acquire_advisory_lock(client, &lock).await?;
 
     let result = run_undo(client, config, target).await;
 
     // Always release the advisory lock
-    if let Err(e) = db::release_advisory_lock(client, table).await {
+    if let Err(e) = db::release_advisory_lock(client, &lock).await {
         log::error!("Failed to release advisory lock: {}", e);
     }
 
@@ -190,14 +193,6 @@ async fn run_undo(
     // Create history table if not exists
     history::create_history_table(client, schema, table).await?;
 
-    // Scan migration files — build map of undo files by version
-    let resolved = scan_migrations(&config.migrations.locations)?;
-    let undo_by_version: HashMap<String, &ResolvedMigration> = resolved
-        .iter()
-        .filter(|m| m.is_undo())
-        .filter_map(|m| m.version().map(|v| (v.raw.clone(), m)))
-        .collect();
-
     // Get applied history and compute effective set
     let applied = history::get_applied_migrations(client, schema, table).await?;
     let effective = history::effective_applied_versions(&applied);
@@ -220,6 +215,38 @@ async fn run_undo(
             .collect(),
     };
 
+    let mut report = UndoReport {
+        report_version: crate::REPORT_VERSION,
+        migrations_undone: 0,
+        total_time_ms: 0,
+        details: Vec::new(),
+    };
+    undo_versions(client, config, &versions_to_undo, &mut report).await?;
+    Ok(report)
+}
+
+/// Undo the given versions in order, recording each success in `report`.
+///
+/// Stops at the first failure, leaving `report` describing what was undone.
+/// The caller is responsible for locking.
+#[cfg(feature = "postgres")]
+pub(crate) async fn undo_versions(
+    client: &Client,
+    config: &WaypointConfig,
+    versions_to_undo: &[MigrationVersion],
+    report: &mut UndoReport,
+) -> Result<()> {
+    let schema = &config.migrations.schema;
+    let table = &config.migrations.table;
+
+    // Scan migration files — build map of undo files by version
+    let resolved = scan_migrations(&config.migrations.locations)?;
+    let undo_by_version: HashMap<String, &ResolvedMigration> = resolved
+        .iter()
+        .filter(|m| m.is_undo())
+        .filter_map(|m| m.version().map(|v| (v.raw.clone(), m)))
+        .collect();
+
     // Get database user info for placeholders
     let db_user = db::get_current_user(client)
         .await
@@ -233,14 +260,8 @@ async fn run_undo(
         .as_deref()
         .unwrap_or(&db_user);
 
-    let mut report = UndoReport {
-        migrations_undone: 0,
-        total_time_ms: 0,
-        details: Vec::new(),
-    };
-
     // Execute undo for each version (newest first)
-    for version in &versions_to_undo {
+    for version in versions_to_undo {
         // Try manual U file first, then fall back to auto-generated reversal
         if let Some(undo_migration) = undo_by_version.get(&version.raw) {
             // Manual undo file takes precedence
@@ -328,7 +349,7 @@ async fn run_undo(
         }
     }
 
-    Ok(report)
+    Ok(())
 }
 
 // ── Dialect-aware entry + MySQL path (Phase 1+: manual U-files only) ──────────
@@ -365,13 +386,13 @@ async fn execute_mysql(
     config: &WaypointConfig,
     target: UndoTarget,
 ) -> Result<UndoReport> {
-    let table = &config.migrations.table;
+    let lock = client.lock_name(&config.migrations).await?;
 
-    client.acquire_lock(table).await?;
+    client.acquire_lock(&lock).await?;
 
     let result = run_undo_mysql(client, config, target).await;
 
-    if let Err(e) = client.release_lock(table).await {
+    if let Err(e) = client.release_lock(&lock).await {
         log::error!("Failed to release advisory lock: {}", e);
     }
 
@@ -403,13 +424,6 @@ async fn run_undo_mysql(
 
     history::create_history_table_db(client, schema, table).await?;
 
-    let resolved = scan_migrations(&config.migrations.locations)?;
-    let undo_by_version: HashMap<String, &ResolvedMigration> = resolved
-        .iter()
-        .filter(|m| m.is_undo())
-        .filter_map(|m| m.version().map(|v| (v.raw.clone(), m)))
-        .collect();
-
     let applied = history::get_applied_migrations_db(client, schema, table).await?;
     let effective = history::effective_applied_versions(&applied);
 
@@ -429,6 +443,34 @@ async fn run_undo_mysql(
             .collect(),
     };
 
+    let mut report = UndoReport {
+        report_version: crate::REPORT_VERSION,
+        migrations_undone: 0,
+        total_time_ms: 0,
+        details: Vec::new(),
+    };
+    undo_versions_mysql(client, config, schema, &versions_to_undo, &mut report).await?;
+    Ok(report)
+}
+
+/// MySQL counterpart of [`undo_versions`]; `schema` is the resolved database.
+#[cfg(feature = "mysql")]
+pub(crate) async fn undo_versions_mysql(
+    client: &DbClient,
+    config: &WaypointConfig,
+    schema: &str,
+    versions_to_undo: &[MigrationVersion],
+    report: &mut UndoReport,
+) -> Result<()> {
+    let table = &config.migrations.table;
+
+    let resolved = scan_migrations(&config.migrations.locations)?;
+    let undo_by_version: HashMap<String, &ResolvedMigration> = resolved
+        .iter()
+        .filter(|m| m.is_undo())
+        .filter_map(|m| m.version().map(|v| (v.raw.clone(), m)))
+        .collect();
+
     let db_user = client
         .current_user()
         .await
@@ -444,13 +486,7 @@ async fn run_undo_mysql(
         .unwrap_or(&db_user)
         .to_string();
 
-    let mut report = UndoReport {
-        migrations_undone: 0,
-        total_time_ms: 0,
-        details: Vec::new(),
-    };
-
-    for version in &versions_to_undo {
+    for version in versions_to_undo {
         let (sql, script, description, checksum, auto_reversal) = match undo_by_version
             .get(&version.raw)
         {
@@ -567,5 +603,5 @@ async fn run_undo_mysql(
         }
     }
 
-    Ok(report)
+    Ok(())
 }
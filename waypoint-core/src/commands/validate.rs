@@ -2,7 +2,7 @@
 
 use std::collections::HashMap;
 
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 
 #[cfg(feature = "postgres")]
 use tokio_postgres::Client;
@@ -14,8 +14,11 @@ use crate::history::{self, AppliedMigration};
 use crate::migration::{scan_migrations, ResolvedMigration};
 
 /// Report returned after a validate operation.
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct ValidateReport {
+    /// Report format version; see [`crate::REPORT_VERSION`].
+    #[serde(default)]
+    pub report_version: u32,
     /// Whether all validations passed without errors.
     pub valid: bool,
     /// Validation errors (e.g. checksum mismatches) that indicate corruption.
@@ -33,9 +36,10 @@ pub async fn execute(client: &Client, config: &WaypointConfig) -> Result<Validat
     if !history::history_table_exists(client, schema, table).await? {
         return Ok(empty_report());
     }
+    history::verify_history_meta(client, schema, table).await?;
     let applied = history::get_applied_migrations(client, schema, table).await?;
     let resolved = scan_migrations(&config.migrations.locations)?;
-    finalise(check(applied, resolved))
+    finalise(check(&applied, &resolved), config.migrations.strict)
 }
 
 /// Execute the validate command (dialect-aware entry).
@@ -47,20 +51,48 @@ pub async fn execute_db(client: &DbClient, config: &WaypointConfig) -> Result<Va
     if !history::history_table_exists_db(client, schema, table).await? {
         return Ok(empty_report());
     }
+    history::verify_history_meta_db(client, schema, table).await?;
     let applied = history::get_applied_migrations_db(client, schema, table).await?;
     let resolved = scan_migrations(&config.migrations.locations)?;
-    finalise(check(applied, resolved))
+    finalise(check(&applied, &resolved), config.migrations.strict)
+}
+
+/// Validate an already-loaded history against an already-scanned set of files.
+///
+/// Migrate uses this so that validation and application see the same snapshot.
+/// Warnings are logged here and returned for the migrate report; under
+/// `strict` they fail validation instead.
+pub(crate) fn validate_loaded(
+    applied: &[AppliedMigration],
+    resolved: &[ResolvedMigration],
+    strict: bool,
+) -> Result<ValidateReport> {
+    let report = finalise(check(applied, resolved), strict)?;
+    for warning in &report.warnings {
+        log::warn!("Validation warning; detail={}", warning);
+    }
+    Ok(report)
 }
 
 fn empty_report() -> ValidateReport {
     ValidateReport {
+        report_version: crate::REPORT_VERSION,
         valid: true,
         issues: Vec::new(),
         warnings: vec!["No history table found — nothing to validate.".to_string()],
     }
 }
 
-fn finalise(report: ValidateReport) -> Result<ValidateReport> {
+fn finalise(mut report: ValidateReport, strict: bool) -> Result<ValidateReport> {
+    if strict && !report.warnings.is_empty() {
+        report.issues.extend(
+            report
+                .warnings
+                .drain(..)
+                .map(|w| format!("{} (warning treated as error in strict mode)", w)),
+        );
+        report.valid = false;
+    }
     log::info!(
         "Validation completed; valid={}, issue_count={}, warning_count={}",
         report.valid,
@@ -73,7 +105,7 @@ fn finalise(report: ValidateReport) -> Result<ValidateReport> {
     Ok(report)
 }
 
-fn check(applied: Vec<AppliedMigration>, resolved: Vec<ResolvedMigration>) -> ValidateReport {
+fn check(applied: &[AppliedMigration], resolved: &[ResolvedMigration]) -> ValidateReport {
     let resolved_by_version: HashMap<String, &ResolvedMigration> = resolved
         .iter()
         .filter(|m| m.is_versioned())
@@ -89,7 +121,7 @@ fn check(applied: Vec<AppliedMigration>, resolved: Vec<ResolvedMigration>) -> Va
     let mut issues = Vec::new();
     let mut warnings = Vec::new();
 
-    for am in &applied {
+    for am in applied {
         if !am.success {
             continue;
         }
@@ -126,6 +158,7 @@ fn check(applied: Vec<AppliedMigration>, resolved: Vec<ResolvedMigration>) -> Va
 
     let valid = issues.is_empty();
     ValidateReport {
+        report_version: crate::REPORT_VERSION,
         valid,
         issues,
         warnings,
@@ -0,0 +1,317 @@
+//! Shadow-database verification (PostgreSQL only).
+//!
+//! Replays every migration recorded as applied on the primary into a fresh
+//! schema on a scratch ("shadow") database, then compares the result with the
+//! primary's schema. A difference means either the primary was changed outside
+//! migrations or a migration does not produce the same schema when run again
+//! from scratch, e.g. because it depends on existing data or the environment.
+//!
+//! Repeatables are then applied a second time on the shadow. Each must run
+//! without error and leave the schema exactly as it was, otherwise it is
+//! reported as not re-runnable: the next change to any repeatable would
+//! re-apply it on a deploy, so such a script is a failure waiting to happen.
+//!
+//! The shadow schema is created under a unique name and always dropped
+//! afterwards, so the shadow database can be reused between runs. Migrations
+//! that qualify objects with a hard-coded schema instead of `${waypoint:schema}` write
+//! outside it, which is why the shadow must be a scratch database.
+
+use serde::{Deserialize, Serialize};
+use tokio_postgres::Client;
+
+use crate::config::WaypointConfig;
+use crate::db::{self, quote_ident};
+use crate::engines::postgres::migrate::requires_no_transaction;
+use crate::error::{Result, WaypointError};
+use crate::history;
+use crate::migration::{scan_migrations, MigrationKind, ResolvedMigration};
+use crate::placeholder::{build_placeholders, replace_placeholders};
+use crate::schema::{self, SchemaDiff};
+use crate::sql_parser::split_statements;
+
+/// Report produced by `waypoint verify --shadow-url`.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct VerifyReport {
+    /// Report format version; see [`crate::REPORT_VERSION`].
+    #[serde(default)]
+    pub report_version: u32,
+    /// Schema that was verified on the primary.
+    pub schema: String,
+    /// Throwaway schema the migrations were replayed into on the shadow.
+    pub shadow_schema: String,
+    /// Scripts replayed, in order.
+    pub replayed: Vec<String>,
+    /// Differences from the replayed schema to the primary's.
+    pub differences: Vec<SchemaDiff>,
+    /// Repeatables that failed or changed the schema when applied again.
+    #[serde(default)]
+    pub repeatable_issues: Vec<RepeatableIssue>,
+    /// Whether the primary matches the replay and every repeatable re-ran
+    /// cleanly.
+    pub passed: bool,
+}
+
+/// A repeatable migration that is not safe to apply twice.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RepeatableIssue {
+    /// Script of the repeatable.
+    pub script: String,
+    /// The error from the second run, or the schema changes it made.
+    pub problem: String,
+}
+
+/// Replay applied migrations on the database at `shadow_url` and compare the
+/// result with the primary schema.
+pub async fn execute(
+    client: &Client,
+    config: &WaypointConfig,
+    shadow_url: &str,
+) -> Result<VerifyReport> {
+    let schema_name = &config.migrations.schema;
+    let table = &config.migrations.table;
+
+    let applied = history::get_applied_migrations(client, schema_name, table).await?;
+    let resolved = scan_migrations(&config.migrations.locations)?;
+    let replay = replay_order(&resolved, &applied);
+
+    let shadow = db::connect(shadow_url).await?;
+    let shadow_schema = format!(
+        "waypoint_verify_{}",
+        std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_millis()
+    );
+    shadow
+        .batch_execute(&format!("CREATE SCHEMA {}", quote_ident(&shadow_schema)))
+        .await?;
+    log::info!(
+        "Replaying migrations on shadow database; shadow_schema={}, migrations={}",
+        shadow_schema,
+        replay.len()
+    );
+
+    let result = run_verify(client, config, &shadow, &shadow_schema, &replay).await;
+
+    // Always drop the shadow schema, even when the replay failed.
+    if let Err(e) = shadow
+        .batch_execute(&format!(
+            "DROP SCHEMA IF EXISTS {} CASCADE",
+            quote_ident(&shadow_schema)
+        ))
+        .await
+    {
+        log::warn!(
+            "Failed to drop shadow schema; shadow_schema={}, error={}",
+            shadow_schema,
+            e
+        );
+    }
+
+    let (differences, repeatable_issues) = result?;
+    Ok(VerifyReport {
+        report_version: crate::REPORT_VERSION,
+        schema: schema_name.to_string(),
+        shadow_schema,
+        replayed: replay.iter().map(|m| m.script.clone()).collect(),
+        passed: differences.is_empty() && repeatable_issues.is_empty(),
+        differences,
+        repeatable_issues,
+    })
+}
+
+/// Migrations to replay: applied versioned migrations in version order, then
+/// applied repeatables in the order migrate runs them.
+fn replay_order<'a>(
+    resolved: &'a [ResolvedMigration],
+    applied: &[history::AppliedMigration],
+) -> Vec<&'a ResolvedMigration> {
+    let effective = history::effective_applied_versions(applied);
+    let applied_scripts: std::collections::HashSet<&str> = applied
+        .iter()
+        .filter(|a| a.success && a.version.is_none())
+        .map(|a| a.script.as_str())
+        .collect();
+
+    let versioned = resolved.iter().filter(|m| {
+        m.is_versioned()
+            && m.version()
+                .is_some_and(|v| effective.contains(v.raw.as_str()))
+    });
+    let repeatables = resolved
+        .iter()
+        .filter(|m| matches!(m.kind, MigrationKind::Repeatable))
+        .filter(|m| applied_scripts.contains(m.script.as_str()));
+    versioned.chain(repeatables).collect()
+}
+
+async fn run_verify(
+    client: &Client,
+    config: &WaypointConfig,
+    shadow: &Client,
+    shadow_schema: &str,
+    replay: &[&ResolvedMigration],
+) -> Result<(Vec<SchemaDiff>, Vec<RepeatableIssue>)> {
+    let schema_name = &config.migrations.schema;
+    let db_user = db::get_current_user(shadow)
+        .await
+        .unwrap_or_else(|_| "unknown".to_string());
+    let db_name = db::get_current_database(shadow)
+        .await
+        .unwrap_or_else(|_| "unknown".to_string());
+    let shadow_sql = |migration: &ResolvedMigration| {
+        let placeholders = build_placeholders(
+            &config.placeholders,
+            shadow_schema,
+            &db_user,
+            &db_name,
+            &migration.script,
+        );
+        replace_placeholders(&migration.sql, &placeholders)
+    };
+
+    shadow
+        .batch_execute(&format!(
+            "SET search_path TO {}",
+            quote_ident(shadow_schema)
+        ))
+        .await?;
+    for migration in replay {
+        replay_sql(shadow, &shadow_sql(migration)?)
+            .await
+            .map_err(|e| WaypointError::MigrationFailed {
+                script: migration.script.clone(),
+                reason: format!("Shadow replay: {}", crate::error::format_db_error(&e)),
+            })?;
+    }
+
+    let expected = schema::introspect(shadow, shadow_schema).await?;
+
+    // Second pass over the repeatables. A failed script is rolled back (one
+    // simple-query batch is one transaction, unless the script needs to run
+    // outside one), so the next one still starts from the replayed schema.
+    let mut repeatable_issues = Vec::new();
+    let mut before = expected.clone();
+    for migration in replay
+        .iter()
+        .filter(|m| matches!(m.kind, MigrationKind::Repeatable))
+    {
+        if let Err(e) = replay_sql(shadow, &shadow_sql(migration)?).await {
+            repeatable_issues.push(RepeatableIssue {
+                script: migration.script.clone(),
+                problem: format!(
+                    "fails when applied again: {}",
+                    crate::error::format_db_error(&e)
+                ),
+            });
+            continue;
+        }
+        let after = schema::introspect(shadow, shadow_schema).await?;
+        let changes = schema::diff(&before, &after);
+        if !changes.is_empty() {
+            repeatable_issues.push(RepeatableIssue {
+                script: migration.script.clone(),
+                problem: format!(
+                    "changes the schema when applied again: {}",
+                    changes
+                        .iter()
+                        .map(|d| d.to_string())
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                ),
+            });
+        }
+        before = after;
+    }
+    for issue in &repeatable_issues {
+        log::warn!(
+            "Repeatable is not re-runnable; script={}, problem={}",
+            issue.script,
+            issue.problem
+        );
+    }
+    // Defaults and view definitions are deparsed relative to search_path, so
+    // introspect the primary with its schema first on the path as well.
+    let saved_path: String = client
+        .query_one("SELECT current_setting('search_path')", &[])
+        .await?
+        .get(0);
+    client
+        .batch_execute(&format!("SET search_path TO {}", quote_ident(schema_name)))
+        .await?;
+    let live = schema::introspect(client, schema_name).await;
+    client
+        .batch_execute(&format!("SET search_path TO {}", saved_path))
+        .await?;
+    let live = live?;
+
+    let own_tables = [
+        config.migrations.table.clone(),
+        crate::ddl_capture::audit_table_name(&config.migrations.table),
+        history::META_TABLE.to_string(),
+    ];
+    let differences = schema::diff(&expected, &live)
+        .into_iter()
+        .filter(|d| !is_waypoint_object(d, &own_tables))
+        .collect();
+    Ok((differences, repeatable_issues))
+}
+
+/// Run one migration on the shadow. Like migrate, a script with statements
+/// that cannot run inside a transaction (`CREATE INDEX CONCURRENTLY`,
+/// `VACUUM`, ...) is sent one statement at a time, since a multi-statement
+/// simple query is one implicit transaction.
+async fn replay_sql(shadow: &Client, sql: &str) -> std::result::Result<(), tokio_postgres::Error> {
+    if !requires_no_transaction(sql) {
+        return shadow.batch_execute(sql).await;
+    }
+    for statement in split_statements(sql) {
+        shadow.batch_execute(statement).await?;
+    }
+    Ok(())
+}
+
+/// Whether a difference concerns waypoint's own history and audit tables or
+/// the DDL capture function, which exist on the primary only.
+fn is_waypoint_object(diff: &SchemaDiff, own_tables: &[String]) -> bool {
+    let table = match diff {
+        SchemaDiff::TableAdded(t) => &t.name,
+        SchemaDiff::TableDropped(name) => name,
+        SchemaDiff::ColumnAdded { table, .. }
+        | SchemaDiff::ColumnDropped { table, .. }
+        | SchemaDiff::ColumnAltered { table, .. }
+        | SchemaDiff::ConstraintDropped { table, .. }
+        | SchemaDiff::TriggerDropped { table, .. } => table,
+        SchemaDiff::IndexAdded(idx) => &idx.table_name,
+        SchemaDiff::IndexDropped { table_name, .. } => table_name,
+        SchemaDiff::ConstraintAdded(c) => &c.table_name,
+        SchemaDiff::TriggerAdded(t) => &t.table_name,
+        SchemaDiff::SequenceAdded(s) => {
+            return own_tables.iter().any(|t| s.name.starts_with(t.as_str()))
+        }
+        SchemaDiff::FunctionAdded(f) => return f.name == "waypoint_capture_ddl",
+        _ => return false,
+    };
+    own_tables.contains(table)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schema::TableDef;
+
+    #[test]
+    fn test_history_table_differences_are_ignored() {
+        let own = ["waypoint_schema_history".to_string()];
+        let history = SchemaDiff::TableAdded(TableDef {
+            schema: "public".to_string(),
+            name: "waypoint_schema_history".to_string(),
+            columns: Vec::new(),
+        });
+        assert!(is_waypoint_object(&history, &own));
+        assert!(!is_waypoint_object(
+            &SchemaDiff::TableDropped("users".to_string()),
+            &own
+        ));
+    }
+}
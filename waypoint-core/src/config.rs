@@ -9,6 +9,7 @@ use std::path::PathBuf;
 
 use serde::Deserialize;
 
+use crate::dialect::DialectKind;
 use crate::error::{Result, WaypointError};
 
 /// Helper macro to apply an optional owned value directly to a target field.
@@ -55,6 +56,38 @@ macro_rules! apply_option_some_clone {
     };
 }
 
+/// Subcommands a `[command.<name>]` section may name, feature-gated ones
+/// included so one file serves every build.
+pub const COMMAND_NAMES: &[&str] = &[
+    "migrate",
+    "new",
+    "import",
+    "info",
+    "validate",
+    "repair",
+    "lock",
+    "baseline",
+    "undo",
+    "clean",
+    "lint",
+    "changelog",
+    "diff",
+    "drift",
+    "snapshot",
+    "restore",
+    "preflight",
+    "check-conflicts",
+    "safety",
+    "advise",
+    "simulate",
+    "verify",
+    "serve",
+    "run-and-exit",
+    "provision-template",
+    "clone",
+    "self-update",
+];
+
 /// SSL/TLS connection mode.
 #[derive(Debug, Clone, Default, PartialEq, Eq)]
 pub enum SslMode {
@@ -83,6 +116,74 @@ impl std::str::FromStr for SslMode {
     }
 }
 
+/// How terminal tables mark migration states.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum OutputTheme {
+    /// Colored state names.
+    #[default]
+    Color,
+    /// A symbol before each uncolored state name, for monochrome terminals
+    /// and readers who cannot tell the colors apart.
+    Minimal,
+    /// Symbols plus bold, bright colors that avoid red/green pairs.
+    HighContrast,
+}
+
+impl std::str::FromStr for OutputTheme {
+    type Err = WaypointError;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "color" | "colour" | "default" => Ok(OutputTheme::Color),
+            "minimal" | "symbols" => Ok(OutputTheme::Minimal),
+            "high-contrast" | "high_contrast" => Ok(OutputTheme::HighContrast),
+            _ => Err(WaypointError::ConfigError(format!(
+                "Invalid theme '{}'. Use 'color', 'minimal', or 'high-contrast'.",
+                s
+            ))),
+        }
+    }
+}
+
+/// How widely the migration lock serializes runs (`lock_scope`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LockScope {
+    /// One run at a time per server (MySQL only; PostgreSQL advisory locks
+    /// never span databases).
+    Cluster,
+    /// One run at a time per database and history table.
+    Database,
+    /// One run at a time per schema and history table, so independent
+    /// schemas in one database can migrate in parallel.
+    Schema,
+}
+
+impl std::str::FromStr for LockScope {
+    type Err = WaypointError;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "cluster" | "server" => Ok(LockScope::Cluster),
+            "database" => Ok(LockScope::Database),
+            "schema" => Ok(LockScope::Schema),
+            _ => Err(WaypointError::ConfigError(format!(
+                "Invalid lock scope '{}'. Use 'cluster', 'database', or 'schema'.",
+                s
+            ))),
+        }
+    }
+}
+
+impl std::fmt::Display for LockScope {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LockScope::Cluster => write!(f, "cluster"),
+            LockScope::Database => write!(f, "database"),
+            LockScope::Schema => write!(f, "schema"),
+        }
+    }
+}
+
 /// Top-level configuration for Waypoint.
 #[derive(Debug, Clone, Default)]
 pub struct WaypointConfig {
@@ -112,6 +213,10 @@ pub struct WaypointConfig {
     pub advisor: crate::advisor::AdvisorConfig,
     /// Migration simulation configuration.
     pub simulation: SimulationConfig,
+    /// Smoke-test queries run after a successful migrate.
+    pub post_checks: Vec<crate::post_check::PostCheck>,
+    /// OpenLineage export of migrate runs.
+    pub lineage: crate::lineage::LineageConfig,
 }
 
 /// Database connection configuration.
@@ -139,6 +244,8 @@ pub struct DatabaseConfig {
     pub statement_timeout_secs: u32,
     /// TCP keepalive interval in seconds (0 disables, default 120).
     pub keepalive_secs: u32,
+    /// Overall deadline in seconds for connecting, including retries (0 means no deadline).
+    pub connect_deadline_secs: u32,
 }
 
 impl Default for DatabaseConfig {
@@ -155,6 +262,7 @@ impl Default for DatabaseConfig {
             connect_timeout_secs: 30,
             statement_timeout_secs: 0,
             keepalive_secs: 120,
+            connect_deadline_secs: 0,
         }
     }
 }
@@ -173,6 +281,7 @@ impl fmt::Debug for DatabaseConfig {
             .field("connect_timeout_secs", &self.connect_timeout_secs)
             .field("statement_timeout_secs", &self.statement_timeout_secs)
             .field("keepalive_secs", &self.keepalive_secs)
+            .field("connect_deadline_secs", &self.connect_deadline_secs)
             .finish()
     }
 }
@@ -224,6 +333,74 @@ pub struct MigrationSettings {
     pub show_progress: bool,
     /// Whether to wrap all pending migrations in a single transaction (all-or-nothing).
     pub batch_transaction: bool,
+    /// Accept any schema/table name (e.g. `Müller`, `my-app`) and rely on quoting,
+    /// rejecting only control characters.
+    pub relaxed_identifiers: bool,
+    /// Put the managed schema first on the session `search_path` while migrating (PostgreSQL).
+    pub set_search_path: bool,
+    /// Undo the versions a migrate run applied, using their `U` files, when
+    /// any `[[post_checks]]` entry fails.
+    pub rollback_on_post_check_failure: bool,
+    /// Record the sizes of the tables each migrate run touches, before and after.
+    pub collect_table_sizes: bool,
+    /// Record every DDL command run during migrate via temporary event
+    /// triggers (PostgreSQL, superuser only).
+    pub capture_ddl: bool,
+    /// File the CLI writes each command's full JSON report to.
+    pub report_file: Option<String>,
+    /// Highest version migrate applies when no `--target` is given.
+    pub target: Option<String>,
+    /// Treat a lock holder idle for this many minutes as stale: migrate
+    /// fails with a diagnosis instead of waiting (0 = always wait).
+    pub stale_lock_minutes: u32,
+    /// Terminate a stale lock holder and take the lock instead of failing.
+    pub steal_lock: bool,
+    /// What the migration lock serializes; `None` keeps each engine's
+    /// native scope (database on PostgreSQL, server on MySQL).
+    pub lock_scope: Option<LockScope>,
+    /// How the CLI marks migration states in tables.
+    pub theme: OutputTheme,
+    /// Production guard rails in one switch: validation warnings fail
+    /// validate and migrate, every migration location must exist, and
+    /// out-of-order migrations are refused.
+    pub strict: bool,
+    /// Local-iteration conveniences in one switch: out-of-order and clean
+    /// are allowed, pre-flight checks are skipped, and migrate updates the
+    /// recorded checksums of edited versioned migrations instead of failing.
+    pub dev: bool,
+    /// Role that objects created by migrate are handed to afterwards
+    /// (PostgreSQL).
+    pub transfer_ownership_to: Option<String>,
+    /// Log a heartbeat every this many seconds while a migration's SQL is
+    /// still running, with its state from `pg_stat_activity` (PostgreSQL;
+    /// 0 = off).
+    pub heartbeat_secs: u32,
+}
+
+impl MigrationSettings {
+    /// Tighten settings for strict mode: validation always runs (so its
+    /// warnings can fail the run), out-of-order is refused, and a missing
+    /// migration location is an error rather than a warning.
+    fn apply_strict(&mut self) -> Result<()> {
+        if self.out_of_order {
+            log::warn!("Strict mode ignores out_of_order; out-of-order migrations are refused");
+            self.out_of_order = false;
+        }
+        self.validate_on_migrate = true;
+        let missing: Vec<String> = self
+            .locations
+            .iter()
+            .filter(|l| !l.exists())
+            .map(|l| l.display().to_string())
+            .collect();
+        if !missing.is_empty() {
+            return Err(WaypointError::ConfigError(format!(
+                "Strict mode: migration location(s) do not exist: {}",
+                missing.join(", ")
+            )));
+        }
+        Ok(())
+    }
 }
 
 impl Default for MigrationSettings {
@@ -241,6 +418,21 @@ impl Default for MigrationSettings {
             dependency_ordering: false,
             show_progress: true,
             batch_transaction: false,
+            relaxed_identifiers: false,
+            set_search_path: false,
+            rollback_on_post_check_failure: false,
+            collect_table_sizes: false,
+            capture_ddl: false,
+            report_file: None,
+            target: None,
+            stale_lock_minutes: 10,
+            steal_lock: false,
+            lock_scope: None,
+            theme: OutputTheme::Color,
+            strict: false,
+            dev: false,
+            transfer_ownership_to: None,
+            heartbeat_secs: 0,
         }
     }
 }
@@ -269,6 +461,19 @@ struct TomlConfig {
     safety: Option<TomlSafetyConfig>,
     advisor: Option<TomlAdvisorConfig>,
     simulation: Option<TomlSimulationConfig>,
+    post_checks: Option<Vec<TomlPostCheck>>,
+    lineage: Option<TomlLineageConfig>,
+    /// `[command.<name>]` sections, applied over the rest of the file when
+    /// that subcommand runs.
+    command: Option<HashMap<String, TomlConfig>>,
+}
+
+#[derive(Deserialize)]
+struct TomlPostCheck {
+    name: String,
+    sql: String,
+    expect_rows: Option<i64>,
+    expect: Option<bool>,
 }
 
 #[derive(Deserialize, Default)]
@@ -284,6 +489,7 @@ struct TomlDatabaseConfig {
     connect_timeout: Option<u32>,
     statement_timeout: Option<u32>,
     keepalive: Option<u32>,
+    connect_deadline: Option<u32>,
 }
 
 #[derive(Deserialize, Default)]
@@ -300,6 +506,28 @@ struct TomlMigrationSettings {
     dependency_ordering: Option<bool>,
     show_progress: Option<bool>,
     batch_transaction: Option<bool>,
+    relaxed_identifiers: Option<bool>,
+    set_search_path: Option<bool>,
+    rollback_on_post_check_failure: Option<bool>,
+    collect_table_sizes: Option<bool>,
+    capture_ddl: Option<bool>,
+    report_file: Option<String>,
+    target: Option<String>,
+    stale_lock_minutes: Option<u32>,
+    steal_lock: Option<bool>,
+    lock_scope: Option<String>,
+    theme: Option<String>,
+    strict: Option<bool>,
+    dev: Option<bool>,
+    transfer_ownership_to: Option<String>,
+    heartbeat_secs: Option<u32>,
+}
+
+#[derive(Deserialize, Default)]
+struct TomlLineageConfig {
+    url: Option<String>,
+    namespace: Option<String>,
+    api_key: Option<String>,
 }
 
 #[derive(Deserialize, Default)]
@@ -321,6 +549,8 @@ struct TomlPreflightConfig {
     max_replication_lag_mb: Option<i64>,
     max_replication_lag_secs: Option<i64>,
     long_query_threshold_secs: Option<i64>,
+    windows: Option<Vec<String>>,
+    freeze_query: Option<String>,
 }
 
 #[derive(Deserialize, Default)]
@@ -397,6 +627,8 @@ pub struct CliOverrides {
     pub connect_timeout: Option<u32>,
     /// Override the statement timeout in seconds.
     pub statement_timeout: Option<u32>,
+    /// Override the heartbeat interval for long-running migration SQL.
+    pub heartbeat_secs: Option<u32>,
     /// Override the logical environment name.
     pub environment: Option<String>,
     /// Override whether to use dependency-based migration ordering.
@@ -405,13 +637,31 @@ pub struct CliOverrides {
     pub keepalive: Option<u32>,
     /// Override batch transaction mode (all-or-nothing).
     pub batch_transaction: Option<bool>,
+    /// Override the overall connect deadline in seconds.
+    pub connect_deadline: Option<u32>,
+    /// Override the file the JSON report is written to.
+    pub report_file: Option<String>,
+    /// Override whether a stale lock holder is terminated (`--steal-lock`).
+    pub steal_lock: Option<bool>,
+    /// Override the table theme (`--theme`).
+    pub theme: Option<OutputTheme>,
+    /// Override strict mode (`--strict`).
+    pub strict: Option<bool>,
+    /// Override dev mode (`--dev`).
+    pub dev: Option<bool>,
+    /// Placeholder values (`--set key=value`), applied over all other sources.
+    pub placeholders: Vec<(String, String)>,
+    /// Name of the invoked subcommand (e.g. `migrate`), selecting the
+    /// `[command.<name>]` TOML section to apply.
+    pub command: Option<String>,
 }
 
 impl WaypointConfig {
     /// Load configuration with the following priority (highest wins):
     /// 1. CLI arguments
     /// 2. Environment variables
-    /// 3. TOML config file
+    /// 3. TOML config file, with the `[command.<name>]` section for
+    ///    `overrides.command` taking precedence over the rest of the file
     /// 4. Built-in defaults
     pub fn load(config_path: Option<&str>, overrides: &CliOverrides) -> Result<Self> {
         let mut config = WaypointConfig::default();
@@ -436,7 +686,7 @@ impl WaypointConfig {
                     toml_path, e
                 ))
             })?;
-            config.apply_toml(toml_config);
+            config.apply_toml_for_command(toml_config, overrides.command.as_deref())?;
         } else if config_path.is_some() {
             // If explicitly specified, error if not found
             return Err(WaypointError::ConfigError(format!(
@@ -451,9 +701,70 @@ impl WaypointConfig {
         // Layer 1: CLI overrides
         config.apply_cli(overrides);
 
+        config.finalize()
+    }
+
+    /// Load configuration from environment variables only.
+    ///
+    /// No config file is read and no CLI overrides are applied, so a container
+    /// image behaves the same regardless of what is mounted in its working
+    /// directory. Used by `waypoint run-and-exit`.
+    pub fn from_env() -> Result<Self> {
+        let mut config = WaypointConfig::default();
+        config.apply_env();
+        config.finalize()
+    }
+
+    /// Normalize and validate a fully layered configuration.
+    fn finalize(mut self) -> Result<Self> {
+        let config = &mut self;
+
+        // Strip identifier quotes from schema names, so history, clean, and
+        // info all compare against the same exact-case spelling.
+        config.migrations.schema =
+            canonical_schema(config.database.url.as_deref(), &config.migrations.schema);
+        if let Some(ref mut dbs) = config.multi_database {
+            for db in dbs.iter_mut() {
+                db.migrations.schema =
+                    canonical_schema(db.database.url.as_deref(), &db.migrations.schema);
+            }
+        }
+
         // Validate identifiers
-        crate::db::validate_identifier(&config.migrations.schema)?;
-        crate::db::validate_identifier(&config.migrations.table)?;
+        let validate = if config.migrations.relaxed_identifiers {
+            crate::db::validate_identifier_relaxed
+        } else {
+            crate::db::validate_identifier
+        };
+        validate(&config.migrations.schema)?;
+        validate(&config.migrations.table)?;
+
+        // Reject malformed windows and post-checks up front rather than at migrate time
+        for window in &config.preflight.windows {
+            crate::preflight::MigrationWindow::parse(window)?;
+        }
+        for check in &config.post_checks {
+            check.validate()?;
+        }
+        if let Some(target) = &config.migrations.target {
+            crate::migration::MigrationVersion::parse(target).map_err(|e| {
+                WaypointError::ConfigError(format!("Invalid migrations.target: {}", e))
+            })?;
+        }
+
+        if config.migrations.strict && config.migrations.dev {
+            return Err(WaypointError::ConfigError(
+                "Strict mode and dev mode cannot be enabled together".to_string(),
+            ));
+        }
+        if config.migrations.strict {
+            config.migrations.apply_strict()?;
+        }
+        if config.migrations.dev {
+            config.migrations.out_of_order = true;
+            config.migrations.clean_enabled = true;
+            config.preflight.enabled = false;
+        }
 
         // Cap connect_retries at 20
         if config.database.connect_retries > 20 {
@@ -461,7 +772,43 @@ impl WaypointConfig {
             log::warn!("connect_retries capped at 20");
         }
 
-        Ok(config)
+        Ok(self)
+    }
+
+    /// Apply a parsed config file, then the section for `command`, if any.
+    fn apply_toml_for_command(
+        &mut self,
+        mut toml: TomlConfig,
+        command: Option<&str>,
+    ) -> Result<()> {
+        // A misspelt section would otherwise be ignored without a word.
+        if let Some(unknown) = toml
+            .command
+            .iter()
+            .flat_map(|sections| sections.keys())
+            .find(|name| !COMMAND_NAMES.contains(&name.as_str()))
+        {
+            return Err(WaypointError::ConfigError(format!(
+                "Unknown section [command.{}]; expected a subcommand name such as migrate, info or clean",
+                unknown
+            )));
+        }
+        let section = command.and_then(|name| {
+            toml.command
+                .as_mut()
+                .and_then(|sections| sections.remove(name))
+        });
+        self.apply_toml(toml);
+        if let Some(section) = section {
+            if section.command.is_some() {
+                return Err(WaypointError::ConfigError(format!(
+                    "[command.{}] cannot contain nested [command] sections",
+                    command.unwrap_or_default()
+                )));
+            }
+            self.apply_toml(section);
+        }
+        Ok(())
     }
 
     fn apply_toml(&mut self, toml: TomlConfig) {
@@ -485,6 +832,7 @@ impl WaypointConfig {
             apply_option!(db.connect_timeout => self.database.connect_timeout_secs);
             apply_option!(db.statement_timeout => self.database.statement_timeout_secs);
             apply_option!(db.keepalive => self.database.keepalive_secs);
+            apply_option!(db.connect_deadline => self.database.connect_deadline_secs);
         }
 
         if let Some(m) = toml.migrations {
@@ -502,6 +850,39 @@ impl WaypointConfig {
             apply_option!(m.dependency_ordering => self.migrations.dependency_ordering);
             apply_option!(m.show_progress => self.migrations.show_progress);
             apply_option!(m.batch_transaction => self.migrations.batch_transaction);
+            apply_option!(m.relaxed_identifiers => self.migrations.relaxed_identifiers);
+            apply_option!(m.set_search_path => self.migrations.set_search_path);
+            apply_option!(
+                m.rollback_on_post_check_failure => self.migrations.rollback_on_post_check_failure
+            );
+            apply_option!(m.collect_table_sizes => self.migrations.collect_table_sizes);
+            apply_option!(m.capture_ddl => self.migrations.capture_ddl);
+            apply_option_some!(m.report_file => self.migrations.report_file);
+            apply_option_some!(m.target => self.migrations.target);
+            apply_option!(m.stale_lock_minutes => self.migrations.stale_lock_minutes);
+            apply_option!(m.steal_lock => self.migrations.steal_lock);
+            apply_option!(m.strict => self.migrations.strict);
+            apply_option!(m.dev => self.migrations.dev);
+            apply_option_some!(m.transfer_ownership_to => self.migrations.transfer_ownership_to);
+            apply_option!(m.heartbeat_secs => self.migrations.heartbeat_secs);
+            if let Some(v) = m.theme {
+                match v.parse() {
+                    Ok(theme) => self.migrations.theme = theme,
+                    Err(_) => log::warn!(
+                        "Invalid theme '{}' in config, using default 'color'. Valid values: color, minimal, high-contrast",
+                        v
+                    ),
+                }
+            }
+            if let Some(v) = m.lock_scope {
+                match v.parse() {
+                    Ok(scope) => self.migrations.lock_scope = Some(scope),
+                    Err(_) => log::warn!(
+                        "Invalid lock_scope '{}' in config, ignoring. Valid values: cluster, database, schema",
+                        v
+                    ),
+                }
+            }
         }
 
         if let Some(h) = toml.hooks {
@@ -541,6 +922,8 @@ impl WaypointConfig {
             apply_option!(p.max_replication_lag_mb => self.preflight.max_replication_lag_mb);
             apply_option!(p.max_replication_lag_secs => self.preflight.max_replication_lag_secs);
             apply_option!(p.long_query_threshold_secs => self.preflight.long_query_threshold_secs);
+            apply_option!(p.windows => self.preflight.windows);
+            apply_option_some!(p.freeze_query => self.preflight.freeze_query);
         }
 
         if let Some(g) = toml.guards {
@@ -577,6 +960,24 @@ impl WaypointConfig {
             apply_option!(s.simulate_before_migrate => self.simulation.simulate_before_migrate);
         }
 
+        if let Some(l) = toml.lineage {
+            apply_option_some!(l.url => self.lineage.url);
+            apply_option!(l.namespace => self.lineage.namespace);
+            apply_option_some!(l.api_key => self.lineage.api_key);
+        }
+
+        if let Some(checks) = toml.post_checks {
+            self.post_checks = checks
+                .into_iter()
+                .map(|c| crate::post_check::PostCheck {
+                    name: c.name,
+                    sql: c.sql,
+                    expect_rows: c.expect_rows,
+                    expect: c.expect,
+                })
+                .collect();
+        }
+
         if let Some(databases) = toml.databases {
             let mut named_dbs = Vec::new();
             for db in databases {
@@ -606,6 +1007,8 @@ impl WaypointConfig {
                     apply_option!(m.dependency_ordering => mig_settings.dependency_ordering);
                     apply_option!(m.show_progress => mig_settings.show_progress);
                     apply_option!(m.batch_transaction => mig_settings.batch_transaction);
+                    apply_option!(m.relaxed_identifiers => mig_settings.relaxed_identifiers);
+                    apply_option!(m.set_search_path => mig_settings.set_search_path);
                 }
 
                 let mut hooks_config = HooksConfig::default();
@@ -675,6 +1078,11 @@ impl WaypointConfig {
                 self.database.connect_timeout_secs = n;
             }
         }
+        if let Ok(v) = std::env::var("WAYPOINT_CONNECT_DEADLINE") {
+            if let Ok(n) = v.parse::<u32>() {
+                self.database.connect_deadline_secs = n;
+            }
+        }
         if let Ok(v) = std::env::var("WAYPOINT_STATEMENT_TIMEOUT") {
             if let Ok(n) = v.parse::<u32>() {
                 self.database.statement_timeout_secs = n;
@@ -699,9 +1107,72 @@ impl WaypointConfig {
         if let Ok(v) = std::env::var("WAYPOINT_BATCH_TRANSACTION") {
             self.migrations.batch_transaction = v == "1" || v.eq_ignore_ascii_case("true");
         }
+        if let Ok(v) = std::env::var("WAYPOINT_ROLLBACK_ON_POST_CHECK_FAILURE") {
+            self.migrations.rollback_on_post_check_failure =
+                v == "1" || v.eq_ignore_ascii_case("true");
+        }
+        if let Ok(v) = std::env::var("WAYPOINT_CAPTURE_DDL") {
+            self.migrations.capture_ddl = v == "1" || v.eq_ignore_ascii_case("true");
+        }
+        if let Ok(v) = std::env::var("WAYPOINT_COLLECT_TABLE_SIZES") {
+            self.migrations.collect_table_sizes = v == "1" || v.eq_ignore_ascii_case("true");
+        }
+        if let Ok(v) = std::env::var("WAYPOINT_RELAXED_IDENTIFIERS") {
+            self.migrations.relaxed_identifiers = v == "1" || v.eq_ignore_ascii_case("true");
+        }
+        if let Ok(v) = std::env::var("WAYPOINT_SET_SEARCH_PATH") {
+            self.migrations.set_search_path = v == "1" || v.eq_ignore_ascii_case("true");
+        }
         if let Ok(v) = std::env::var("WAYPOINT_ENVIRONMENT") {
             self.migrations.environment = Some(v);
         }
+        if let Ok(v) = std::env::var("WAYPOINT_REPORT_FILE") {
+            self.migrations.report_file = Some(v);
+        }
+        if let Ok(v) = std::env::var("WAYPOINT_LINEAGE_URL") {
+            self.lineage.url = Some(v);
+        }
+        if let Ok(v) = std::env::var("WAYPOINT_LINEAGE_NAMESPACE") {
+            self.lineage.namespace = v;
+        }
+        if let Ok(v) = std::env::var("WAYPOINT_LINEAGE_API_KEY") {
+            self.lineage.api_key = Some(v);
+        }
+        if let Ok(v) = std::env::var("WAYPOINT_TARGET") {
+            self.migrations.target = Some(v);
+        }
+        if let Ok(v) = std::env::var("WAYPOINT_STALE_LOCK_MINUTES") {
+            if let Ok(n) = v.parse::<u32>() {
+                self.migrations.stale_lock_minutes = n;
+            }
+        }
+        if let Ok(v) = std::env::var("WAYPOINT_STEAL_LOCK") {
+            self.migrations.steal_lock = v == "1" || v.eq_ignore_ascii_case("true");
+        }
+        if let Ok(v) = std::env::var("WAYPOINT_LOCK_SCOPE") {
+            if let Ok(scope) = v.parse() {
+                self.migrations.lock_scope = Some(scope);
+            }
+        }
+        if let Ok(v) = std::env::var("WAYPOINT_STRICT") {
+            self.migrations.strict = v == "1" || v.eq_ignore_ascii_case("true");
+        }
+        if let Ok(v) = std::env::var("WAYPOINT_TRANSFER_OWNERSHIP_TO") {
+            self.migrations.transfer_ownership_to = Some(v);
+        }
+        if let Ok(v) = std::env::var("WAYPOINT_HEARTBEAT_SECS") {
+            if let Ok(n) = v.parse::<u32>() {
+                self.migrations.heartbeat_secs = n;
+            }
+        }
+        if let Ok(v) = std::env::var("WAYPOINT_DEV") {
+            self.migrations.dev = v == "1" || v.eq_ignore_ascii_case("true");
+        }
+        if let Ok(v) = std::env::var("WAYPOINT_THEME") {
+            if let Ok(theme) = v.parse() {
+                self.migrations.theme = theme;
+            }
+        }
 
         // Scan for placeholder env vars: WAYPOINT_PLACEHOLDER_{KEY}
         for (key, value) in std::env::vars() {
@@ -729,10 +1200,35 @@ impl WaypointConfig {
         }
         apply_option!(overrides.connect_timeout => self.database.connect_timeout_secs);
         apply_option!(overrides.statement_timeout => self.database.statement_timeout_secs);
+        apply_option!(overrides.heartbeat_secs => self.migrations.heartbeat_secs);
         apply_option_some_clone!(overrides.environment => self.migrations.environment);
         apply_option!(overrides.dependency_ordering => self.migrations.dependency_ordering);
         apply_option!(overrides.keepalive => self.database.keepalive_secs);
         apply_option!(overrides.batch_transaction => self.migrations.batch_transaction);
+        apply_option!(overrides.connect_deadline => self.database.connect_deadline_secs);
+        apply_option_some_clone!(overrides.report_file => self.migrations.report_file);
+        apply_option!(overrides.steal_lock => self.migrations.steal_lock);
+        apply_option!(overrides.theme => self.migrations.theme);
+        apply_option!(overrides.strict => self.migrations.strict);
+        apply_option!(overrides.dev => self.migrations.dev);
+        for (key, value) in &overrides.placeholders {
+            // Lookup is case-insensitive, so drop any differently-cased entry it replaces.
+            self.placeholders
+                .retain(|k, _| !k.eq_ignore_ascii_case(key));
+            self.placeholders.insert(key.clone(), value.clone());
+        }
+    }
+
+    /// Dialect of the configured database, from the URL scheme; PostgreSQL
+    /// when no URL is set or its scheme is not recognised.
+    pub fn dialect_kind(&self) -> DialectKind {
+        self.database
+            .url
+            .as_deref()
+            .map(normalize_jdbc_url)
+            .as_deref()
+            .and_then(DialectKind::from_url)
+            .unwrap_or(DialectKind::Postgres)
     }
 
     /// Build a connection string from the config.
@@ -834,9 +1330,25 @@ fn normalize_jdbc_url(url: &str) -> String {
     url.to_string()
 }
 
+/// Canonicalize a configured schema name for the dialect implied by `url`
+/// (PostgreSQL when the URL is absent or not recognised).
+fn canonical_schema(url: Option<&str>, schema: &str) -> String {
+    url.and_then(DialectKind::from_url)
+        .unwrap_or(DialectKind::Postgres)
+        .canonical_ident(schema)
+}
+
 /// Strip `filesystem:` prefix from a location path (Flyway compatibility).
+///
+/// Windows verbatim paths (`\\?\C:\...`, as produced by `canonicalize` and
+/// used for paths beyond `MAX_PATH`) skip the OS's own normalization, so `/`
+/// is not a separator inside them; it is rewritten to `\` here so a location
+/// like `\\?\C:\repo/db/migrations` still resolves.
 pub fn normalize_location(location: &str) -> PathBuf {
     let stripped = location.strip_prefix("filesystem:").unwrap_or(location);
+    if let Some(rest) = stripped.strip_prefix(r"\\?\") {
+        return PathBuf::from(format!(r"\\?\{}", rest.replace('/', "\\")));
+    }
     PathBuf::from(stripped)
 }
 
@@ -908,10 +1420,19 @@ mod tests {
             ssl_mode: None,
             connect_timeout: None,
             statement_timeout: None,
+            heartbeat_secs: None,
             environment: None,
             dependency_ordering: None,
             keepalive: None,
             batch_transaction: None,
+            connect_deadline: None,
+            report_file: None,
+            steal_lock: None,
+            theme: None,
+            strict: None,
+            dev: None,
+            placeholders: Vec::new(),
+            command: None,
         };
 
         config.apply_cli(&overrides);
@@ -967,6 +1488,311 @@ app_name = "myapp"
         assert_eq!(config.placeholders.get("app_name").unwrap(), "myapp");
     }
 
+    #[test]
+    fn test_connect_deadline_from_toml_and_cli() {
+        let toml_str = r#"
+[database]
+connect_retries = 5
+connect_deadline = 45
+"#;
+
+        let toml_config: TomlConfig = toml::from_str(toml_str).unwrap();
+        let mut config = WaypointConfig::default();
+        assert_eq!(config.database.connect_deadline_secs, 0);
+        config.apply_toml(toml_config);
+        assert_eq!(config.database.connect_deadline_secs, 45);
+
+        config.apply_cli(&CliOverrides {
+            connect_deadline: Some(10),
+            ..Default::default()
+        });
+        assert_eq!(config.database.connect_deadline_secs, 10);
+    }
+
+    #[test]
+    fn test_report_file_from_toml_and_cli() {
+        let toml_str = r#"
+[migrations]
+report_file = "reports/last.json"
+"#;
+
+        let toml_config: TomlConfig = toml::from_str(toml_str).unwrap();
+        let mut config = WaypointConfig::default();
+        assert_eq!(config.migrations.report_file, None);
+        config.apply_toml(toml_config);
+        assert_eq!(
+            config.migrations.report_file.as_deref(),
+            Some("reports/last.json")
+        );
+
+        config.apply_cli(&CliOverrides {
+            report_file: Some("/tmp/report.json".to_string()),
+            ..Default::default()
+        });
+        assert_eq!(
+            config.migrations.report_file.as_deref(),
+            Some("/tmp/report.json")
+        );
+    }
+
+    #[test]
+    fn test_lock_scope_from_toml() {
+        let mut config = WaypointConfig::default();
+        assert_eq!(config.migrations.lock_scope, None);
+        let toml_config: TomlConfig =
+            toml::from_str("[migrations]\nlock_scope = \"Schema\"\n").unwrap();
+        config.apply_toml(toml_config);
+        assert_eq!(config.migrations.lock_scope, Some(LockScope::Schema));
+
+        let toml_config: TomlConfig =
+            toml::from_str("[migrations]\nlock_scope = \"table\"\n").unwrap();
+        config.apply_toml(toml_config);
+        assert_eq!(config.migrations.lock_scope, Some(LockScope::Schema));
+        assert_eq!("server".parse::<LockScope>().unwrap(), LockScope::Cluster);
+    }
+
+    #[test]
+    fn test_theme_from_toml_and_cli() {
+        let toml_config: TomlConfig =
+            toml::from_str("[migrations]\ntheme = \"High-Contrast\"\n").unwrap();
+        let mut config = WaypointConfig::default();
+        assert_eq!(config.migrations.theme, OutputTheme::Color);
+        config.apply_toml(toml_config);
+        assert_eq!(config.migrations.theme, OutputTheme::HighContrast);
+
+        config.apply_cli(&CliOverrides {
+            theme: Some(OutputTheme::Minimal),
+            ..Default::default()
+        });
+        assert_eq!(config.migrations.theme, OutputTheme::Minimal);
+
+        let toml_config: TomlConfig = toml::from_str("[migrations]\ntheme = \"neon\"\n").unwrap();
+        config.apply_toml(toml_config);
+        assert_eq!(config.migrations.theme, OutputTheme::Minimal);
+        assert!("symbols".parse::<OutputTheme>().is_ok());
+    }
+
+    #[test]
+    fn test_strict_mode_tightens_settings() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut settings = MigrationSettings {
+            strict: true,
+            out_of_order: true,
+            validate_on_migrate: false,
+            locations: vec![dir.path().to_path_buf()],
+            ..Default::default()
+        };
+        settings.apply_strict().unwrap();
+        assert!(!settings.out_of_order);
+        assert!(settings.validate_on_migrate);
+
+        settings.locations.push(dir.path().join("missing"));
+        let err = settings.apply_strict().unwrap_err().to_string();
+        assert!(err.contains("missing"), "{}", err);
+    }
+
+    #[test]
+    fn test_dev_mode_enables_conveniences() {
+        let config = WaypointConfig {
+            migrations: MigrationSettings {
+                dev: true,
+                ..Default::default()
+            },
+            ..Default::default()
+        }
+        .finalize()
+        .unwrap();
+        assert!(config.migrations.out_of_order);
+        assert!(config.migrations.clean_enabled);
+        assert!(!config.preflight.enabled);
+
+        let err = WaypointConfig {
+            migrations: MigrationSettings {
+                dev: true,
+                strict: true,
+                ..Default::default()
+            },
+            ..Default::default()
+        }
+        .finalize()
+        .unwrap_err();
+        assert!(err.to_string().contains("cannot be enabled together"));
+    }
+
+    #[test]
+    fn test_target_from_toml_and_command_section() {
+        let toml_str = r#"
+[migrations]
+target = "100"
+
+[command.info.migrations]
+target = "90"
+"#;
+
+        let mut config = WaypointConfig::default();
+        config
+            .apply_toml_for_command(toml::from_str(toml_str).unwrap(), Some("migrate"))
+            .unwrap();
+        assert_eq!(config.migrations.target.as_deref(), Some("100"));
+
+        let mut config = WaypointConfig::default();
+        config
+            .apply_toml_for_command(toml::from_str(toml_str).unwrap(), Some("info"))
+            .unwrap();
+        assert_eq!(config.migrations.target.as_deref(), Some("90"));
+    }
+
+    #[test]
+    fn test_cli_placeholders_override_toml() {
+        let toml_str = r#"
+[placeholders]
+Tenant = "default"
+region = "us"
+"#;
+
+        let toml_config: TomlConfig = toml::from_str(toml_str).unwrap();
+        let mut config = WaypointConfig::default();
+        config.apply_toml(toml_config);
+        config.apply_cli(&CliOverrides {
+            placeholders: vec![
+                ("tenant".to_string(), "acme".to_string()),
+                ("extra".to_string(), "a=b".to_string()),
+            ],
+            ..Default::default()
+        });
+
+        assert_eq!(config.placeholders.len(), 3);
+        assert_eq!(config.placeholders.get("tenant").unwrap(), "acme");
+        assert_eq!(config.placeholders.get("region").unwrap(), "us");
+        assert_eq!(config.placeholders.get("extra").unwrap(), "a=b");
+    }
+
+    #[test]
+    fn test_command_section_applies_to_that_command_only() {
+        let toml_str = r#"
+[database]
+statement_timeout = 300
+
+[migrations]
+schema = "app"
+
+[command.clean.database]
+statement_timeout = 10
+
+[command.clean.migrations]
+clean_enabled = true
+"#;
+
+        let mut config = WaypointConfig::default();
+        config
+            .apply_toml_for_command(toml::from_str(toml_str).unwrap(), Some("migrate"))
+            .unwrap();
+        assert_eq!(config.database.statement_timeout_secs, 300);
+        assert!(!config.migrations.clean_enabled);
+
+        let mut config = WaypointConfig::default();
+        config
+            .apply_toml_for_command(toml::from_str(toml_str).unwrap(), Some("clean"))
+            .unwrap();
+        assert_eq!(config.database.statement_timeout_secs, 10);
+        assert!(config.migrations.clean_enabled);
+        assert_eq!(config.migrations.schema, "app");
+
+        let nested = "[command.clean.command.migrate.migrations]\nschema = \"x\"\n";
+        let mut config = WaypointConfig::default();
+        assert!(config
+            .apply_toml_for_command(toml::from_str(nested).unwrap(), Some("clean"))
+            .is_err());
+
+        // A typo is reported whichever command runs.
+        let typo = "[command.migrat.migrations]\nschema = \"x\"\n";
+        let mut config = WaypointConfig::default();
+        let err = config
+            .apply_toml_for_command(toml::from_str(typo).unwrap(), Some("info"))
+            .unwrap_err();
+        assert!(matches!(err, WaypointError::ConfigError(_)));
+        assert!(err.to_string().contains("[command.migrat]"), "{}", err);
+    }
+
+    #[test]
+    fn test_relaxed_identifiers_from_toml() {
+        let toml_str = r#"
+[migrations]
+schema = "my-app"
+relaxed_identifiers = true
+"#;
+
+        let toml_config: TomlConfig = toml::from_str(toml_str).unwrap();
+        let mut config = WaypointConfig::default();
+        config.apply_toml(toml_config);
+
+        assert!(config.migrations.relaxed_identifiers);
+        assert_eq!(config.migrations.schema, "my-app");
+    }
+
+    #[test]
+    fn test_preflight_windows_and_freeze_from_toml() {
+        let toml_str = r#"
+[preflight]
+windows = ["Mon-Fri 22:00-06:00"]
+freeze_query = "SELECT frozen, reason FROM ops.change_freeze"
+"#;
+
+        let toml_config: TomlConfig = toml::from_str(toml_str).unwrap();
+        let mut config = WaypointConfig::default();
+        config.apply_toml(toml_config);
+
+        assert_eq!(config.preflight.windows, vec!["Mon-Fri 22:00-06:00"]);
+        assert_eq!(
+            config.preflight.freeze_query.as_deref(),
+            Some("SELECT frozen, reason FROM ops.change_freeze")
+        );
+        assert!(config.finalize().is_ok());
+    }
+
+    #[test]
+    fn test_post_checks_from_toml() {
+        let toml_str = r#"
+[[post_checks]]
+name = "users readable"
+sql = "SELECT 1 FROM users LIMIT 1"
+expect_rows = 1
+
+[[post_checks]]
+name = "no orphans"
+sql = "SELECT NOT EXISTS (SELECT 1 FROM orders WHERE user_id IS NULL)"
+expect = true
+"#;
+
+        let toml_config: TomlConfig = toml::from_str(toml_str).unwrap();
+        let mut config = WaypointConfig::default();
+        config.apply_toml(toml_config);
+
+        assert_eq!(config.post_checks.len(), 2);
+        assert_eq!(config.post_checks[0].name, "users readable");
+        assert_eq!(config.post_checks[0].expect_rows, Some(1));
+        assert_eq!(config.post_checks[1].expect, Some(true));
+        assert!(config.finalize().is_ok());
+    }
+
+    #[test]
+    fn test_invalid_preflight_window_rejected() {
+        let mut config = WaypointConfig::default();
+        config.preflight.windows = vec!["Someday 22:00-06:00".to_string()];
+        assert!(config.finalize().is_err());
+    }
+
+    #[test]
+    fn test_canonical_schema_per_dialect() {
+        assert_eq!(canonical_schema(None, "MyApp"), "MyApp");
+        assert_eq!(canonical_schema(None, "\"MyApp\""), "MyApp");
+        assert_eq!(
+            canonical_schema(Some("postgres://u@h/db"), "Reporting"),
+            "Reporting"
+        );
+        assert_eq!(canonical_schema(Some("mysql://u@h/db"), "MyApp"), "MyApp");
+    }
+
     #[test]
     fn test_normalize_jdbc_url_with_credentials() {
         let url = "jdbc:postgresql://myhost:5432/mydb?user=admin&password=secret";
@@ -1022,6 +1848,18 @@ app_name = "myapp"
         );
     }
 
+    #[test]
+    fn test_normalize_location_windows_verbatim() {
+        assert_eq!(
+            normalize_location(r"filesystem:\\?\C:\repo/db/migrations"),
+            PathBuf::from(r"\\?\C:\repo\db\migrations")
+        );
+        assert_eq!(
+            normalize_location(r"\\?\UNC\server\share/sql"),
+            PathBuf::from(r"\\?\UNC\server\share\sql")
+        );
+    }
+
     #[test]
     fn test_normalize_location_relative() {
         assert_eq!(
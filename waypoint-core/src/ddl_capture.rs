@@ -0,0 +1,201 @@
+//! DDL capture via PostgreSQL event triggers.
+//!
+//! With `capture_ddl` enabled, migrate installs a pair of event triggers for
+//! the duration of the run that record every DDL command (command tag, object
+//! type and identity) into `{table}_ddl_audit` next to the history table.
+//! Unlike parsing the migration SQL, this also sees DDL issued from dynamic
+//! SQL, functions and `DO` blocks.
+//!
+//! Event triggers are database-wide, so the trigger function only records
+//! commands from sessions that have opted in through the
+//! `waypoint.ddl_capture_schema` setting; other sessions pay one
+//! `current_setting` call per DDL command while a run is in progress.
+//! Creating event triggers requires superuser; when that fails the run
+//! continues without capture.
+
+use serde::{Deserialize, Serialize};
+
+#[cfg(feature = "postgres")]
+use tokio_postgres::Client;
+
+#[cfg(feature = "postgres")]
+use crate::db::quote_ident;
+#[cfg(feature = "postgres")]
+use crate::error::Result;
+
+/// One DDL command recorded during a migrate run.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CapturedDdl {
+    /// Migration being applied when the command ran (hooks around it
+    /// included); `None` for waypoint's own DDL, such as creating the
+    /// history table.
+    pub script: Option<String>,
+    /// Command tag, e.g. `CREATE TABLE` or `ALTER TABLE`.
+    pub command_tag: String,
+    /// Object type, e.g. `table` or `index`.
+    pub object_type: Option<String>,
+    /// Fully qualified object identity as reported by PostgreSQL.
+    pub object_identity: Option<String>,
+}
+
+/// Name of the audit table kept alongside the history table.
+pub fn audit_table_name(history_table: &str) -> String {
+    format!("{}_ddl_audit", history_table)
+}
+
+#[cfg(feature = "postgres")]
+fn quote_literal(value: &str) -> String {
+    format!("'{}'", value.replace('\'', "''"))
+}
+
+#[cfg(feature = "postgres")]
+fn trigger_names(schema: &str) -> (String, String) {
+    (
+        format!("waypoint_ddl_end_{}", schema),
+        format!("waypoint_ddl_drop_{}", schema),
+    )
+}
+
+#[cfg(feature = "postgres")]
+fn function_name(schema: &str) -> String {
+    format!(
+        "{}.{}",
+        quote_ident(schema),
+        quote_ident("waypoint_capture_ddl")
+    )
+}
+
+/// Create the audit table and install the event triggers, then opt this
+/// session in under a fresh run id, which is returned.
+#[cfg(feature = "postgres")]
+pub async fn install(client: &Client, schema: &str, history_table: &str) -> Result<String> {
+    let audit = format!(
+        "{}.{}",
+        quote_ident(schema),
+        quote_ident(&audit_table_name(history_table))
+    );
+    let function = function_name(schema);
+    let (end_trigger, drop_trigger) = trigger_names(schema);
+    let schema_lit = quote_literal(schema);
+
+    client
+        .batch_execute(&format!(
+            r#"
+CREATE TABLE IF NOT EXISTS {audit} (
+    id              BIGSERIAL PRIMARY KEY,
+    run_id          TEXT NOT NULL,
+    script          TEXT,
+    command_tag     TEXT NOT NULL,
+    object_type     TEXT,
+    schema_name     TEXT,
+    object_identity TEXT,
+    captured_at     TIMESTAMPTZ NOT NULL DEFAULT now()
+);
+
+CREATE OR REPLACE FUNCTION {function}() RETURNS event_trigger
+LANGUAGE plpgsql AS $waypoint$
+BEGIN
+    IF current_setting('waypoint.ddl_capture_schema', true) IS DISTINCT FROM {schema_lit} THEN
+        RETURN;
+    END IF;
+    IF TG_EVENT = 'sql_drop' THEN
+        INSERT INTO {audit} (run_id, script, command_tag, object_type, schema_name, object_identity)
+        SELECT current_setting('waypoint.ddl_capture_run'),
+               nullif(current_setting('waypoint.ddl_capture_script', true), ''),
+               TG_TAG, object_type, schema_name, object_identity
+        FROM pg_event_trigger_dropped_objects()
+        WHERE original;
+    ELSE
+        INSERT INTO {audit} (run_id, script, command_tag, object_type, schema_name, object_identity)
+        SELECT current_setting('waypoint.ddl_capture_run'),
+               nullif(current_setting('waypoint.ddl_capture_script', true), ''),
+               command_tag, object_type, schema_name, object_identity
+        FROM pg_event_trigger_ddl_commands();
+    END IF;
+END
+$waypoint$;
+
+DROP EVENT TRIGGER IF EXISTS {end_trigger};
+DROP EVENT TRIGGER IF EXISTS {drop_trigger};
+CREATE EVENT TRIGGER {end_trigger} ON ddl_command_end EXECUTE FUNCTION {function}();
+CREATE EVENT TRIGGER {drop_trigger} ON sql_drop EXECUTE FUNCTION {function}();
+"#,
+            end_trigger = quote_ident(&end_trigger),
+            drop_trigger = quote_ident(&drop_trigger),
+        ))
+        .await?;
+
+    let run_id = format!(
+        "{}-{}",
+        chrono::Utc::now().format("%Y%m%dT%H%M%S%.6fZ"),
+        std::process::id()
+    );
+    client
+        .execute(
+            "SELECT set_config('waypoint.ddl_capture_schema', $1, false), \
+                    set_config('waypoint.ddl_capture_run', $2, false)",
+            &[&schema, &run_id],
+        )
+        .await?;
+    log::info!(
+        "Installed DDL capture event triggers; schema={}, run_id={}",
+        schema,
+        run_id
+    );
+    Ok(run_id)
+}
+
+/// Attribute subsequently captured DDL to `script`.
+#[cfg(feature = "postgres")]
+pub async fn set_script(client: &Client, script: &str) -> Result<()> {
+    client
+        .execute(
+            "SELECT set_config('waypoint.ddl_capture_script', $1, false)",
+            &[&script],
+        )
+        .await?;
+    Ok(())
+}
+
+/// Read back what a run captured, in execution order.
+#[cfg(feature = "postgres")]
+pub async fn captured(
+    client: &Client,
+    schema: &str,
+    history_table: &str,
+    run_id: &str,
+) -> Result<Vec<CapturedDdl>> {
+    let sql = format!(
+        "SELECT script, command_tag, object_type, object_identity FROM {}.{} \
+         WHERE run_id = $1 ORDER BY id",
+        quote_ident(schema),
+        quote_ident(&audit_table_name(history_table))
+    );
+    let rows = client.query(&sql, &[&run_id]).await?;
+    Ok(rows
+        .iter()
+        .map(|r| CapturedDdl {
+            script: r.get(0),
+            command_tag: r.get(1),
+            object_type: r.get(2),
+            object_identity: r.get(3),
+        })
+        .collect())
+}
+
+/// Drop the event triggers and opt this session back out. The audit table
+/// and trigger function are kept.
+#[cfg(feature = "postgres")]
+pub async fn uninstall(client: &Client, schema: &str) -> Result<()> {
+    let (end_trigger, drop_trigger) = trigger_names(schema);
+    client
+        .batch_execute(&format!(
+            "DROP EVENT TRIGGER IF EXISTS {}; DROP EVENT TRIGGER IF EXISTS {}; \
+             SELECT set_config('waypoint.ddl_capture_schema', '', false), \
+                    set_config('waypoint.ddl_capture_script', '', false);",
+            quote_ident(&end_trigger),
+            quote_ident(&drop_trigger),
+        ))
+        .await?;
+    Ok(())
+}
@@ -47,6 +47,28 @@ impl DialectKind {
             None
         }
     }
+
+    /// Canonical form of a configured identifier: the name as written, with
+    /// surrounding identifier quotes removed.
+    ///
+    /// Waypoint always quotes the configured schema in the DDL it runs, so on
+    /// PostgreSQL `MyApp` and `"MyApp"` both name the exact-case schema `MyApp`.
+    /// Folding unquoted names to lower case would move existing deployments to
+    /// a different schema. MySQL identifier case depends on server settings, so
+    /// names are kept as written there too.
+    pub fn canonical_ident(&self, name: &str) -> String {
+        match self {
+            DialectKind::Postgres => match name.strip_prefix('"').and_then(|n| n.strip_suffix('"'))
+            {
+                Some(inner) => inner.replace("\"\"", "\""),
+                None => name.to_string(),
+            },
+            DialectKind::Mysql => match name.strip_prefix('`').and_then(|n| n.strip_suffix('`')) {
+                Some(inner) => inner.replace("``", "`"),
+                None => name.to_string(),
+            },
+        }
+    }
 }
 
 /// Describes how migrations should be split, locked, and tracked on a given engine.
@@ -124,6 +146,22 @@ mod tests {
         );
     }
 
+    #[test]
+    fn canonical_ident_keeps_postgres_case() {
+        let pg = DialectKind::Postgres;
+        assert_eq!(pg.canonical_ident("MyApp"), "MyApp");
+        assert_eq!(pg.canonical_ident("public"), "public");
+        assert_eq!(pg.canonical_ident(r#""MyApp""#), "MyApp");
+        assert_eq!(pg.canonical_ident(r#""a""b""#), r#"a"b"#);
+    }
+
+    #[test]
+    fn canonical_ident_keeps_mysql_case() {
+        let my = DialectKind::Mysql;
+        assert_eq!(my.canonical_ident("MyApp"), "MyApp");
+        assert_eq!(my.canonical_ident("`MyApp`"), "MyApp");
+    }
+
     #[test]
     fn from_url_returns_none_for_kv_or_unknown() {
         assert_eq!(DialectKind::from_url("host=localhost user=postgres"), None);
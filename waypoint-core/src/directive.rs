@@ -20,6 +20,22 @@ pub struct MigrationDirectives {
     pub ensure: Vec<String>,
     /// Safety override: `-- waypoint:safety-override` bypasses DANGER blocks
     pub safety_override: bool,
+    /// Repeatables only: `-- waypoint:requires-version 12` defers the
+    /// migration until that version has been applied (V prefix is stripped)
+    pub requires_version: Option<String>,
+    /// Role to run the migration as: `-- waypoint:run-as reporting_owner`
+    /// (PostgreSQL, via `SET LOCAL ROLE`)
+    pub run_as: Option<String>,
+    /// Row-count guard: `-- waypoint:max-affected-rows 100000` fails and
+    /// rolls back the migration when a single DML statement affects more rows
+    pub max_affected_rows: Option<String>,
+}
+
+impl MigrationDirectives {
+    /// The `max-affected-rows` limit, if set and a valid count.
+    pub fn row_limit(&self) -> Option<u64> {
+        self.max_affected_rows.as_deref()?.parse().ok()
+    }
 }
 
 /// Strip a directive prefix, ensuring the prefix is followed by whitespace or end of string.
@@ -82,6 +98,23 @@ pub fn parse_directives(sql: &str) -> MigrationDirectives {
             if !value.is_empty() {
                 directives.ensure.push(value.to_string());
             }
+        } else if let Some(value) =
+            strip_directive_prefix(comment_body, "waypoint:requires-version")
+        {
+            if !value.is_empty() {
+                directives.requires_version =
+                    Some(value.strip_prefix(['V', 'v']).unwrap_or(value).to_string());
+            }
+        } else if let Some(value) = strip_directive_prefix(comment_body, "waypoint:run-as") {
+            if !value.is_empty() {
+                directives.run_as = Some(value.to_string());
+            }
+        } else if let Some(value) =
+            strip_directive_prefix(comment_body, "waypoint:max-affected-rows")
+        {
+            if !value.is_empty() {
+                directives.max_affected_rows = Some(value.replace('_', ""));
+            }
         } else if comment_body.trim() == "waypoint:safety-override" {
             directives.safety_override = true;
         }
@@ -241,4 +274,41 @@ mod tests {
         let d = parse_directives(sql);
         assert_eq!(d.require, vec!["table_exists(\"my-table\")"]);
     }
+
+    #[test]
+    fn test_parse_run_as() {
+        let sql = "-- waypoint:run-as reporting_owner\nCREATE TABLE r();";
+        let d = parse_directives(sql);
+        assert_eq!(d.run_as.as_deref(), Some("reporting_owner"));
+        assert_eq!(
+            parse_directives("-- waypoint:run-as\nSELECT 1;").run_as,
+            None
+        );
+    }
+
+    #[test]
+    fn test_parse_max_affected_rows() {
+        let sql = "-- waypoint:max-affected-rows 100_000\nUPDATE users SET active = true;";
+        let d = parse_directives(sql);
+        assert_eq!(d.max_affected_rows.as_deref(), Some("100000"));
+        assert_eq!(d.row_limit(), Some(100_000));
+        assert_eq!(
+            parse_directives("-- waypoint:max-affected-rows\nSELECT 1;").max_affected_rows,
+            None
+        );
+    }
+
+    #[test]
+    fn test_parse_requires_version() {
+        let sql = "-- waypoint:requires-version V12\nCREATE VIEW v AS SELECT 1;";
+        let d = parse_directives(sql);
+        assert_eq!(d.requires_version.as_deref(), Some("12"));
+        assert!(d.require.is_empty());
+        assert_eq!(
+            parse_directives("-- waypoint:requires-version v12\nSELECT 1;")
+                .requires_version
+                .as_deref(),
+            Some("12")
+        );
+    }
 }
@@ -9,13 +9,84 @@ use mysql_async::Pool;
 
 use crate::dialect::{mysql::MysqlDialect, DatabaseDialect};
 use crate::error::{Result, WaypointError};
-use crate::history::AppliedMigration;
+use crate::history::{AppliedMigration, HistoryMeta, META_TABLE};
 
 /// Build a fully-qualified MySQL-quoted table name (`` `schema`.`table` ``).
 fn fq(schema: &str, table: &str) -> String {
     MysqlDialect.qualified_table(schema, table)
 }
 
+/// Check the [`META_TABLE`] row for `table` on MySQL, then record this
+/// binary in it. As on PostgreSQL, the row never moves to an older layout.
+pub async fn check_history_meta(pool: &Pool, schema: &str, table: &str) -> Result<()> {
+    let meta = fq(schema, META_TABLE);
+    let mut conn = pool.get_conn().await?;
+    conn.query_drop(format!(
+        "CREATE TABLE IF NOT EXISTS {meta} (
+    history_table      VARCHAR(255) PRIMARY KEY,
+    tool_version       VARCHAR(50) NOT NULL,
+    history_version    INT NOT NULL,
+    checksum_algorithm VARCHAR(50) NOT NULL,
+    updated_on         TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP
+)"
+    ))
+    .await?;
+    drop(conn);
+    verify_history_meta(pool, schema, table).await?;
+
+    let mut conn = pool.get_conn().await?;
+    // Assignments run left to right, so history_version is updated last.
+    let current = HistoryMeta::current(table);
+    conn.exec_drop(
+        format!(
+            "INSERT INTO {meta} (history_table, tool_version, history_version, checksum_algorithm) \
+             VALUES (?, ?, ?, ?) \
+             ON DUPLICATE KEY UPDATE \
+             tool_version = IF(history_version <= VALUES(history_version), VALUES(tool_version), tool_version), \
+             checksum_algorithm = IF(history_version <= VALUES(history_version), VALUES(checksum_algorithm), checksum_algorithm), \
+             updated_on = IF(history_version <= VALUES(history_version), CURRENT_TIMESTAMP, updated_on), \
+             history_version = GREATEST(history_version, VALUES(history_version))"
+        ),
+        (
+            current.history_table,
+            current.tool_version,
+            current.history_version,
+            current.checksum_algorithm,
+        ),
+    )
+    .await?;
+    Ok(())
+}
+
+/// Check the [`META_TABLE`] row for `table` on MySQL without writing
+/// anything. A missing table or row passes.
+pub async fn verify_history_meta(pool: &Pool, schema: &str, table: &str) -> Result<()> {
+    if !history_table_exists(pool, schema, META_TABLE).await? {
+        return Ok(());
+    }
+    let mut conn = pool.get_conn().await?;
+    let row: Option<(String, i32, String)> = conn
+        .exec_first(
+            format!(
+                "SELECT tool_version, history_version, checksum_algorithm \
+                 FROM {} WHERE history_table = ?",
+                fq(schema, META_TABLE)
+            ),
+            (table,),
+        )
+        .await?;
+    match row {
+        Some((tool_version, history_version, checksum_algorithm)) => HistoryMeta {
+            history_table: table.to_string(),
+            tool_version,
+            history_version,
+            checksum_algorithm,
+        }
+        .check_compatible(),
+        None => Ok(()),
+    }
+}
+
 /// Check if the history table exists on MySQL.
 pub async fn history_table_exists(pool: &Pool, schema: &str, table: &str) -> Result<bool> {
     let mut conn = pool.get_conn().await?;
@@ -139,6 +210,67 @@ pub async fn insert_applied_migration(
     Ok(())
 }
 
+/// Insert a "started" record (`success = 0`) ahead of a migration and return
+/// its `installed_rank`.
+#[allow(clippy::too_many_arguments)]
+pub async fn insert_started_migration(
+    pool: &Pool,
+    schema: &str,
+    table: &str,
+    version: Option<&str>,
+    description: &str,
+    migration_type: &str,
+    script: &str,
+    checksum: Option<i32>,
+    installed_by: &str,
+) -> Result<i32> {
+    let fq = fq(schema, table);
+    let sql_max = format!("SELECT COALESCE(MAX(installed_rank), 0) + 1 FROM {}", fq);
+    let mut conn = pool.get_conn().await?;
+    let next_rank: i32 = conn.query_first(&sql_max).await?.unwrap_or(1);
+    let insert_sql = format!(
+        "INSERT INTO {} \
+         (installed_rank, version, description, type, script, checksum, \
+          installed_by, execution_time, success) \
+         VALUES (?, ?, ?, ?, ?, ?, ?, 0, 0)",
+        fq
+    );
+    conn.exec_drop(
+        &insert_sql,
+        (
+            next_rank,
+            version,
+            description,
+            migration_type,
+            script,
+            checksum,
+            installed_by,
+        ),
+    )
+    .await?;
+    Ok(next_rank)
+}
+
+/// Record the outcome of a migration previously registered with
+/// [`insert_started_migration`].
+pub async fn finish_migration(
+    pool: &Pool,
+    schema: &str,
+    table: &str,
+    installed_rank: i32,
+    execution_time: i32,
+    success: bool,
+) -> Result<()> {
+    let sql = format!(
+        "UPDATE {} SET execution_time = ?, success = ? WHERE installed_rank = ?",
+        fq(schema, table)
+    );
+    let mut conn = pool.get_conn().await?;
+    conn.exec_drop(&sql, (execution_time, success as i8, installed_rank))
+        .await?;
+    Ok(())
+}
+
 /// Check if the history table has any entries.
 pub async fn has_entries(pool: &Pool, schema: &str, table: &str) -> Result<bool> {
     let sql = format!("SELECT 1 FROM {} LIMIT 1", fq(schema, table));
@@ -12,15 +12,18 @@
 use std::collections::HashMap;
 
 use crate::commands::migrate::{
-    should_run_in_environment, GuardAction, MigrateDetail, MigrateReport,
+    pending_repeatables, repeatable_statuses, should_run_in_environment, touched_tables,
+    GuardAction, MigrateDetail, MigrateReport,
 };
 use crate::config::WaypointConfig;
 use crate::db::DbClient;
 use crate::error::{Result, WaypointError};
 use crate::history;
 use crate::hooks::{self, HookType, ResolvedHook};
+use crate::logging;
 use crate::migration::{scan_migrations, MigrationVersion, ResolvedMigration};
 use crate::placeholder::{build_placeholders, replace_placeholders};
+use crate::table_stats;
 
 /// Dialect-aware `require` guard evaluator. Mirrors the PG version but uses
 /// `guard::evaluate_db` so the underlying SQL is dispatched per engine.
@@ -39,14 +42,14 @@ async fn evaluate_require_guards_db(
                 Ok(true) => {}
                 Ok(false) => match config.guards.on_require_fail {
                     crate::guard::OnRequireFail::Skip => {
-                        log::info!(
+                        log::info!(target: logging::MIGRATE,
                             "Guard require failed, skipping migration; script={}, expr={}",
                             migration.script,
                             expr_str
                         );
                         return Ok(GuardAction::Skip);
                     }
-                    crate::guard::OnRequireFail::Warn => log::warn!(
+                    crate::guard::OnRequireFail::Warn => log::warn!(target: logging::MIGRATE,
                         "Guard require failed (continuing); script={}, expr={}",
                         migration.script,
                         expr_str
@@ -140,26 +143,78 @@ pub async fn execute_with_options(
         )));
     }
 
-    let table = &config.migrations.table;
+    if config.migrations.set_search_path {
+        log::debug!(target: logging::MIGRATE, "set_search_path has no effect on MySQL; the connection database is used");
+    }
+    if config.migrations.capture_ddl {
+        log::warn!(target: logging::MIGRATE, "capture_ddl is not supported on MySQL (no event triggers); ignoring");
+    }
+    if config.migrations.heartbeat_secs > 0 {
+        log::warn!(target: logging::MIGRATE, "heartbeat_secs is not supported on MySQL; ignoring");
+    }
 
-    client.acquire_lock(table).await?;
+    let lock = crate::commands::lock::acquire_for_migrate_mysql(client, config).await?;
 
-    let result = run_migrate(client, config, target_version).await;
+    if config.migrations.transfer_ownership_to.is_some() {
+        log::warn!(target: logging::MIGRATE, "transfer_ownership_to is ignored on MySQL, which has no object owners");
+    }
 
-    if let Err(e) = client.release_lock(table).await {
-        log::error!("Failed to release advisory lock: {}", e);
+    // The schema is resolved only for size stats; run_migrate resolves its own.
+    let stats_schema = if config.migrations.collect_table_sizes {
+        client
+            .resolve_schema(&config.migrations.schema)
+            .await
+            .map_err(|e| log::warn!(target: logging::MIGRATE, "Could not resolve schema for table sizes; error={}", e))
+            .ok()
+    } else {
+        None
+    };
+    let sizes_before = match stats_schema {
+        Some(ref schema) => table_stats::snapshot_mysql(client, schema)
+            .await
+            .map_err(|e| log::warn!(target: logging::MIGRATE, "Could not read table sizes before migrate; error={}", e))
+            .ok(),
+        None => None,
+    };
+
+    let mut result = run_migrate(client, config, target_version).await;
+
+    if let Ok(ref mut report) = result {
+        if let (Some(schema), Some(before)) = (&stats_schema, &sizes_before) {
+            match table_stats::snapshot_mysql(client, schema).await {
+                Ok(after) => {
+                    let touched = table_stats::touched_tables(&report.details);
+                    report.table_sizes = table_stats::size_changes(before, &after, &touched);
+                }
+                Err(e) => {
+                    log::warn!(target: logging::MIGRATE, "Could not read table sizes after migrate; error={}", e)
+                }
+            }
+        }
+        report.post_checks =
+            crate::post_check::run_post_checks_db(client, &config.post_checks).await;
+        if config.migrations.rollback_on_post_check_failure
+            && report.post_checks.iter().any(|c| !c.passed)
+        {
+            report.post_check_rollback =
+                crate::post_check::rollback_run_mysql(client, config, &report.details).await;
+        }
+    }
+
+    if let Err(e) = client.release_lock(&lock).await {
+        log::error!(target: logging::LOCK, "Failed to release advisory lock: {}", e);
     }
 
     match &result {
         Ok(report) => {
-            log::info!(
+            log::info!(target: logging::MIGRATE,
                 "Migrate completed (mysql); migrations_applied={}, total_time_ms={}",
                 report.migrations_applied,
                 report.total_time_ms
             );
         }
         Err(e) => {
-            log::error!("Migrate failed (mysql): {}", e);
+            log::error!(target: logging::MIGRATE, "Migrate failed (mysql): {}", e);
         }
     }
 
@@ -176,17 +231,38 @@ async fn run_migrate(
 
     history::create_history_table_db(client, &schema, table).await?;
 
-    if config.migrations.validate_on_migrate {
-        if let Err(e) = crate::commands::validate::execute_db(client, config).await {
-            match &e {
-                WaypointError::ValidationFailed(_) => return Err(e),
-                _ => log::debug!("Validation skipped: {}", e),
-            }
+    // Scan and read history once; validation and planning share this snapshot.
+    let resolved = scan_migrations(&config.migrations.locations)?;
+    let mut applied = history::get_applied_migrations_db(client, &schema, table).await?;
+    let mut repaired = Vec::new();
+    if config.migrations.dev {
+        repaired = crate::commands::repair::auto_repair_checksums_db(
+            client, &schema, table, &applied, &resolved,
+        )
+        .await?;
+        if !repaired.is_empty() {
+            applied = history::get_applied_migrations_db(client, &schema, table).await?;
         }
     }
 
-    if config.preflight.enabled {
-        let report = crate::preflight::run_preflight_db(client, &config.preflight).await?;
+    let mut validation_warnings = if config.migrations.validate_on_migrate {
+        crate::commands::validate::validate_loaded(&applied, &resolved, config.migrations.strict)?
+            .warnings
+    } else {
+        Vec::new()
+    };
+    validation_warnings.splice(0..0, repaired);
+
+    // Windows and freezes are policy, not health: they hold even when the
+    // health checks are skipped.
+    let report = if config.preflight.enabled {
+        Some(crate::preflight::run_preflight_db(client, &config.preflight).await?)
+    } else if config.preflight.has_policy() {
+        Some(crate::preflight::run_policy_checks_db(client, &config.preflight).await?)
+    } else {
+        None
+    };
+    if let Some(report) = report {
         if !report.passed {
             let failed_checks: Vec<String> = report
                 .checks
@@ -200,9 +276,6 @@ async fn run_migrate(
         }
     }
 
-    let resolved = scan_migrations(&config.migrations.locations)?;
-    let applied = history::get_applied_migrations_db(client, &schema, table).await?;
-
     let mut all_hooks: Vec<ResolvedHook> = hooks::scan_hooks(&config.migrations.locations)?;
     let config_hooks = hooks::load_config_hooks(&config.hooks)?;
     all_hooks.extend(config_hooks);
@@ -222,7 +295,11 @@ async fn run_migrate(
         .unwrap_or(&db_user)
         .to_string();
 
-    let target = target_version.map(MigrationVersion::parse).transpose()?;
+    // `--target` wins over `migrations.target` from config.
+    let target = target_version
+        .or(config.migrations.target.as_deref())
+        .map(MigrationVersion::parse)
+        .transpose()?;
     let baseline_version = applied
         .iter()
         .find(|a| a.migration_type == "BASELINE")
@@ -281,28 +358,20 @@ async fn run_migrate(
         })
         .collect();
 
-    let pending_repeatables: Vec<&ResolvedMigration> = resolved
-        .iter()
-        .filter(|m| {
-            if m.version().is_some() || m.is_undo() {
-                return false;
-            }
-            if !should_run_in_environment(&m.directives, current_env) {
-                return false;
-            }
-            match applied_scripts.get(&m.script) {
-                None => true,
-                Some(prev) => prev != &Some(m.checksum),
-            }
-        })
-        .collect();
-
     let mut report = MigrateReport {
+        report_version: crate::REPORT_VERSION,
         migrations_applied: 0,
         total_time_ms: 0,
         details: Vec::new(),
         hooks_executed: 0,
         hooks_time_ms: 0,
+        post_checks: Vec::new(),
+        post_check_rollback: None,
+        table_sizes: Vec::new(),
+        captured_ddl: Vec::new(),
+        ownership_transfer: None,
+        validation_warnings,
+        repeatables: Vec::new(),
     };
 
     // `pending_versioned` isn't used again after this — move it in and sort
@@ -310,6 +379,46 @@ async fn run_migrate(
     let mut sorted_versioned = pending_versioned;
     sorted_versioned.sort_by(|a, b| a.version().unwrap().cmp(b.version().unwrap()));
 
+    let repeatables: Vec<&ResolvedMigration> = resolved
+        .iter()
+        .filter(|m| m.version().is_none() && !m.is_undo())
+        .filter(|m| should_run_in_environment(&m.directives, current_env))
+        .collect();
+    let available: Vec<MigrationVersion> = effective_versions
+        .iter()
+        .filter_map(|v| MigrationVersion::parse(v).ok())
+        .chain(sorted_versioned.iter().filter_map(|m| m.version().cloned()))
+        .collect();
+    report.repeatables = repeatable_statuses(
+        repeatables.iter().copied(),
+        &applied_scripts,
+        &available,
+        baseline_version.as_ref(),
+    )?;
+    let pending_repeatables = pending_repeatables(&repeatables, &report.repeatables);
+
+    if let Some(m) = sorted_versioned
+        .iter()
+        .chain(pending_repeatables.iter())
+        .find(|m| m.directives.run_as.is_some())
+    {
+        return Err(WaypointError::InvalidDirective {
+            script: m.script.clone(),
+            reason: "waypoint:run-as is only supported on PostgreSQL".to_string(),
+        });
+    }
+    // MySQL commits DDL implicitly, so an exceeded limit could not be rolled back.
+    if let Some(m) = sorted_versioned
+        .iter()
+        .chain(pending_repeatables.iter())
+        .find(|m| m.directives.max_affected_rows.is_some())
+    {
+        return Err(WaypointError::InvalidDirective {
+            script: m.script.clone(),
+            reason: "waypoint:max-affected-rows is only supported on PostgreSQL".to_string(),
+        });
+    }
+
     let has_pending = !sorted_versioned.is_empty() || !pending_repeatables.is_empty();
 
     if has_pending {
@@ -363,6 +472,7 @@ async fn run_migrate(
             description: m.description.clone(),
             script: m.script.clone(),
             execution_time_ms: elapsed,
+            touched_tables: touched_tables(m, &placeholders),
         });
 
         // ensure guards run AFTER the migration. On MySQL DDL has already
@@ -391,19 +501,19 @@ async fn run_migrate(
                         )
                         .await
                         {
-                            log::warn!(
+                            log::warn!(target: logging::MIGRATE,
                                 "Failed to store reversal SQL; version={}, error={}",
                                 ver.raw,
                                 e
                             );
                         }
                         for w in &result.warnings {
-                            log::warn!("Reversal warning for version {}: {}", ver.raw, w);
+                            log::warn!(target: logging::MIGRATE, "Reversal warning for version {}: {}", ver.raw, w);
                         }
                     }
                 }
                 Err(e) => {
-                    log::warn!("Failed to generate reversal for version {}: {}", ver.raw, e);
+                    log::warn!(target: logging::MIGRATE, "Failed to generate reversal for version {}: {}", ver.raw, e);
                 }
             }
         }
@@ -445,6 +555,7 @@ async fn run_migrate(
             description: m.description.clone(),
             script: m.script.clone(),
             execution_time_ms: elapsed,
+            touched_tables: touched_tables(m, &placeholders),
         });
 
         evaluate_ensure_guards_db(client, &schema, m).await?;
@@ -503,21 +614,17 @@ async fn apply_one(
     placeholders: &HashMap<String, String>,
 ) -> Result<i32> {
     let sql = replace_placeholders(&m.sql, placeholders)?;
-    log::info!("Applying migration; script={}", m.script);
-    let elapsed = client
-        .execute_raw(&sql)
-        .await
-        .map_err(|e| WaypointError::MigrationFailed {
-            script: m.script.clone(),
-            reason: e.to_string(),
-        })?;
+    log::info!(target: logging::MIGRATE, "Applying migration; script={}", m.script);
 
     let migration_type = if m.version().is_some() {
         "SQL"
     } else {
         "SQL_REPEATABLE"
     };
-    history::insert_applied_migration_db(
+
+    // MySQL DDL auto-commits, so every migration is non-transactional: record
+    // a "started" row first so a crash mid-migration leaves evidence behind.
+    let rank = history::insert_started_migration_db(
         client,
         schema,
         table,
@@ -527,10 +634,29 @@ async fn apply_one(
         &m.script,
         Some(m.checksum),
         installed_by,
-        elapsed,
-        true,
     )
     .await?;
 
-    Ok(elapsed)
+    logging::sql(&m.script, &sql);
+    match client.execute_raw(&sql).await {
+        Ok(elapsed) => {
+            history::finish_migration_db(client, schema, table, rank, elapsed, true).await?;
+            Ok(elapsed)
+        }
+        Err(e) => {
+            if let Err(record_err) =
+                history::finish_migration_db(client, schema, table, rank, 0, false).await
+            {
+                log::warn!(target: logging::MIGRATE,
+                    "Failed to record migration failure in history table; script={}, error={}",
+                    m.script,
+                    record_err
+                );
+            }
+            Err(WaypointError::MigrationFailed {
+                script: m.script.clone(),
+                reason: e.to_string(),
+            })
+        }
+    }
 }
@@ -61,6 +61,7 @@ pub async fn analyze(
         .count();
 
     Ok(AdvisorReport {
+        report_version: crate::REPORT_VERSION,
         schema: schema.to_string(),
         advisories,
         warning_count,
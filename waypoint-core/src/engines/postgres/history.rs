@@ -7,10 +7,12 @@ use tokio_postgres::Client;
 
 use crate::db::quote_ident;
 use crate::error::Result;
-use crate::history::AppliedMigration;
+use crate::history::{AppliedMigration, HistoryMeta, META_TABLE};
 
-/// Create the schema history table if it does not exist.
+/// Create the schema history table if it does not exist, after checking its
+/// [`META_TABLE`] row.
 pub async fn create_history_table(client: &Client, schema: &str, table: &str) -> Result<()> {
+    check_history_meta(client, schema, table).await?;
     let fq = format!("{}.{}", quote_ident(schema), quote_ident(table));
     let idx_name = format!("{}_s_idx", table);
     let ver_idx_name = format!("{}_v_idx", table);
@@ -56,6 +58,77 @@ async fn upgrade_history_table(client: &Client, schema: &str, table: &str) -> Re
     Ok(())
 }
 
+/// Check the [`META_TABLE`] row for `table`, then record this binary in it.
+///
+/// The row is never moved back to an older layout, so a concurrent run of an
+/// older binary cannot undo a newer one's upgrade.
+pub async fn check_history_meta(client: &Client, schema: &str, table: &str) -> Result<()> {
+    let fq = format!("{}.{}", quote_ident(schema), quote_ident(META_TABLE));
+    client
+        .batch_execute(&format!(
+            "CREATE TABLE IF NOT EXISTS {fq} (
+    history_table      VARCHAR(255) PRIMARY KEY,
+    tool_version       VARCHAR(50) NOT NULL,
+    history_version    INTEGER NOT NULL,
+    checksum_algorithm VARCHAR(50) NOT NULL,
+    updated_on         TIMESTAMPTZ NOT NULL DEFAULT now()
+)"
+        ))
+        .await?;
+    verify_history_meta(client, schema, table).await?;
+
+    let current = HistoryMeta::current(table);
+    client
+        .execute(
+            &format!(
+                "INSERT INTO {fq} (history_table, tool_version, history_version, checksum_algorithm) \
+                 VALUES ($1, $2, $3, $4) \
+                 ON CONFLICT (history_table) DO UPDATE SET \
+                 tool_version = EXCLUDED.tool_version, \
+                 history_version = EXCLUDED.history_version, \
+                 checksum_algorithm = EXCLUDED.checksum_algorithm, \
+                 updated_on = now() \
+                 WHERE {fq}.history_version <= EXCLUDED.history_version"
+            ),
+            &[
+                &current.history_table,
+                &current.tool_version,
+                &current.history_version,
+                &current.checksum_algorithm,
+            ],
+        )
+        .await?;
+    Ok(())
+}
+
+/// Check the [`META_TABLE`] row for `table` without writing anything, for
+/// commands that only read the history. A missing table or row passes.
+pub async fn verify_history_meta(client: &Client, schema: &str, table: &str) -> Result<()> {
+    if !history_table_exists(client, schema, META_TABLE).await? {
+        return Ok(());
+    }
+    let fq = format!("{}.{}", quote_ident(schema), quote_ident(META_TABLE));
+    let row = client
+        .query_opt(
+            &format!(
+                "SELECT tool_version, history_version, checksum_algorithm \
+                 FROM {fq} WHERE history_table = $1"
+            ),
+            &[&table],
+        )
+        .await?;
+    match row {
+        Some(row) => HistoryMeta {
+            history_table: table.to_string(),
+            tool_version: row.get(0),
+            history_version: row.get(1),
+            checksum_algorithm: row.get(2),
+        }
+        .check_compatible(),
+        None => Ok(()),
+    }
+}
+
 /// Check if the history table exists.
 pub async fn history_table_exists(client: &Client, schema: &str, table: &str) -> Result<bool> {
     let row = client
@@ -157,6 +230,70 @@ pub async fn insert_applied_migration(
     Ok(())
 }
 
+/// Insert a "started" record (`success = FALSE`) ahead of a non-transactional
+/// migration and return its `installed_rank`.
+///
+/// If the process dies mid-migration the row stays behind as a failed entry,
+/// so the half-applied state is visible in `info` and cleared by `repair`.
+#[allow(clippy::too_many_arguments)]
+pub async fn insert_started_migration(
+    client: &Client,
+    schema: &str,
+    table: &str,
+    version: Option<&str>,
+    description: &str,
+    migration_type: &str,
+    script: &str,
+    checksum: Option<i32>,
+    installed_by: &str,
+) -> Result<i32> {
+    let fq = format!("{}.{}", quote_ident(schema), quote_ident(table));
+    let sql = format!(
+        "INSERT INTO {fq} \
+         (installed_rank, version, description, type, script, checksum, installed_by, execution_time, success) \
+         VALUES (\
+            (SELECT COALESCE(MAX(installed_rank), 0) + 1 FROM {fq}), \
+            $1, $2, $3, $4, $5, $6, 0, FALSE\
+         ) RETURNING installed_rank",
+        fq = fq,
+    );
+    let row = client
+        .query_one(
+            &sql,
+            &[
+                &version,
+                &description,
+                &migration_type,
+                &script,
+                &checksum,
+                &installed_by,
+            ],
+        )
+        .await?;
+    Ok(row.get(0))
+}
+
+/// Record the outcome of a migration previously registered with
+/// [`insert_started_migration`].
+pub async fn finish_migration(
+    client: &Client,
+    schema: &str,
+    table: &str,
+    installed_rank: i32,
+    execution_time: i32,
+    success: bool,
+) -> Result<()> {
+    let sql = format!(
+        "UPDATE {}.{} SET execution_time = $1, success = $2 WHERE installed_rank = $3",
+        quote_ident(schema),
+        quote_ident(table)
+    );
+    client
+        .execute(&sql, &[&execution_time, &success, &installed_rank])
+        .await?;
+    Ok(())
+}
+
 /// Delete all failed migration records (success = FALSE).
 pub async fn delete_failed_migrations(client: &Client, schema: &str, table: &str) -> Result<u64> {
     let sql = format!(
@@ -11,15 +11,20 @@ use std::collections::{HashMap, HashSet};
 use tokio_postgres::Client;
 
 use crate::commands::migrate::{
-    should_run_in_environment, GuardAction, MigrateDetail, MigrateReport,
+    pending_repeatables, repeatable_statuses, should_run_in_environment, touched_tables,
+    GuardAction, MigrateDetail, MigrateReport, RepeatableStatus,
 };
 use crate::config::WaypointConfig;
 use crate::db;
+use crate::ddl_capture;
 use crate::error::{Result, WaypointError};
+use crate::heartbeat;
 use crate::history;
 use crate::hooks::{self, HookType, ResolvedHook};
+use crate::logging;
 use crate::migration::{scan_migrations, MigrationVersion, ResolvedMigration};
 use crate::placeholder::{build_placeholders, replace_placeholders};
+use crate::table_stats;
 
 /// Common state prepared by `prepare_migrate()` for both run modes.
 struct MigrateSetup<'a> {
@@ -45,10 +50,15 @@ struct MigrateSetup<'a> {
     applied_scripts: HashMap<String, Option<i32>>,
     /// Current environment from config.
     current_env: Option<&'a str>,
+    /// Warnings from `validate_on_migrate`, surfaced on the report.
+    validation_warnings: Vec<String>,
 }
 
-/// Perform all shared setup: history table creation, validation, preflight,
-/// file scanning, hooks loading, version computation.
+/// Perform all shared setup: history table creation, file scanning, validation,
+/// preflight, hooks loading, version computation.
+///
+/// Files are scanned and history is read exactly once; validation runs against
+/// that same snapshot, so what was validated is what gets planned and applied.
 async fn prepare_migrate<'a>(
     client: &Client,
     config: &'a WaypointConfig,
@@ -59,19 +69,37 @@ async fn prepare_migrate<'a>(
 
     history::create_history_table(client, schema, table).await?;
 
-    if config.migrations.validate_on_migrate {
-        if let Err(e) = crate::commands::validate::execute(client, config).await {
-            match &e {
-                WaypointError::ValidationFailed(_) => return Err(e),
-                _ => {
-                    log::debug!("Validation skipped: {}", e);
-                }
-            }
+    let resolved = scan_migrations(&config.migrations.locations)?;
+    let mut applied = history::get_applied_migrations(client, schema, table).await?;
+    let mut repaired = Vec::new();
+    if config.migrations.dev {
+        repaired = crate::commands::repair::auto_repair_checksums(
+            client, schema, table, &applied, &resolved,
+        )
+        .await?;
+        if !repaired.is_empty() {
+            applied = history::get_applied_migrations(client, schema, table).await?;
         }
     }
 
-    if config.preflight.enabled {
-        let preflight_report = crate::preflight::run_preflight(client, &config.preflight).await?;
+    let mut validation_warnings = if config.migrations.validate_on_migrate {
+        crate::commands::validate::validate_loaded(&applied, &resolved, config.migrations.strict)?
+            .warnings
+    } else {
+        Vec::new()
+    };
+    validation_warnings.splice(0..0, repaired);
+
+    // Windows and freezes are policy, not health: they hold even when the
+    // health checks are skipped.
+    let preflight_report = if config.preflight.enabled {
+        Some(crate::preflight::run_preflight(client, &config.preflight).await?)
+    } else if config.preflight.has_policy() {
+        Some(crate::preflight::run_policy_checks(client, &config.preflight).await?)
+    } else {
+        None
+    };
+    if let Some(preflight_report) = preflight_report {
         if !preflight_report.passed {
             let failed_checks: Vec<String> = preflight_report
                 .checks
@@ -85,14 +113,10 @@ async fn prepare_migrate<'a>(
         }
     }
 
-    let resolved = scan_migrations(&config.migrations.locations)?;
-
     let mut all_hooks: Vec<ResolvedHook> = hooks::scan_hooks(&config.migrations.locations)?;
     let config_hooks = hooks::load_config_hooks(&config.hooks)?;
     all_hooks.extend(config_hooks);
 
-    let applied = history::get_applied_migrations(client, schema, table).await?;
-
     let db_user = db::get_current_user(client)
         .await
         .unwrap_or_else(|_| "unknown".to_string());
@@ -106,7 +130,11 @@ async fn prepare_migrate<'a>(
         .unwrap_or(&db_user)
         .to_string();
 
-    let target = target_version.map(MigrationVersion::parse).transpose()?;
+    // `--target` wins over `migrations.target` from config.
+    let target = target_version
+        .or(config.migrations.target.as_deref())
+        .map(MigrationVersion::parse)
+        .transpose()?;
 
     let baseline_version = applied
         .iter()
@@ -142,9 +170,72 @@ async fn prepare_migrate<'a>(
         highest_applied,
         applied_scripts,
         current_env,
+        validation_warnings,
     })
 }
 
+/// The exact set of migrations a run will apply, decided once while the lock is held.
+struct MigratePlan<'s> {
+    /// Pending versioned migrations, in apply order.
+    versioned: Vec<&'s ResolvedMigration>,
+    /// Pending repeatable migrations (new or checksum changed).
+    repeatables: Vec<&'s ResolvedMigration>,
+    /// Apply decision for every repeatable in scope, including unchanged ones.
+    repeatable_statuses: Vec<RepeatableStatus>,
+}
+
+/// Decide which migrations to apply from the prepared snapshot.
+///
+/// Ordering errors (out-of-order, etc.) surface here, before any hook or
+/// migration has run.
+fn plan_migrate<'s>(
+    setup: &'s MigrateSetup<'_>,
+    config: &WaypointConfig,
+) -> Result<MigratePlan<'s>> {
+    let versioned: Vec<&ResolvedMigration> = setup
+        .resolved
+        .iter()
+        .filter(|m| m.is_versioned())
+        .filter(|m| should_run_in_environment(&m.directives, setup.current_env))
+        .collect();
+
+    let repeatables: Vec<&ResolvedMigration> = setup
+        .resolved
+        .iter()
+        .filter(|m| !m.is_versioned() && !m.is_undo())
+        .filter(|m| should_run_in_environment(&m.directives, setup.current_env))
+        .collect();
+
+    let pending_versioned = filter_pending_versioned(&versioned, setup, config)?;
+    let available: Vec<MigrationVersion> = setup
+        .effective_versions
+        .iter()
+        .filter_map(|v| MigrationVersion::parse(v).ok())
+        .chain(
+            pending_versioned
+                .iter()
+                .filter_map(|m| m.version().cloned()),
+        )
+        .collect();
+    let statuses = repeatable_statuses(
+        repeatables.iter().copied(),
+        &setup.applied_scripts,
+        &available,
+        setup.baseline_version.as_ref(),
+    )?;
+    let plan = MigratePlan {
+        versioned: pending_versioned,
+        repeatables: pending_repeatables(&repeatables, &statuses),
+        repeatable_statuses: statuses,
+    };
+    log::debug!(target: logging::MIGRATE,
+        "Migration plan computed; versioned={}, repeatable={}",
+        plan.versioned.len(),
+        plan.repeatables.len()
+    );
+    Ok(plan)
+}
+
 /// Filter resolved migrations down to pending versioned ones, applying
 /// baseline/target/out-of-order checks.
 fn filter_pending_versioned<'a>(
@@ -162,14 +253,14 @@ fn filter_pending_versioned<'a>(
 
         if let Some(ref bv) = setup.baseline_version {
             if version <= bv {
-                log::debug!("Skipping {} (below baseline)", migration.script);
+                log::debug!(target: logging::MIGRATE, "Skipping {} (below baseline)", migration.script);
                 continue;
             }
         }
 
         if let Some(ref tv) = setup.target {
             if version > tv {
-                log::debug!("Skipping {} (above target {})", migration.script, tv);
+                log::debug!(target: logging::MIGRATE, "Skipping {} (above target {})", migration.script, tv);
                 break;
             }
         }
@@ -190,23 +281,6 @@ fn filter_pending_versioned<'a>(
     Ok(pending)
 }
 
-/// Filter resolved migrations down to pending repeatable ones (checksum changed or new).
-fn filter_pending_repeatables<'a>(
-    repeatables: &[&'a ResolvedMigration],
-    setup: &MigrateSetup<'_>,
-) -> Vec<&'a ResolvedMigration> {
-    let mut pending = Vec::new();
-    for migration in repeatables {
-        if let Some(&applied_checksum) = setup.applied_scripts.get(&migration.script) {
-            if applied_checksum == Some(migration.checksum) {
-                continue;
-            }
-        }
-        pending.push(*migration);
-    }
-    pending
-}
-
 /// Evaluate all `-- waypoint:require` guard preconditions for a migration.
 async fn evaluate_require_guards(
     client: &Client,
@@ -224,14 +298,14 @@ async fn evaluate_require_guards(
                 Ok(true) => {}
                 Ok(false) => match config.guards.on_require_fail {
                     crate::guard::OnRequireFail::Skip => {
-                        log::info!(
+                        log::info!(target: logging::MIGRATE,
                             "Guard require failed, skipping migration; script={}, expr={}",
                             migration.script,
                             expr_str
                         );
                         return Ok(GuardAction::Skip);
                     }
-                    crate::guard::OnRequireFail::Warn => log::warn!(
+                    crate::guard::OnRequireFail::Warn => log::warn!(target: logging::MIGRATE,
                         "Guard require failed (continuing); script={}, expr={}",
                         migration.script,
                         expr_str
@@ -245,7 +319,7 @@ async fn evaluate_require_guards(
                     }
                 },
                 Err(e) => {
-                    log::warn!(
+                    log::warn!(target: logging::MIGRATE,
                         "Guard evaluation error; script={}, expr={}, error={}",
                         migration.script,
                         expr_str,
@@ -325,26 +399,116 @@ pub async fn execute_with_options(
 ) -> Result<MigrateReport> {
     let table = &config.migrations.table;
 
-    db::acquire_advisory_lock(client, table).await?;
+    let lock = crate::commands::lock::acquire_for_migrate(client, config).await?;
+
+    let previous_search_path = if config.migrations.set_search_path {
+        match db::set_search_path(client, &config.migrations.schema).await {
+            Ok(previous) => Some(previous),
+            Err(e) => {
+                if let Err(e) = db::release_advisory_lock(client, &lock).await {
+                    log::error!(target: logging::LOCK, "Failed to release advisory lock: {}", e);
+                }
+                return Err(e);
+            }
+        }
+    } else {
+        None
+    };
 
-    let result = if config.migrations.batch_transaction {
+    let ddl_run = if config.migrations.capture_ddl {
+        match ddl_capture::install(client, &config.migrations.schema, table).await {
+            Ok(run_id) => Some(run_id),
+            Err(e) => {
+                log::warn!(target: logging::MIGRATE,
+                    "Could not install DDL capture event triggers (superuser required), continuing without; error={}",
+                    e
+                );
+                None
+            }
+        }
+    } else {
+        None
+    };
+
+    let sizes_before = if config.migrations.collect_table_sizes {
+        table_stats::snapshot(client, &config.migrations.schema)
+            .await
+            .map_err(|e| log::warn!(target: logging::MIGRATE, "Could not read table sizes before migrate; error={}", e))
+            .ok()
+    } else {
+        None
+    };
+
+    // History is read and the plan is built only inside `prepare_migrate`,
+    // i.e. after the lock is held. A deployer that waited on the lock
+    // therefore sees every row written by the run that held it before, and
+    // never re-applies those migrations. Keep any history reads below this line.
+    let mut result = if config.migrations.batch_transaction {
         run_batch_migrate(client, config, target_version, force).await
     } else {
         run_migrate(client, config, target_version, force).await
     };
 
-    if let Err(e) = db::release_advisory_lock(client, table).await {
-        log::error!("Failed to release advisory lock: {}", e);
+    if let Some(ref run_id) = ddl_run {
+        if let Ok(ref mut report) = result {
+            match ddl_capture::captured(client, &config.migrations.schema, table, run_id).await {
+                Ok(captured) => report.captured_ddl = captured,
+                Err(e) => log::warn!(target: logging::MIGRATE,
+                    "Could not read captured DDL; run_id={}, error={}",
+                    run_id,
+                    e
+                ),
+            }
+        }
+        if let Err(e) = ddl_capture::uninstall(client, &config.migrations.schema).await {
+            log::error!(target: logging::MIGRATE, "Failed to remove DDL capture event triggers: {}", e);
+        }
+    }
+
+    if let Ok(ref mut report) = result {
+        if let Some(ref before) = sizes_before {
+            match table_stats::snapshot(client, &config.migrations.schema).await {
+                Ok(after) => {
+                    let touched = table_stats::touched_tables(&report.details);
+                    report.table_sizes = table_stats::size_changes(before, &after, &touched);
+                }
+                Err(e) => {
+                    log::warn!(target: logging::MIGRATE, "Could not read table sizes after migrate; error={}", e)
+                }
+            }
+        }
+        if let Some(ref role) = config.migrations.transfer_ownership_to {
+            report.ownership_transfer = Some(
+                crate::ownership::transfer(client, &config.migrations.schema, table, role).await,
+            );
+        }
+        report.post_checks = crate::post_check::run_post_checks(client, &config.post_checks).await;
+        if config.migrations.rollback_on_post_check_failure
+            && report.post_checks.iter().any(|c| !c.passed)
+        {
+            report.post_check_rollback =
+                crate::post_check::rollback_run(client, config, &report.details).await;
+        }
+    }
+
+    if let Some(ref previous) = previous_search_path {
+        if let Err(e) = db::restore_search_path(client, previous).await {
+            log::error!(target: logging::MIGRATE, "Failed to restore search_path: {}", e);
+        }
+    }
+
+    if let Err(e) = db::release_advisory_lock(client, &lock).await {
+        log::error!(target: logging::LOCK, "Failed to release advisory lock: {}", e);
     }
 
     match &result {
-        Ok(report) => log::info!(
+        Ok(report) => log::info!(target: logging::MIGRATE,
             "Migrate completed; migrations_applied={}, total_time_ms={}, hooks_executed={}",
             report.migrations_applied,
             report.total_time_ms,
             report.hooks_executed
         ),
-        Err(e) => log::error!("Migrate failed: {}", e),
+        Err(e) => log::error!(target: logging::MIGRATE, "Migrate failed: {}", e),
     }
 
     result
@@ -360,13 +524,22 @@ async fn run_migrate(
     let table = &config.migrations.table;
 
     let setup = prepare_migrate(client, config, target_version).await?;
+    let plan = plan_migrate(&setup, config)?;
 
     let mut report = MigrateReport {
+        report_version: crate::REPORT_VERSION,
         migrations_applied: 0,
         total_time_ms: 0,
         details: Vec::new(),
         hooks_executed: 0,
         hooks_time_ms: 0,
+        post_checks: Vec::new(),
+        post_check_rollback: None,
+        table_sizes: Vec::new(),
+        captured_ddl: Vec::new(),
+        ownership_transfer: None,
+        validation_warnings: setup.validation_warnings.clone(),
+        repeatables: plan.repeatable_statuses.clone(),
     };
 
     let before_placeholders = build_placeholders(
@@ -386,16 +559,7 @@ async fn run_migrate(
     report.hooks_executed += count;
     report.hooks_time_ms += ms;
 
-    let versioned: Vec<&ResolvedMigration> = setup
-        .resolved
-        .iter()
-        .filter(|m| m.is_versioned())
-        .filter(|m| should_run_in_environment(&m.directives, setup.current_env))
-        .collect();
-
-    let pending_versioned = filter_pending_versioned(&versioned, &setup, config)?;
-
-    for migration in &pending_versioned {
+    for migration in &plan.versioned {
         let version = migration.version().unwrap();
 
         let each_placeholders = build_placeholders(
@@ -405,6 +569,9 @@ async fn run_migrate(
             &setup.db_name,
             &migration.script,
         );
+        if config.migrations.capture_ddl {
+            ddl_capture::set_script(client, &migration.script).await?;
+        }
         let (count, ms) = hooks::run_hooks(
             client,
             &setup.all_hooks,
@@ -465,7 +632,7 @@ async fn run_migrate(
         if has_ensure_guards {
             if let Err(guard_err) = evaluate_ensure_guards(client, schema, migration).await {
                 if let Err(rollback_err) = client.batch_execute("ROLLBACK").await {
-                    log::error!(
+                    log::error!(target: logging::MIGRATE,
                         "Failed to rollback after ensure guard failure: {}",
                         rollback_err
                     );
@@ -496,7 +663,7 @@ async fn run_migrate(
                             )
                             .await
                             {
-                                log::warn!(
+                                log::warn!(target: logging::MIGRATE,
                                     "Failed to store reversal SQL; version={}, error={}",
                                     ver.raw,
                                     e
@@ -504,11 +671,11 @@ async fn run_migrate(
                             }
                         }
                         for warning in &result.warnings {
-                            log::warn!("Reversal warning for {}: {}", migration.script, warning);
+                            log::warn!(target: logging::MIGRATE, "Reversal warning for {}: {}", migration.script, warning);
                         }
                     }
                     Err(e) => {
-                        log::warn!(
+                        log::warn!(target: logging::MIGRATE,
                             "Failed to generate reversal; script={}, error={}",
                             migration.script,
                             e
@@ -535,27 +702,11 @@ async fn run_migrate(
             description: migration.description.clone(),
             script: migration.script.clone(),
             execution_time_ms: exec_time,
+            touched_tables: touched_tables(migration, &each_placeholders),
         });
     }
 
-    let repeatables: Vec<&ResolvedMigration> = setup
-        .resolved
-        .iter()
-        .filter(|m| !m.is_versioned() && !m.is_undo())
-        .filter(|m| should_run_in_environment(&m.directives, setup.current_env))
-        .collect();
-
-    for migration in &repeatables {
-        if let Some(&applied_checksum) = setup.applied_scripts.get(&migration.script) {
-            if applied_checksum == Some(migration.checksum) {
-                continue;
-            }
-            log::info!(
-                "Re-applying changed repeatable migration; migration={}",
-                migration.script
-            );
-        }
-
+    for migration in &plan.repeatables {
         let each_placeholders = build_placeholders(
             &config.placeholders,
             schema,
@@ -563,6 +714,9 @@ async fn run_migrate(
             &setup.db_name,
             &migration.script,
         );
+        if config.migrations.capture_ddl {
+            ddl_capture::set_script(client, &migration.script).await?;
+        }
         let (count, ms) = hooks::run_hooks(
             client,
             &setup.all_hooks,
@@ -603,6 +757,7 @@ async fn run_migrate(
             description: migration.description.clone(),
             script: migration.script.clone(),
             execution_time_ms: exec_time,
+            touched_tables: touched_tables(migration, &each_placeholders),
         });
     }
 
@@ -714,25 +869,11 @@ async fn run_batch_migrate(
     let table = &config.migrations.table;
 
     let setup = prepare_migrate(client, config, target_version).await?;
-
-    let current_env = setup.current_env;
-
-    let versioned: Vec<&ResolvedMigration> = setup
-        .resolved
-        .iter()
-        .filter(|m| m.is_versioned())
-        .filter(|m| should_run_in_environment(&m.directives, current_env))
-        .collect();
-
-    let mut pending_versioned = filter_pending_versioned(&versioned, &setup, config)?;
-
-    let repeatables: Vec<&ResolvedMigration> = setup
-        .resolved
-        .iter()
-        .filter(|m| !m.is_versioned() && !m.is_undo())
-        .filter(|m| should_run_in_environment(&m.directives, current_env))
-        .collect();
-    let pending_repeatables = filter_pending_repeatables(&repeatables, &setup);
+    let MigratePlan {
+        versioned: mut pending_versioned,
+        repeatables: pending_repeatables,
+        repeatable_statuses,
+    } = plan_migrate(&setup, config)?;
 
     let placeholders_map = build_placeholders(
         &config.placeholders,
@@ -782,11 +923,19 @@ async fn run_batch_migrate(
     pending_versioned.retain(|m| !skipped_scripts.contains(m.script.as_str()));
 
     let mut report = MigrateReport {
+        report_version: crate::REPORT_VERSION,
         migrations_applied: 0,
         total_time_ms: 0,
         details: Vec::new(),
         hooks_executed: 0,
         hooks_time_ms: 0,
+        post_checks: Vec::new(),
+        post_check_rollback: None,
+        table_sizes: Vec::new(),
+        captured_ddl: Vec::new(),
+        ownership_transfer: None,
+        validation_warnings: setup.validation_warnings.clone(),
+        repeatables: repeatable_statuses,
     };
 
     let before_placeholders = build_placeholders(
@@ -830,7 +979,7 @@ async fn run_batch_migrate(
         match crate::reversal::capture_before(client, schema).await {
             Ok(snap) => Some(snap),
             Err(e) => {
-                log::warn!(
+                log::warn!(target: logging::MIGRATE,
                     "Failed to capture before-snapshot for batch reversal: {}",
                     e
                 );
@@ -855,6 +1004,9 @@ async fn run_batch_migrate(
                 &setup.db_name,
                 &migration.script,
             );
+            if config.migrations.capture_ddl {
+                ddl_capture::set_script(client, &migration.script).await?;
+            }
 
             let (count, ms) = hooks::run_hooks(
                 client,
@@ -868,12 +1020,11 @@ async fn run_batch_migrate(
 
             let sql = replace_placeholders(&migration.sql, &each_placeholders)?;
             let start = std::time::Instant::now();
-            client
-                .batch_execute(&sql)
+            execute_as(client, config, migration, &sql)
                 .await
-                .map_err(|e| WaypointError::MigrationFailed {
+                .map_err(|reason| WaypointError::MigrationFailed {
                     script: migration.script.clone(),
-                    reason: crate::error::format_db_error(&e),
+                    reason,
                 })?;
             let exec_time = start.elapsed().as_millis() as i32;
 
@@ -911,6 +1062,7 @@ async fn run_batch_migrate(
                 description: migration.description.clone(),
                 script: migration.script.clone(),
                 execution_time_ms: exec_time,
+                touched_tables: touched_tables(migration, &each_placeholders),
             });
         }
 
@@ -922,6 +1074,9 @@ async fn run_batch_migrate(
                 &setup.db_name,
                 &migration.script,
             );
+            if config.migrations.capture_ddl {
+                ddl_capture::set_script(client, &migration.script).await?;
+            }
 
             let (count, ms) = hooks::run_hooks(
                 client,
@@ -935,12 +1090,11 @@ async fn run_batch_migrate(
 
             let sql = replace_placeholders(&migration.sql, &each_placeholders)?;
             let start = std::time::Instant::now();
-            client
-                .batch_execute(&sql)
+            execute_as(client, config, migration, &sql)
                 .await
-                .map_err(|e| WaypointError::MigrationFailed {
+                .map_err(|reason| WaypointError::MigrationFailed {
                     script: migration.script.clone(),
-                    reason: crate::error::format_db_error(&e),
+                    reason,
                 })?;
             let exec_time = start.elapsed().as_millis() as i32;
 
@@ -977,6 +1131,7 @@ async fn run_batch_migrate(
                 description: migration.description.clone(),
                 script: migration.script.clone(),
                 execution_time_ms: exec_time,
+                touched_tables: touched_tables(migration, &each_placeholders),
             });
         }
 
@@ -1011,7 +1166,7 @@ async fn run_batch_migrate(
                                     )
                                     .await
                                     {
-                                        log::warn!(
+                                        log::warn!(target: logging::MIGRATE,
                                             "Failed to store reversal SQL; version={}, error={}",
                                             ver.raw,
                                             e
@@ -1019,7 +1174,7 @@ async fn run_batch_migrate(
                                     }
                                 }
                                 for warning in &result.warnings {
-                                    log::warn!(
+                                    log::warn!(target: logging::MIGRATE,
                                         "Reversal warning for {}: {}",
                                         migration.script,
                                         warning
@@ -1027,7 +1182,7 @@ async fn run_batch_migrate(
                                 }
                             }
                             Err(e) => {
-                                log::warn!(
+                                log::warn!(target: logging::MIGRATE,
                                     "Failed to generate reversal; script={}, error={}",
                                     migration.script,
                                     e
@@ -1040,9 +1195,9 @@ async fn run_batch_migrate(
         }
         Err(e) => {
             if let Err(rollback_err) = client.batch_execute("ROLLBACK").await {
-                log::error!("Failed to rollback batch transaction: {}", rollback_err);
+                log::error!(target: logging::MIGRATE, "Failed to rollback batch transaction: {}", rollback_err);
             }
-            log::error!("Batch migration failed, all changes rolled back: {}", e);
+            log::error!(target: logging::MIGRATE, "Batch migration failed, all changes rolled back: {}", e);
             return Err(e);
         }
     }
@@ -1068,6 +1223,10 @@ async fn run_batch_migrate(
 }
 
 /// Apply a single migration within a transaction.
+///
+/// The history INSERT runs inside the same transaction as the migration SQL,
+/// before COMMIT, so there is no window in which a migration is committed but
+/// unrecorded. A failed INSERT rolls the migration back with it.
 #[allow(clippy::too_many_arguments)]
 async fn apply_migration(
     client: &Client,
@@ -1080,7 +1239,7 @@ async fn apply_migration(
     db_name: &str,
     hold_transaction: bool,
 ) -> Result<i32> {
-    log::info!(
+    log::info!(target: logging::MIGRATE,
         "Applying migration; migration={}, schema={}",
         migration.script,
         schema
@@ -1099,10 +1258,41 @@ async fn apply_migration(
     let version_str = migration.version().map(|v| v.raw.as_str());
     let type_str = migration.migration_type().to_string();
 
+    if requires_no_transaction(&sql) {
+        if migration.directives.max_affected_rows.is_some() {
+            return Err(WaypointError::InvalidDirective {
+                script: migration.script.clone(),
+                reason: "waypoint:max-affected-rows needs a transaction to roll back, \
+                         but this migration must run outside one"
+                    .to_string(),
+            });
+        }
+        if hold_transaction {
+            return Err(WaypointError::InvalidDirective {
+                script: migration.script.clone(),
+                reason: "waypoint:ensure needs a transaction to roll back, \
+                         but this migration must run outside one"
+                    .to_string(),
+            });
+        }
+        let _heartbeat = heartbeat::start(client, config, &migration.script).await;
+        return apply_migration_no_transaction(
+            client,
+            migration,
+            &sql,
+            schema,
+            table,
+            version_str,
+            &type_str,
+            installed_by,
+        )
+        .await;
+    }
+
     let start = std::time::Instant::now();
     client.batch_execute("BEGIN").await?;
 
-    match client.batch_execute(&sql).await {
+    match execute_as(client, config, migration, &sql).await {
         Ok(()) => {
             let exec_time = start.elapsed().as_millis() as i32;
             match history::insert_applied_migration(
@@ -1128,15 +1318,15 @@ async fn apply_migration(
                 }
                 Err(e) => {
                     if let Err(rollback_err) = client.batch_execute("ROLLBACK").await {
-                        log::error!("Failed to rollback transaction: {}", rollback_err);
+                        log::error!(target: logging::MIGRATE, "Failed to rollback transaction: {}", rollback_err);
                     }
                     Err(e)
                 }
             }
         }
-        Err(e) => {
+        Err(reason) => {
             if let Err(rollback_err) = client.batch_execute("ROLLBACK").await {
-                log::error!("Failed to rollback transaction: {}", rollback_err);
+                log::error!(target: logging::MIGRATE, "Failed to rollback transaction: {}", rollback_err);
             }
 
             if let Err(record_err) = history::insert_applied_migration(
@@ -1154,7 +1344,184 @@ async fn apply_migration(
             )
             .await
             {
-                log::warn!(
+                log::warn!(target: logging::MIGRATE,
+                    "Failed to record migration failure in history table; script={}, error={}",
+                    migration.script,
+                    record_err
+                );
+            }
+
+            log::error!(target: logging::MIGRATE,
+                "Migration failed; script={}, reason={}",
+                migration.script,
+                reason
+            );
+            Err(WaypointError::MigrationFailed {
+                script: migration.script.clone(),
+                reason,
+            })
+        }
+    }
+}
+
+/// Run migration SQL inside the caller's transaction, as the role named by
+/// `-- waypoint:run-as` if any. The role is reset afterwards so that the
+/// history row is still written by the migrating user.
+///
+/// With `-- waypoint:max-affected-rows`, statements are sent one at a time and
+/// any DML statement affecting more rows than the limit fails the migration,
+/// so the caller's ROLLBACK undoes it. Errors are returned as a reason ready
+/// for [`WaypointError::MigrationFailed`].
+async fn execute_as(
+    client: &Client,
+    config: &WaypointConfig,
+    migration: &ResolvedMigration,
+    sql: &str,
+) -> std::result::Result<(), String> {
+    logging::sql(&migration.script, sql);
+    let _heartbeat = heartbeat::start(client, config, &migration.script).await;
+    let db_err = |e: tokio_postgres::Error| crate::error::format_db_error(&e);
+    if let Some(role) = migration.directives.run_as.as_deref() {
+        log::info!(target: logging::MIGRATE,
+            "Running migration as role; script={}, role={}",
+            migration.script,
+            role
+        );
+        client
+            .batch_execute(&format!("SET LOCAL ROLE {}", db::quote_ident(role)))
+            .await
+            .map_err(db_err)?;
+    }
+    match migration.directives.row_limit() {
+        Some(limit) => execute_with_row_limit(client, migration, sql, limit).await?,
+        None => client.batch_execute(sql).await.map_err(db_err)?,
+    }
+    if migration.directives.run_as.is_some() {
+        client.batch_execute("RESET ROLE").await.map_err(db_err)?;
+    }
+    Ok(())
+}
+
+/// Execute `sql` statement by statement, failing as soon as a DML statement
+/// reports more than `limit` affected rows.
+async fn execute_with_row_limit(
+    client: &Client,
+    migration: &ResolvedMigration,
+    sql: &str,
+    limit: u64,
+) -> std::result::Result<(), String> {
+    for statement in crate::sql_parser::split_statements(sql) {
+        let messages = client
+            .simple_query(statement)
+            .await
+            .map_err(|e| crate::error::format_db_error(&e))?;
+        if !crate::sql_parser::is_dml(statement) {
+            continue;
+        }
+        let affected: u64 = messages
+            .iter()
+            .map(|m| match m {
+                tokio_postgres::SimpleQueryMessage::CommandComplete(n) => *n,
+                _ => 0,
+            })
+            .sum();
+        if affected > limit {
+            log::error!(target: logging::MIGRATE,
+                "Statement exceeded max-affected-rows; script={}, affected={}, limit={}",
+                migration.script,
+                affected,
+                limit
+            );
+            let preview: String = statement.trim().chars().take(80).collect();
+            return Err(format!(
+                "statement affected {} rows, above waypoint:max-affected-rows {}: {}",
+                affected, limit, preview
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Whether a migration contains statements PostgreSQL refuses to run inside a
+/// transaction block (`CREATE INDEX CONCURRENTLY`, `VACUUM`, ...).
+pub(crate) fn requires_no_transaction(sql: &str) -> bool {
+    validate_batch_compatible("", sql).is_err()
+}
+
+/// Apply a migration that cannot run inside a transaction.
+///
+/// A "started" row is written to history first and updated once the
+/// statements finish, so a crash part-way through leaves a failed row behind
+/// instead of an untracked, half-applied migration. Statements are sent one
+/// at a time because a multi-statement simple query runs as an implicit
+/// transaction.
+#[allow(clippy::too_many_arguments)]
+async fn apply_migration_no_transaction(
+    client: &Client,
+    migration: &ResolvedMigration,
+    sql: &str,
+    schema: &str,
+    table: &str,
+    version_str: Option<&str>,
+    type_str: &str,
+    installed_by: &str,
+) -> Result<i32> {
+    let rank = history::insert_started_migration(
+        client,
+        schema,
+        table,
+        version_str,
+        &migration.description,
+        type_str,
+        &migration.script,
+        Some(migration.checksum),
+        installed_by,
+    )
+    .await?;
+    log::info!(target: logging::MIGRATE,
+        "Running migration outside a transaction; script={}, installed_rank={}",
+        migration.script,
+        rank
+    );
+
+    let start = std::time::Instant::now();
+    let run_as = migration.directives.run_as.as_deref();
+    let mut outcome = match run_as {
+        // No transaction to scope the role to, so it is set for the session
+        // and reset below.
+        Some(role) => {
+            client
+                .batch_execute(&format!("SET ROLE {}", db::quote_ident(role)))
+                .await
+        }
+        None => Ok(()),
+    };
+    if outcome.is_ok() {
+        logging::sql(&migration.script, sql);
+        for statement in crate::sql_parser::split_statements(sql) {
+            if let Err(e) = client.batch_execute(statement).await {
+                outcome = Err(e);
+                break;
+            }
+        }
+    }
+    if run_as.is_some() {
+        if let Err(e) = client.batch_execute("RESET ROLE").await {
+            log::error!(target: logging::MIGRATE, "Failed to reset role after migration: {}", e);
+        }
+    }
+    let exec_time = start.elapsed().as_millis() as i32;
+
+    match outcome {
+        Ok(()) => {
+            history::finish_migration(client, schema, table, rank, exec_time, true).await?;
+            Ok(exec_time)
+        }
+        Err(e) => {
+            if let Err(record_err) =
+                history::finish_migration(client, schema, table, rank, exec_time, false).await
+            {
+                log::warn!(target: logging::MIGRATE,
                     "Failed to record migration failure in history table; script={}, error={}",
                     migration.script,
                     record_err
@@ -1162,7 +1529,7 @@ async fn apply_migration(
             }
 
             let reason = crate::error::format_db_error(&e);
-            log::error!(
+            log::error!(target: logging::MIGRATE,
                 "Migration failed; script={}, reason={}",
                 migration.script,
                 reason
@@ -1226,6 +1593,14 @@ mod tests {
         assert!(result.is_err());
     }
 
+    #[test]
+    fn test_requires_no_transaction() {
+        assert!(requires_no_transaction(
+            "CREATE INDEX CONCURRENTLY idx_a ON a (id);"
+        ));
+        assert!(!requires_no_transaction("CREATE INDEX idx_a ON a (id);"));
+    }
+
     #[test]
     fn test_batch_compatible_normal_ddl() {
         let sql =
@@ -171,6 +171,7 @@ pub async fn analyze_migration(
     all_suggestions.dedup();
 
     Ok(SafetyReport {
+        report_version: crate::REPORT_VERSION,
         script: script.to_string(),
         overall_verdict: worst_verdict,
         statements,
@@ -34,6 +34,37 @@ pub fn format_db_error(e: &tokio_postgres::Error) -> String {
     msg
 }
 
+/// Root cause of a failed connection, used to pick a targeted hint.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConnectionErrorKind {
+    /// The host name could not be resolved.
+    Dns,
+    /// The TLS handshake failed.
+    Tls,
+    /// The server rejected the credentials.
+    Auth,
+    /// The connection attempt did not complete in time.
+    Timeout,
+    /// Nothing accepted the connection on the target host/port.
+    Refused,
+    /// Any other connection failure.
+    Other,
+}
+
+impl std::fmt::Display for ConnectionErrorKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let s = match self {
+            ConnectionErrorKind::Dns => "DNS resolution failed",
+            ConnectionErrorKind::Tls => "TLS handshake failed",
+            ConnectionErrorKind::Auth => "authentication failed",
+            ConnectionErrorKind::Timeout => "timed out",
+            ConnectionErrorKind::Refused => "connection refused",
+            ConnectionErrorKind::Other => "connection error",
+        };
+        f.write_str(s)
+    }
+}
+
 /// All error types that Waypoint operations can produce.
 #[derive(Error, Debug)]
 pub enum WaypointError {
@@ -184,6 +215,15 @@ pub enum WaypointError {
     #[error("Pre-flight checks failed: {checks}")]
     PreflightFailed { checks: String },
 
+    /// Migrations succeeded but one or more `[[post_checks]]` smoke tests failed.
+    /// `rolled_back` is true when the run was then undone in full.
+    #[error("Post-migrate checks failed ({count}): {details}")]
+    PostCheckFailed {
+        count: usize,
+        details: String,
+        rolled_back: bool,
+    },
+
     /// A guard precondition or postcondition check failed.
     #[error("Guard {kind} failed for {script}: {expression}")]
     GuardFailed {
@@ -211,6 +251,23 @@ pub enum WaypointError {
     /// The database connection was lost during an operation.
     #[error("Connection lost during {operation}: {detail}")]
     ConnectionLost { operation: String, detail: String },
+
+    /// The history was written in a layout or with a checksum algorithm this
+    /// binary does not support.
+    #[error("Incompatible schema history: {reason}")]
+    IncompatibleHistory { reason: String },
+
+    /// Input would have been read from the terminal, but `--no-input` is set.
+    #[error("Input required: {what} would be read from the terminal")]
+    InputRequired { what: String },
+
+    /// Connecting to the database failed, classified by root cause.
+    #[error("Failed to connect after {attempts} attempt(s) ({kind}): {reason}")]
+    ConnectionFailed {
+        kind: ConnectionErrorKind,
+        attempts: u32,
+        reason: String,
+    },
 }
 
 /// Convenience type alias for `Result<T, WaypointError>`.
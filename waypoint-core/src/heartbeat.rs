@@ -0,0 +1,109 @@
+//! Heartbeat logging for long-running migration SQL (PostgreSQL).
+//!
+//! With `heartbeat_secs` set, a background task logs a line every interval
+//! while a migration's SQL is still executing. From the first heartbeat on,
+//! it also observes the migrating session through a [`Monitor`] connection:
+//! its state, wait event, the sessions blocking it, the locks it is waiting
+//! for, index build progress, and the statement it is on. A session that is
+//! `active` with no wait event is working; one waiting on a `Lock` behind
+//! another PID is stuck.
+//!
+//! Migrations that finish within one interval never open the second
+//! connection.
+
+use std::time::Duration;
+
+use tokio::time::Instant;
+use tokio_postgres::Client;
+
+use crate::config::WaypointConfig;
+use crate::logging;
+use crate::monitor::Monitor;
+
+/// A running heartbeat; stops when dropped.
+pub(crate) struct Heartbeat {
+    task: tokio::task::JoinHandle<()>,
+}
+
+impl Drop for Heartbeat {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+/// Start logging heartbeats for `script`, running on `client`. Returns `None`
+/// when heartbeats are off or the session cannot be identified.
+pub(crate) async fn start(
+    client: &Client,
+    config: &WaypointConfig,
+    script: &str,
+) -> Option<Heartbeat> {
+    let secs = config.migrations.heartbeat_secs;
+    if secs == 0 {
+        return None;
+    }
+    let pid: i32 = match client.query_one("SELECT pg_backend_pid()", &[]).await {
+        Ok(row) => row.get(0),
+        Err(e) => {
+            log::warn!(
+                target: logging::MIGRATE,
+                "Heartbeat disabled, could not read backend PID; script={}, error={}",
+                script,
+                e
+            );
+            return None;
+        }
+    };
+
+    let period = Duration::from_secs(u64::from(secs));
+    let config = config.clone();
+    let script = script.to_string();
+
+    let task = tokio::spawn(async move {
+        let started = Instant::now();
+        let mut ticks = tokio::time::interval_at(started + period, period);
+        let mut monitor: Option<Monitor> = None;
+        let mut connect_failed = false;
+
+        loop {
+            ticks.tick().await;
+            let elapsed = started.elapsed().as_secs();
+
+            if monitor.is_none() && !connect_failed {
+                match Monitor::connect(&config).await {
+                    Ok(m) => monitor = Some(m),
+                    Err(e) => {
+                        connect_failed = true;
+                        log::warn!(
+                            target: logging::MIGRATE,
+                            "Heartbeat cannot open a monitoring connection, logging elapsed time only; error={}",
+                            e
+                        );
+                    }
+                }
+            }
+
+            let activity = match &monitor {
+                Some(m) => m.observe(pid).await.ok().flatten(),
+                None => None,
+            };
+            match activity {
+                Some(activity) => log::info!(
+                    target: logging::MIGRATE,
+                    "Migration still running; script={}, elapsed_secs={}, {}",
+                    script,
+                    elapsed,
+                    activity
+                ),
+                None => log::info!(
+                    target: logging::MIGRATE,
+                    "Migration still running; script={}, elapsed_secs={}, pid={}",
+                    script,
+                    elapsed,
+                    pid
+                ),
+            }
+        }
+    });
+    Some(Heartbeat { task })
+}
@@ -10,6 +10,12 @@
 //! [`crate::engines::postgres::history`] for back-compat — code that
 //! previously called `crate::history::create_history_table(&Client, …)`
 //! keeps working unchanged.
+//!
+//! Next to the history tables, [`META_TABLE`] holds one [`HistoryMeta`] row
+//! per history table: the layout version and checksum algorithm it was
+//! written with, and the last waypoint version that wrote to it. Every
+//! command that writes history checks the row first, so an older binary in
+//! a mixed-version rollout refuses to touch a history it would misread.
 
 use chrono::{DateTime, Utc};
 
@@ -43,6 +49,69 @@ pub struct AppliedMigration {
     pub reversal_sql: Option<String>,
 }
 
+/// Table holding one [`HistoryMeta`] row per history table in the schema.
+pub const META_TABLE: &str = "waypoint_meta";
+
+/// Layout of the history table this binary reads and writes. Bump it with
+/// any change an older binary would misread or corrupt.
+pub const HISTORY_VERSION: i32 = 1;
+
+/// Algorithm behind the `checksum` column.
+pub const CHECKSUM_ALGORITHM: &str = "crc32";
+
+/// A row of [`META_TABLE`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HistoryMeta {
+    /// History table the row describes.
+    pub history_table: String,
+    /// Last waypoint version that wrote to the history table.
+    pub tool_version: String,
+    /// Layout version of the history table.
+    pub history_version: i32,
+    /// Algorithm behind the `checksum` column.
+    pub checksum_algorithm: String,
+}
+
+impl HistoryMeta {
+    /// The row this binary writes for `history_table`.
+    pub fn current(history_table: &str) -> Self {
+        HistoryMeta {
+            history_table: history_table.to_string(),
+            tool_version: env!("CARGO_PKG_VERSION").to_string(),
+            history_version: HISTORY_VERSION,
+            checksum_algorithm: CHECKSUM_ALGORITHM.to_string(),
+        }
+    }
+
+    /// Fail if this binary must not write to a history described by `self`.
+    pub fn check_compatible(&self) -> Result<()> {
+        if self.history_version > HISTORY_VERSION {
+            return Err(WaypointError::IncompatibleHistory {
+                reason: format!(
+                    "'{}' uses history layout {} (written by waypoint {}), but this is waypoint {}, which supports layout {} at most",
+                    self.history_table,
+                    self.history_version,
+                    self.tool_version,
+                    env!("CARGO_PKG_VERSION"),
+                    HISTORY_VERSION
+                ),
+            });
+        }
+        if self.checksum_algorithm != CHECKSUM_ALGORITHM {
+            return Err(WaypointError::IncompatibleHistory {
+                reason: format!(
+                    "'{}' records {} checksums (written by waypoint {}), but this binary computes {}",
+                    self.history_table,
+                    self.checksum_algorithm,
+                    self.tool_version,
+                    CHECKSUM_ALGORITHM
+                ),
+            });
+        }
+        Ok(())
+    }
+}
+
 // ── Re-exports of the legacy PG-only entry points ────────────────────────────
 //
 // External callers expect these names at `crate::history::*`. They live in
@@ -51,15 +120,17 @@ pub struct AppliedMigration {
 
 #[cfg(feature = "postgres")]
 pub use crate::engines::postgres::history::{
-    create_history_table, delete_failed_migrations, get_applied_migrations, has_entries,
-    history_table_exists, insert_applied_migration, next_installed_rank, update_checksum,
-    update_repeatable_checksum,
+    check_history_meta, create_history_table, delete_failed_migrations, finish_migration,
+    get_applied_migrations, has_entries, history_table_exists, insert_applied_migration,
+    insert_started_migration, next_installed_rank, update_checksum, update_repeatable_checksum,
+    verify_history_meta,
 };
 
 // ── Dialect-aware dispatchers ────────────────────────────────────────────────
 
 /// Create the schema history table if it does not exist (dialect-aware).
 pub async fn create_history_table_db(client: &DbClient, schema: &str, table: &str) -> Result<()> {
+    check_history_meta_db(client, schema, table).await?;
     let dialect = client.dialect();
     let ddl = dialect.history_table_ddl(schema, table);
     // PG accepts the multi-statement string; MySQL needs per-statement
@@ -109,6 +180,38 @@ fn is_benign_index_dup(e: &WaypointError) -> bool {
     msg.contains("er_dup_keyname") || msg.contains("duplicate key name")
 }
 
+/// Check the [`META_TABLE`] row for `table` and record this binary in it
+/// (dialect-aware). Fails with [`WaypointError::IncompatibleHistory`] when
+/// the history was written in a newer layout or with another checksum
+/// algorithm.
+pub async fn check_history_meta_db(client: &DbClient, schema: &str, table: &str) -> Result<()> {
+    match client {
+        #[cfg(feature = "postgres")]
+        DbClient::Postgres(c) => {
+            crate::engines::postgres::history::check_history_meta(c, schema, table).await
+        }
+        #[cfg(feature = "mysql")]
+        DbClient::Mysql(pool) => {
+            crate::engines::mysql::history::check_history_meta(pool, schema, table).await
+        }
+    }
+}
+
+/// Check the [`META_TABLE`] row for `table` without writing anything
+/// (dialect-aware), for commands that only read the history.
+pub async fn verify_history_meta_db(client: &DbClient, schema: &str, table: &str) -> Result<()> {
+    match client {
+        #[cfg(feature = "postgres")]
+        DbClient::Postgres(c) => {
+            crate::engines::postgres::history::verify_history_meta(c, schema, table).await
+        }
+        #[cfg(feature = "mysql")]
+        DbClient::Mysql(pool) => {
+            crate::engines::mysql::history::verify_history_meta(pool, schema, table).await
+        }
+    }
+}
+
 /// Check if the history table exists (dialect-aware).
 pub async fn history_table_exists_db(client: &DbClient, schema: &str, table: &str) -> Result<bool> {
     match client {
@@ -194,6 +297,90 @@ pub async fn insert_applied_migration_db(
     }
 }
 
+/// Insert a "started" record and return its `installed_rank` (dialect-aware).
+#[allow(clippy::too_many_arguments)]
+pub async fn insert_started_migration_db(
+    client: &DbClient,
+    schema: &str,
+    table: &str,
+    version: Option<&str>,
+    description: &str,
+    migration_type: &str,
+    script: &str,
+    checksum: Option<i32>,
+    installed_by: &str,
+) -> Result<i32> {
+    match client {
+        #[cfg(feature = "postgres")]
+        DbClient::Postgres(c) => {
+            crate::engines::postgres::history::insert_started_migration(
+                c,
+                schema,
+                table,
+                version,
+                description,
+                migration_type,
+                script,
+                checksum,
+                installed_by,
+            )
+            .await
+        }
+        #[cfg(feature = "mysql")]
+        DbClient::Mysql(pool) => {
+            crate::engines::mysql::history::insert_started_migration(
+                pool,
+                schema,
+                table,
+                version,
+                description,
+                migration_type,
+                script,
+                checksum,
+                installed_by,
+            )
+            .await
+        }
+    }
+}
+
+/// Record the outcome of a "started" record (dialect-aware).
+pub async fn finish_migration_db(
+    client: &DbClient,
+    schema: &str,
+    table: &str,
+    installed_rank: i32,
+    execution_time: i32,
+    success: bool,
+) -> Result<()> {
+    match client {
+        #[cfg(feature = "postgres")]
+        DbClient::Postgres(c) => {
+            crate::engines::postgres::history::finish_migration(
+                c,
+                schema,
+                table,
+                installed_rank,
+                execution_time,
+                success,
+            )
+            .await
+        }
+        #[cfg(feature = "mysql")]
+        DbClient::Mysql(pool) => {
+            crate::engines::mysql::history::finish_migration(
+                pool,
+                schema,
+                table,
+                installed_rank,
+                execution_time,
+                success,
+            )
+            .await
+        }
+    }
+}
+
 /// Check if the history table has any entries (dialect-aware).
 pub async fn has_entries_db(client: &DbClient, schema: &str, table: &str) -> Result<bool> {
     match client {
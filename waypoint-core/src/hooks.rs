@@ -12,6 +12,7 @@ use crate::config::HooksConfig;
 use crate::db;
 use crate::db::DbClient;
 use crate::error::{Result, WaypointError};
+use crate::logging;
 use crate::placeholder::replace_placeholders;
 
 /// The phase at which a hook runs.
@@ -193,9 +194,10 @@ pub async fn run_hooks(
     let mut count = 0;
 
     for hook in hooks.iter().filter(|h| &h.hook_type == phase) {
-        log::info!("Running {} hook: {}", phase, hook.script_name);
+        log::info!(target: logging::HOOKS, "Running {} hook: {}", phase, hook.script_name);
 
         let sql = replace_placeholders(&hook.sql, placeholders)?;
+        logging::sql(&hook.script_name, &sql);
 
         match db::execute_in_transaction(client, &sql).await {
             Ok(exec_time) => {
@@ -235,9 +237,10 @@ pub async fn run_hooks_db(
     let mut count = 0;
 
     for hook in hooks.iter().filter(|h| &h.hook_type == phase) {
-        log::info!("Running {} hook: {}", phase, hook.script_name);
+        log::info!(target: logging::HOOKS, "Running {} hook: {}", phase, hook.script_name);
 
         let sql = replace_placeholders(&hook.sql, placeholders)?;
+        logging::sql(&hook.script_name, &sql);
 
         let exec_result = match client.dialect_kind() {
             crate::dialect::DialectKind::Postgres => client.execute_in_transaction(&sql).await,
@@ -11,7 +11,7 @@
 //! use waypoint_core::Waypoint;
 //!
 //! # async fn run() -> Result<(), Box<dyn std::error::Error>> {
-//! let config = WaypointConfig::load(None, None)?;
+//! let config = WaypointConfig::load(None, &Default::default())?;
 //! let wp = Waypoint::new(config).await?;
 //! let report = wp.migrate(None).await?;
 //! println!("Applied {} migrations", report.migrations_applied);
@@ -26,10 +26,14 @@
 //! - [`migration`] — Migration file parsing and scanning
 //! - [`db`] — Database connections, TLS, advisory locks
 //! - [`history`] — Schema history table operations
+//! - [`heartbeat`] — Progress heartbeats for long-running migration SQL (PostgreSQL)
+//! - [`monitor`] — Observe the migrating session from a second connection (PostgreSQL)
 //! - [`commands`] — Individual command implementations
 //! - [`checksum`] — CRC32 checksums (Flyway-compatible)
 //! - [`placeholder`] — `${key}` placeholder replacement in SQL
 //! - [`hooks`] — SQL callback hooks (before/after migrate)
+//! - [`lineage`] — OpenLineage run events for migrate
+//! - [`logging`] — Log targets and SQL log redaction
 //! - [`directive`] — `-- waypoint:*` comment directive parsing
 //! - [`guard`] — Guard expression parser and evaluator for pre/post conditions
 //! - [`sql_parser`] — Regex-based DDL extraction
@@ -37,7 +41,14 @@
 //! - [`schema`] — Schema introspection + diff
 //! - [`dependency`] — Migration dependency graph
 //! - [`preflight`] — Pre-migration health checks
+//! - [`post_check`] — Smoke-test queries run after migrate
+//! - [`ddl_capture`] — Event-trigger DDL audit during migrate (PostgreSQL)
+//! - [`table_stats`] — Table size deltas around a migrate run
+//! - [`ownership`] — Post-migrate ownership transfer (PostgreSQL)
+//! - [`messages`] — Message catalog for localized CLI hints and summaries
 //! - [`multi`] — Multi-database orchestration
+//! - `render` — Terminal tables and summaries used by the CLI (`render` feature)
+//! - [`testing`] — Template-cloned databases for fast integration tests
 //! - [`error`] — Error types
 
 pub mod advisor;
@@ -45,22 +56,37 @@ pub mod checksum;
 pub mod commands;
 pub mod config;
 pub mod db;
+pub mod ddl_capture;
 pub mod dependency;
 pub mod dialect;
 pub mod directive;
 pub mod engines;
 pub mod error;
 pub mod guard;
+#[cfg(feature = "postgres")]
+pub mod heartbeat;
 pub mod history;
 pub mod hooks;
+pub mod lineage;
+pub mod logging;
+pub mod messages;
 pub mod migration;
+#[cfg(feature = "postgres")]
+pub mod monitor;
 pub mod multi;
+pub mod ownership;
 pub mod placeholder;
+pub mod post_check;
 pub mod preflight;
+#[cfg(feature = "render")]
+pub mod render;
 pub mod reversal;
 pub mod safety;
 pub mod schema;
 pub mod sql_parser;
+pub mod table_stats;
+#[cfg(feature = "postgres")]
+pub mod testing;
 
 use std::path::PathBuf;
 
@@ -77,21 +103,35 @@ pub use commands::check_conflicts::ConflictReport;
 pub use commands::diff::DiffReport;
 pub use commands::drift::DriftReport;
 pub use commands::explain::ExplainReport;
-pub use commands::info::{MigrationInfo, MigrationState};
+pub use commands::import::ImportReport;
+pub use commands::info::{LastApplied, LastAppliedReport, MigrationInfo, MigrationState};
 pub use commands::lint::LintReport;
+pub use commands::lock::LockReport;
 pub use commands::migrate::MigrateReport;
+pub use commands::new::NewReport;
 pub use commands::repair::RepairReport;
 pub use commands::safety::SafetyCommandReport;
 pub use commands::simulate::SimulationReport;
 pub use commands::snapshot::{RestoreReport, SnapshotReport};
+#[cfg(feature = "postgres")]
+pub use commands::template::{CloneReport, ProvisionTemplateReport};
 pub use commands::undo::{UndoReport, UndoTarget};
 pub use commands::validate::ValidateReport;
+#[cfg(feature = "postgres")]
+pub use commands::verify::VerifyReport;
 pub use config::CliOverrides;
 pub use dialect::{DatabaseDialect, DialectKind};
 pub use multi::MultiWaypoint;
 pub use preflight::PreflightReport;
 pub use safety::SafetyReport;
 
+/// Version of the JSON layout of the report structs (`MigrateReport`,
+/// `ValidateReport`, ...), recorded in their `report_version` field.
+///
+/// Bumped when a field is removed or changes meaning; adding fields does not
+/// bump it. Reports written before versioning deserialize with version `0`.
+pub const REPORT_VERSION: u32 = 1;
+
 /// Main entry point for the Waypoint library.
 ///
 /// Create a `Waypoint` instance with a config and use its methods to
@@ -195,6 +235,12 @@ impl Waypoint {
         commands::info::execute_db(&self.client, &self.config).await
     }
 
+    /// The most recent successful migration, for checking that deploys are
+    /// still arriving. `None` until something has been applied.
+    pub async fn last_applied(&self) -> Result<Option<LastApplied>> {
+        commands::info::last_applied_db(&self.client, &self.config).await
+    }
+
     /// Validate applied migrations against local files.
     pub async fn validate(&self) -> Result<ValidateReport> {
         commands::validate::execute_db(&self.client, &self.config).await
@@ -220,6 +266,16 @@ impl Waypoint {
         commands::clean::execute_db(&self.client, &self.config, allow_clean).await
     }
 
+    /// Show which session holds the migration lock.
+    pub async fn lock_status(&self) -> Result<LockReport> {
+        commands::lock::status_db(&self.client, &self.config).await
+    }
+
+    /// Release the migration lock, terminating the holding session if `force`.
+    pub async fn lock_release(&self, force: bool) -> Result<LockReport> {
+        commands::lock::release_db(&self.client, &self.config, force).await
+    }
+
     /// Run lint on migration files (no DB required).
     pub fn lint(locations: &[PathBuf], disabled_rules: &[String]) -> Result<LintReport> {
         commands::lint::execute(locations, disabled_rules)
@@ -296,6 +352,31 @@ impl Waypoint {
     pub async fn simulate(&self) -> Result<SimulationReport> {
         commands::simulate::execute_db(&self.client, &self.config).await
     }
+
+    /// Create a database named `name`, migrate it, and mark it as a template
+    /// (PostgreSQL only). The configured connection is used as the maintenance
+    /// connection.
+    #[cfg(feature = "postgres")]
+    pub async fn provision_template(
+        &self,
+        name: &str,
+        replace: bool,
+    ) -> Result<ProvisionTemplateReport> {
+        commands::template::provision(self.client.as_postgres()?, &self.config, name, replace).await
+    }
+
+    /// Create a database named `name` as a copy of `template` (PostgreSQL only).
+    #[cfg(feature = "postgres")]
+    pub async fn clone_from_template(&self, template: &str, name: &str) -> Result<CloneReport> {
+        commands::template::clone_from_template(self.client.as_postgres()?, template, name).await
+    }
+
+    /// Replay applied migrations on a scratch database and compare the result
+    /// with the configured schema (PostgreSQL only).
+    #[cfg(feature = "postgres")]
+    pub async fn verify(&self, shadow_url: &str) -> Result<VerifyReport> {
+        commands::verify::execute(self.client.as_postgres()?, &self.config, shadow_url).await
+    }
 }
 
 /// Connect to whichever backend the URL scheme indicates.
@@ -307,13 +388,14 @@ async fn connect_for_url(
     match kind {
         #[cfg(feature = "postgres")]
         DialectKind::Postgres => {
-            let client = db::connect_with_full_config(
+            let client = db::connect_with_deadline(
                 conn_string,
                 &config.database.ssl_mode,
                 config.database.connect_retries,
                 config.database.connect_timeout_secs,
                 config.database.statement_timeout_secs,
                 config.database.keepalive_secs,
+                config.database.connect_deadline_secs,
             )
             .await?;
             Ok(DbClient::with_postgres(client))
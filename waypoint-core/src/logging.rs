@@ -0,0 +1,112 @@
+//! Log targets and SQL log redaction.
+//!
+//! Core areas log under fixed targets rather than their module path, so a
+//! filter such as `RUST_LOG=info,waypoint::lock=debug` keeps working as code
+//! moves between modules:
+//!
+//! | Target | Covers |
+//! |---|---|
+//! | `waypoint::migrate` | Planning and applying migrations |
+//! | `waypoint::db` | Connecting, retries, TLS, session settings |
+//! | `waypoint::lock` | Advisory lock acquisition, waiting and release |
+//! | `waypoint::hooks` | Callback hooks |
+//! | `waypoint::sql` | The SQL sent for migrations and hooks (debug) |
+//!
+//! SQL is logged only on its own target, with string literals masked and
+//! long scripts truncated, so `--verbose` output stays readable in CI and
+//! never echoes passwords or data embedded in migrations.
+
+/// Planning and applying migrations.
+pub const MIGRATE: &str = "waypoint::migrate";
+/// Connections and session settings.
+pub const DB: &str = "waypoint::db";
+/// The advisory migration lock.
+pub const LOCK: &str = "waypoint::lock";
+/// Callback hooks.
+pub const HOOKS: &str = "waypoint::hooks";
+/// SQL sent to the database for migrations and hooks.
+pub const SQL: &str = "waypoint::sql";
+
+/// Longest SQL excerpt written to the log, in characters.
+const MAX_SQL_CHARS: usize = 2000;
+
+/// Log the SQL about to run for `script` at debug level on [`SQL`].
+pub fn sql(script: &str, sql: &str) {
+    if log::log_enabled!(target: SQL, log::Level::Debug) {
+        log::debug!(target: SQL, "Executing SQL; script={}\n{}", script, redact(sql.trim()));
+    }
+}
+
+/// Mask string literals and truncate `sql` for logging.
+///
+/// Literal contents become `'***'`. Comments and dollar-quoted bodies are kept
+/// as written, though literals inside a function body are masked too.
+pub fn redact(sql: &str) -> String {
+    let mut out = String::with_capacity(sql.len().min(MAX_SQL_CHARS) + 32);
+    let mut chars = sql.chars().peekable();
+    let mut written = 0;
+
+    while let Some(c) = chars.next() {
+        if written >= MAX_SQL_CHARS {
+            let rest = 1 + chars.count();
+            out.push_str(&format!("... ({} more characters)", rest));
+            break;
+        }
+        match c {
+            '\'' => {
+                // Skip to the closing quote; '' is an escaped quote.
+                while let Some(c) = chars.next() {
+                    if c == '\'' {
+                        if chars.peek() == Some(&'\'') {
+                            chars.next();
+                        } else {
+                            break;
+                        }
+                    }
+                }
+                out.push_str("'***'");
+                written += 5;
+            }
+            '-' if chars.peek() == Some(&'-') => {
+                out.push(c);
+                for c in chars.by_ref() {
+                    out.push(c);
+                    written += 1;
+                    if c == '\n' {
+                        break;
+                    }
+                }
+            }
+            _ => {
+                out.push(c);
+                written += 1;
+            }
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_redact_masks_string_literals() {
+        assert_eq!(
+            redact("ALTER ROLE app PASSWORD 's3cr''et'; SELECT 1;"),
+            "ALTER ROLE app PASSWORD '***'; SELECT 1;"
+        );
+        assert_eq!(
+            redact("-- don't mask comments\nINSERT INTO t VALUES ('a', 2);"),
+            "-- don't mask comments\nINSERT INTO t VALUES ('***', 2);"
+        );
+    }
+
+    #[test]
+    fn test_redact_truncates_long_sql() {
+        let sql = "SELECT 1;\n".repeat(1000);
+        let redacted = redact(&sql);
+        assert!(redacted.len() < 2100);
+        assert!(redacted.ends_with("... (8000 more characters)"));
+    }
+}
@@ -0,0 +1,298 @@
+//! Message catalog for user-facing CLI text (hints and summaries).
+//!
+//! Every translatable string has a [`Msg`] key with built-in English text.
+//! [`set_language`] switches the process to another catalog: a built-in one
+//! by language code (`de`), or a TOML file of the same shape as
+//! `locales/de.toml`, where `[hint] config = "..."` defines the key
+//! `hint.config`. Keys missing from a catalog fall back to English, so a
+//! partial translation is usable.
+//!
+//! Placeholders are written `{name}` and filled in by [`tr`]; a catalog entry
+//! must use the same placeholders as the English text.
+
+use std::collections::HashMap;
+use std::fmt::Display;
+use std::sync::OnceLock;
+
+use crate::error::{Result, WaypointError};
+
+macro_rules! messages {
+    ($($variant:ident = $key:literal => $text:literal,)*) => {
+        /// A translatable message. See the module docs.
+        #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+        pub enum Msg {
+            $(
+                #[doc = $text]
+                $variant,
+            )*
+        }
+
+        impl Msg {
+            /// Every message, in declaration order.
+            pub const ALL: &'static [Msg] = &[$(Msg::$variant,)*];
+
+            /// Dotted catalog key, e.g. `hint.config`.
+            pub fn key(self) -> &'static str {
+                match self {
+                    $(Msg::$variant => $key,)*
+                }
+            }
+
+            /// Built-in English text.
+            pub fn english(self) -> &'static str {
+                match self {
+                    $(Msg::$variant => $text,)*
+                }
+            }
+        }
+    };
+}
+
+messages! {
+    LabelError = "label.error" => "ERROR:",
+    LabelWarning = "label.warning" => "WARNING:",
+    LabelHint = "label.hint" => "Hint:",
+
+    HintConfig = "hint.config" => "Check your waypoint.toml or set WAYPOINT_DATABASE_URL environment variable.",
+    HintDatabase = "hint.database" => "Verify database is running and connection details are correct.",
+    HintConnectionDns = "hint.connection_dns" => "Check the host name in your database URL; it could not be resolved.",
+    HintConnectionTls = "hint.connection_tls" => "The server may not support TLS. Try --ssl-mode prefer or --ssl-mode disable.",
+    HintConnectionAuth = "hint.connection_auth" => "Check the database user and password, and that pg_hba.conf allows this client.",
+    HintConnectionTimeout = "hint.connection_timeout" => "The server did not respond in time. Check firewalls, or raise --connect-timeout / --connect-deadline.",
+    HintConnectionRefused = "hint.connection_refused" => "Nothing is listening on that host/port. Verify the database is running and the port is correct.",
+    HintCleanDisabled = "hint.clean_disabled" => "Pass --allow-clean flag or set clean_enabled = true in waypoint.toml.",
+    HintChecksumMismatch = "hint.checksum_mismatch" => "Run 'waypoint repair' to update checksums, or restore the original migration file.",
+    HintOutOfOrder = "hint.out_of_order" => "Use --out-of-order flag to allow out-of-order migrations.",
+    HintUndoMissing = "hint.undo_missing" => "Create a U{version}__<description>.sql file, or enable [reversals] for auto-generated undo.",
+    HintMigrationBlocked = "hint.migration_blocked" => "Use --force to override DANGER blocks, or add '-- waypoint:safety-override' to the migration.",
+    HintGuardFailed = "hint.guard_failed" => "Check guard conditions in your migration directives (-- waypoint:require / -- waypoint:ensure).",
+    HintDriftDetected = "hint.drift_detected" => "Run 'waypoint diff' to generate a migration that resolves this drift.",
+    HintLintFailed = "hint.lint_failed" => "Fix the issues or add rule IDs to [lint] disabled_rules in waypoint.toml.",
+    HintNonTransactional = "hint.non_transactional" => "Remove --transaction to apply migrations individually, or rewrite the migration to avoid CONCURRENTLY/VACUUM/etc.",
+    HintPostCheckRolledBack = "hint.post_check_rolled_back" => "The run was rolled back with its undo scripts. Fix the migrations or checks and migrate again.",
+    HintPostCheckFailed = "hint.post_check_failed" => "The migrations were applied. Investigate the failed checks, then fix forward or run 'waypoint undo'.",
+    HintConnectionLost = "hint.connection_lost" => "Run 'waypoint info' to check the current migration state.",
+    HintPlaceholderNotFound = "hint.placeholder_not_found" => "Define placeholder '{key}' in [placeholders] section of waypoint.toml or as an environment variable.",
+    HintMigrationFailed = "hint.migration_failed" => "Fix the SQL error in '{script}', then run 'waypoint repair' if needed.",
+    HintHookFailed = "hint.hook_failed" => "Check the hook file '{script}' for SQL errors.",
+    HintUndoFailed = "hint.undo_failed" => "Fix the SQL error in undo script '{script}'.",
+    HintValidationFailed = "hint.validation_failed" => "Run 'waypoint validate' for details, then 'waypoint repair' to fix.",
+    HintDependencyCycle = "hint.dependency_cycle" => "Check '-- waypoint:depends' directives for circular references.",
+    HintMissingDependency = "hint.missing_dependency" => "Ensure the referenced migration version exists in your migration locations.",
+    HintInvalidDirective = "hint.invalid_directive" => "Check the '-- waypoint:' directive syntax in the migration file header.",
+    HintPreflightFailed = "hint.preflight_failed" => "Use --skip-preflight to bypass, or resolve the database health issues.",
+    HintConflictsDetected = "hint.conflicts_detected" => "Resolve migration version conflicts between branches before merging.",
+    HintLockError = "hint.lock_error" => "Another migration may be running. Run 'waypoint lock status' to see who holds the lock.",
+    HintLockRelease = "hint.lock_release" => "Run 'waypoint lock release --force' to terminate the holding session.",
+    HintSimulationFailed = "hint.simulation_failed" => "Fix the SQL errors shown above before running the actual migration.",
+    HintBaselineExists = "hint.baseline_exists" => "A baseline already exists. Use 'waypoint info' to see the current state.",
+    HintDatabaseNotFound = "hint.database_not_found" => "Check the database name in --database flag or [[databases]] config.",
+    HintMigrationParse = "hint.migration_parse" => "Check migration filenames follow the pattern V{version}__{description}.sql.",
+    HintMultiDb = "hint.multi_db" => "Check [[databases]] dependency configuration in waypoint.toml.",
+    HintIncompatibleHistory = "hint.incompatible_history" => "A newer waypoint has written to this history. Upgrade this binary before running it against the database.",
+    HintInputRequired = "hint.input_required" => "--no-input is set, so waypoint never waits for typed input. Pipe the input in, or pass it as an argument or file.",
+
+    InfoEmpty = "info.empty" => "No migrations found.",
+    InfoLast = "info.last" => "Last applied: {script} at {installed_on} by {installed_by}",
+    InfoNoneApplied = "info.none_applied" => "No migrations have been applied yet.",
+    MigrateUpToDate = "migrate.up_to_date" => "Schema is up to date. No migration necessary.",
+    MigrateApplied = "migrate.applied" => "Successfully applied {count} migration(s) (execution time {ms}ms)",
+    MigrateHooks = "migrate.hooks" => "Executed {count} hook(s) ({ms}ms)",
+    MigrateSkippedRepeatables = "migrate.skipped_repeatables" => "Skipped {count} unchanged repeatable migration(s)",
+    ValidateOk = "validate.ok" => "Successfully validated all applied migrations.",
+    RepairNone = "repair.none" => "Repair complete. No changes needed.",
+    RepairDone = "repair.done" => "Repair complete:",
+    UndoNone = "undo.none" => "No migrations to undo. Schema is already at its earliest state.",
+    UndoDone = "undo.done" => "Successfully undone {count} migration(s) (execution time {ms}ms)",
+    CleanNone = "clean.none" => "Nothing to clean.",
+    CleanDone = "clean.done" => "Successfully cleaned. Dropped {count} object(s):",
+    LockFree = "lock.free" => "Migration lock is free (table '{table}', key {key})",
+    LockHeld = "lock.held" => "Migration lock (table '{table}', key {key}):",
+    LockTerminated = "lock.terminated" => "Terminated session {pid} holding the migration lock",
+    DriftNone = "drift.none" => "No drift detected in schema '{schema}'.",
+    DriftFound = "drift.found" => "Schema drift detected in '{schema}': {count} difference(s)",
+    ExplainNone = "explain.none" => "Dry run: No pending migrations.",
+    ExplainPending = "explain.pending" => "Dry run: {count} migration(s) would be applied:",
+    PreflightPassed = "preflight.passed" => "Pre-flight checks passed.",
+    PreflightFailed = "preflight.failed" => "Pre-flight checks FAILED.",
+}
+
+/// Built-in catalogs besides English, by language code.
+const BUILT_IN: &[(&str, &str)] = &[("de", include_str!("../locales/de.toml"))];
+
+/// A loaded catalog: catalog key → translated text.
+#[derive(Debug, Clone, Default)]
+pub struct Catalog {
+    entries: HashMap<String, String>,
+}
+
+impl Catalog {
+    /// Parse a TOML catalog. Nested tables are flattened into dotted keys.
+    pub fn parse(source: &str) -> Result<Self> {
+        let table: toml::Table = toml::from_str(source)
+            .map_err(|e| WaypointError::ConfigError(format!("Invalid message catalog: {}", e)))?;
+        let mut entries = HashMap::new();
+        flatten("", &table, &mut entries)?;
+        Ok(Catalog { entries })
+    }
+
+    /// Text for `msg`, falling back to English when the catalog lacks it.
+    pub fn get(&self, msg: Msg) -> &str {
+        self.entries
+            .get(msg.key())
+            .map(String::as_str)
+            .unwrap_or_else(|| msg.english())
+    }
+
+    /// Whether the catalog defines `msg` itself.
+    pub fn contains(&self, msg: Msg) -> bool {
+        self.entries.contains_key(msg.key())
+    }
+}
+
+fn flatten(prefix: &str, table: &toml::Table, out: &mut HashMap<String, String>) -> Result<()> {
+    for (name, value) in table {
+        let key = if prefix.is_empty() {
+            name.clone()
+        } else {
+            format!("{}.{}", prefix, name)
+        };
+        match value {
+            toml::Value::String(text) => {
+                out.insert(key, text.clone());
+            }
+            toml::Value::Table(nested) => flatten(&key, nested, out)?,
+            _ => {
+                return Err(WaypointError::ConfigError(format!(
+                    "Invalid message catalog: '{}' must be a string",
+                    key
+                )))
+            }
+        }
+    }
+    Ok(())
+}
+
+static ACTIVE: OnceLock<Catalog> = OnceLock::new();
+
+/// Resolve `lang` to a catalog: `en` (English), a built-in language code, or
+/// the path of a TOML catalog file. Codes such as `de_DE.UTF-8` match `de`.
+pub fn load_language(lang: &str) -> Result<Catalog> {
+    let looks_like_path = lang.ends_with(".toml") || lang.contains(['/', '\\']);
+    if looks_like_path {
+        let source = std::fs::read_to_string(lang).map_err(|e| {
+            WaypointError::ConfigError(format!("Cannot read message catalog '{}': {}", lang, e))
+        })?;
+        return Catalog::parse(&source);
+    }
+
+    let code = lang
+        .split(['_', '-', '.'])
+        .next()
+        .unwrap_or_default()
+        .to_ascii_lowercase();
+    if code == "en" {
+        return Ok(Catalog::default());
+    }
+    match BUILT_IN.iter().find(|(c, _)| *c == code) {
+        Some((_, source)) => Catalog::parse(source),
+        None => {
+            let available: Vec<&str> = std::iter::once("en")
+                .chain(BUILT_IN.iter().map(|(c, _)| *c))
+                .collect();
+            Err(WaypointError::ConfigError(format!(
+                "Unknown language '{}'. Use one of {} or the path to a .toml catalog.",
+                lang,
+                available.join(", ")
+            )))
+        }
+    }
+}
+
+/// Switch this process to `lang` (see [`load_language`]). Only the first call
+/// takes effect; messages are English until then.
+pub fn set_language(lang: &str) -> Result<()> {
+    let catalog = load_language(lang)?;
+    log::debug!(
+        "Loaded message catalog; lang={}, entries={}",
+        lang,
+        catalog.entries.len()
+    );
+    let _ = ACTIVE.set(catalog);
+    Ok(())
+}
+
+/// Fill `{name}` placeholders in `text` from `args`.
+pub fn format(text: &str, args: &[(&str, &dyn Display)]) -> String {
+    let mut out = text.to_string();
+    for (name, value) in args {
+        out = out.replace(&format!("{{{}}}", name), &value.to_string());
+    }
+    out
+}
+
+/// Text for `msg` in the active language, with placeholders filled.
+pub fn tr(msg: Msg, args: &[(&str, &dyn Display)]) -> String {
+    let text = match ACTIVE.get() {
+        Some(catalog) => catalog.get(msg),
+        None => msg.english(),
+    };
+    format(text, args)
+}
+
+/// A hint line: the localized "Hint:" label followed by `msg`.
+pub fn hint(msg: Msg, args: &[(&str, &dyn Display)]) -> String {
+    format!("{} {}", tr(Msg::LabelHint, &[]), tr(msg, args))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn placeholders(text: &str) -> Vec<&str> {
+        let mut names: Vec<&str> = text
+            .split('{')
+            .skip(1)
+            .filter_map(|rest| rest.split_once('}').map(|(name, _)| name))
+            .collect();
+        names.sort_unstable();
+        names
+    }
+
+    #[test]
+    fn test_built_in_catalogs_are_complete() {
+        for (code, _) in BUILT_IN {
+            let catalog = load_language(code).unwrap();
+            for msg in Msg::ALL {
+                assert!(catalog.contains(*msg), "{} lacks {}", code, msg.key());
+                assert_eq!(
+                    placeholders(catalog.get(*msg)),
+                    placeholders(msg.english()),
+                    "{} has different placeholders for {}",
+                    code,
+                    msg.key()
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_partial_catalog_falls_back_to_english() {
+        let catalog = Catalog::parse("[migrate]\napplied = \"{count} angewendet\"\n").unwrap();
+        assert_eq!(
+            format(catalog.get(Msg::MigrateApplied), &[("count", &3)]),
+            "3 angewendet"
+        );
+        assert_eq!(catalog.get(Msg::InfoEmpty), "No migrations found.");
+        assert!(Catalog::parse("[hint]\nconfig = 1\n").is_err());
+    }
+
+    #[test]
+    fn test_load_language_codes() {
+        assert!(load_language("en").unwrap().entries.is_empty());
+        assert!(load_language("de_DE.UTF-8")
+            .unwrap()
+            .contains(Msg::HintConfig));
+        assert!(load_language("xx").is_err());
+    }
+}
@@ -258,6 +258,25 @@ pub fn scan_migrations(locations: &[std::path::PathBuf]) -> Result<Vec<ResolvedM
             let sql = std::fs::read_to_string(&path)?;
             let checksum = calculate_checksum(&sql);
             let directives = directive::parse_directives(&sql);
+            if let Some(required) = &directives.requires_version {
+                if !matches!(kind, MigrationKind::Repeatable) {
+                    return Err(WaypointError::InvalidDirective {
+                        script: filename,
+                        reason:
+                            "waypoint:requires-version applies to repeatable (R__) migrations only"
+                                .to_string(),
+                    });
+                }
+                requires_version(&filename, required)?;
+            }
+            if let Some(limit) = &directives.max_affected_rows {
+                if directives.row_limit().is_none() {
+                    return Err(WaypointError::MigrationParseError(format!(
+                        "Invalid waypoint:max-affected-rows in '{}': '{}' is not a row count",
+                        filename, limit
+                    )));
+                }
+            }
 
             migrations.push(ResolvedMigration {
                 kind,
@@ -313,6 +332,17 @@ pub fn scan_migrations(locations: &[std::path::PathBuf]) -> Result<Vec<ResolvedM
     Ok(migrations)
 }
 
+/// The version a `waypoint:requires-version` directive in `script` names.
+pub(crate) fn requires_version(script: &str, raw: &str) -> Result<MigrationVersion> {
+    MigrationVersion::parse(raw).map_err(|e| WaypointError::InvalidDirective {
+        script: script.to_string(),
+        reason: format!(
+            "waypoint:requires-version '{}' is not a version: {}",
+            raw, e
+        ),
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -426,4 +456,42 @@ mod tests {
         assert_eq!(m.migration_type(), MigrationType::Undo);
         assert_eq!(m.migration_type().to_string(), "UNDO_SQL");
     }
+
+    #[test]
+    fn test_scan_rejects_misplaced_or_invalid_requires_version() {
+        let dir = tempfile::tempdir().unwrap();
+        let locations = vec![dir.path().to_path_buf()];
+
+        std::fs::write(
+            dir.path().join("V1__Create.sql"),
+            "-- waypoint:requires-version 1\nCREATE TABLE t (id INT);",
+        )
+        .unwrap();
+        let err = scan_migrations(&locations).unwrap_err();
+        assert!(
+            matches!(err, WaypointError::InvalidDirective { .. }),
+            "{}",
+            err
+        );
+        std::fs::remove_file(dir.path().join("V1__Create.sql")).unwrap();
+
+        std::fs::write(
+            dir.path().join("R__View.sql"),
+            "-- waypoint:requires-version abc\nCREATE VIEW v AS SELECT 1;",
+        )
+        .unwrap();
+        let err = scan_migrations(&locations).unwrap_err();
+        assert!(
+            matches!(err, WaypointError::InvalidDirective { .. }),
+            "{}",
+            err
+        );
+
+        std::fs::write(
+            dir.path().join("R__View.sql"),
+            "-- waypoint:requires-version v2\nCREATE VIEW v AS SELECT 1;",
+        )
+        .unwrap();
+        assert_eq!(scan_migrations(&locations).unwrap().len(), 1);
+    }
 }
@@ -0,0 +1,267 @@
+//! Observe a running migration from a second connection (PostgreSQL).
+//!
+//! A [`Monitor`] is a plain session with no statement timeout that only reads
+//! the statistics views. Given the PID of the migrating session it reports
+//! what that session is doing right now: its state and wait event, the
+//! sessions blocking it, the locks it is still waiting for, and how far a
+//! `CREATE INDEX` has got according to `pg_stat_progress_create_index`.
+//!
+//! The migrating session can be found by PID (the heartbeat knows it) or, from
+//! another process such as `waypoint serve`, as the holder of the migration
+//! advisory lock.
+
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+use tokio_postgres::Client;
+
+use crate::config::{MigrationSettings, WaypointConfig};
+use crate::db;
+use crate::dialect::DialectKind;
+use crate::error::{Result, WaypointError};
+use crate::logging;
+
+/// Longest statement excerpt kept in a [`SessionActivity`], in characters.
+const STATEMENT_CHARS: usize = 120;
+
+/// State, wait event, blockers and current statement of one session.
+const ACTIVITY_SQL: &str = "\
+SELECT state, \
+       wait_event_type || ':' || wait_event, \
+       pg_blocking_pids(pid), \
+       regexp_replace(query, '\\s+', ' ', 'g') \
+FROM pg_stat_activity WHERE pid = $1";
+
+/// Locks the session has requested but not been granted.
+const LOCK_WAITS_SQL: &str = "\
+SELECT locktype, relation::regclass::text, mode \
+FROM pg_locks WHERE pid = $1 AND NOT granted \
+ORDER BY locktype, relation";
+
+/// Progress of a `CREATE INDEX` / `REINDEX` running in the session.
+const INDEX_PROGRESS_SQL: &str = "\
+SELECT index_relid::regclass::text, phase, \
+       blocks_done, blocks_total, tuples_done, tuples_total \
+FROM pg_stat_progress_create_index WHERE pid = $1";
+
+/// Granted holder of the migration advisory lock. A bigint key is split
+/// across classid (high 32 bits) and objid (low 32 bits), with objsubid = 1.
+const LOCK_HOLDER_SQL: &str = "\
+SELECT pid FROM pg_locks \
+WHERE locktype = 'advisory' AND objsubid = 1 AND granted \
+  AND database = (SELECT oid FROM pg_database WHERE datname = current_database()) \
+  AND classid = ($1::bigint >> 32)::oid \
+  AND objid = ($1::bigint & 4294967295)::oid";
+
+/// A snapshot of the migrating session.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SessionActivity {
+    /// Backend PID of the session.
+    pub pid: i32,
+    /// Session state, e.g. `active` or `idle in transaction`.
+    pub state: Option<String>,
+    /// Wait event as `type:event`, e.g. `Lock:relation`; `None` while on CPU.
+    pub wait_event: Option<String>,
+    /// PIDs of the sessions blocking this one.
+    pub blocked_by: Vec<i32>,
+    /// Locks requested but not yet granted.
+    pub lock_waits: Vec<LockWait>,
+    /// Progress of an index build, if one is running.
+    pub index_build: Option<IndexBuildProgress>,
+    /// Current statement, redacted and truncated.
+    pub statement: Option<String>,
+}
+
+/// A lock the session is waiting for.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LockWait {
+    /// `pg_locks.locktype`, e.g. `relation` or `transactionid`.
+    pub locktype: String,
+    /// The locked relation, for relation locks.
+    pub relation: Option<String>,
+    /// Requested mode, e.g. `AccessExclusiveLock`.
+    pub mode: String,
+}
+
+/// A row of `pg_stat_progress_create_index`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct IndexBuildProgress {
+    /// Index being built.
+    pub index: Option<String>,
+    /// Current phase, e.g. `building index: scanning table`.
+    pub phase: String,
+    /// Blocks processed in the current phase.
+    pub blocks_done: i64,
+    /// Blocks to process in the current phase, or 0 if not known.
+    pub blocks_total: i64,
+    /// Tuples processed in the current phase.
+    pub tuples_done: i64,
+    /// Tuples to process in the current phase, or 0 if not known.
+    pub tuples_total: i64,
+}
+
+impl IndexBuildProgress {
+    /// Percent complete of the current phase, from blocks or else tuples.
+    pub fn percent(&self) -> Option<f64> {
+        let (done, total) = if self.blocks_total > 0 {
+            (self.blocks_done, self.blocks_total)
+        } else {
+            (self.tuples_done, self.tuples_total)
+        };
+        (total > 0).then(|| done as f64 * 100.0 / total as f64)
+    }
+}
+
+impl fmt::Display for SessionActivity {
+    /// `key=value` pairs for log lines.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let blocked_by: Vec<String> = self.blocked_by.iter().map(|p| p.to_string()).collect();
+        write!(
+            f,
+            "pid={}, state={}, wait_event={}, blocked_by={{{}}}",
+            self.pid,
+            self.state.as_deref().unwrap_or("unknown"),
+            self.wait_event.as_deref().unwrap_or("none"),
+            blocked_by.join(",")
+        )?;
+        if !self.lock_waits.is_empty() {
+            let waits: Vec<String> = self
+                .lock_waits
+                .iter()
+                .map(|w| match &w.relation {
+                    Some(rel) => format!("{} on {}", w.mode, rel),
+                    None => format!("{} on {}", w.mode, w.locktype),
+                })
+                .collect();
+            write!(f, ", waiting_for={}", waits.join("; "))?;
+        }
+        if let Some(ib) = &self.index_build {
+            write!(f, ", index_build={}", ib.phase)?;
+            if let Some(pct) = ib.percent() {
+                write!(f, " ({:.1}%)", pct)?;
+            }
+        }
+        write!(f, ", statement={}", self.statement.as_deref().unwrap_or(""))
+    }
+}
+
+/// A connection used only to observe the migrating session.
+pub struct Monitor {
+    client: Client,
+}
+
+impl Monitor {
+    /// Open a monitoring connection with the configured URL and TLS mode, a
+    /// single attempt and no statement timeout.
+    pub async fn connect(config: &WaypointConfig) -> Result<Self> {
+        let url = config.connection_string()?;
+        if DialectKind::from_url(&url) == Some(DialectKind::Mysql) {
+            return Err(WaypointError::ConfigError(
+                "the monitoring connection is only supported on PostgreSQL".to_string(),
+            ));
+        }
+        let client = db::connect_with_config(
+            &url,
+            &config.database.ssl_mode,
+            0,
+            config.database.connect_timeout_secs,
+            0,
+        )
+        .await?;
+        client
+            .batch_execute("SET application_name = 'waypoint-monitor'")
+            .await?;
+        Ok(Monitor { client })
+    }
+
+    /// PID of the session holding the migration lock for `migrations`, i.e.
+    /// the one running migrate.
+    pub async fn migrating_session(&self, migrations: &MigrationSettings) -> Result<Option<i32>> {
+        let lock_id = db::advisory_lock_id(&db::pg_lock_name(migrations)?);
+        let row = self.client.query_opt(LOCK_HOLDER_SQL, &[&lock_id]).await?;
+        Ok(row.map(|r| r.get(0)))
+    }
+
+    /// Snapshot the session `pid`; `None` once it has disconnected.
+    pub async fn observe(&self, pid: i32) -> Result<Option<SessionActivity>> {
+        let Some(row) = self.client.query_opt(ACTIVITY_SQL, &[&pid]).await? else {
+            return Ok(None);
+        };
+        let query: Option<String> = row.get(3);
+
+        let lock_waits = self
+            .client
+            .query(LOCK_WAITS_SQL, &[&pid])
+            .await?
+            .iter()
+            .map(|r| LockWait {
+                locktype: r.get(0),
+                relation: r.get(1),
+                mode: r.get(2),
+            })
+            .collect();
+
+        // The view exists from PostgreSQL 12; older servers simply report
+        // no index progress.
+        let index_build = match self.client.query_opt(INDEX_PROGRESS_SQL, &[&pid]).await {
+            Ok(row) => row.map(|r| IndexBuildProgress {
+                index: r.get(0),
+                phase: r.get(1),
+                blocks_done: r.get(2),
+                blocks_total: r.get(3),
+                tuples_done: r.get(4),
+                tuples_total: r.get(5),
+            }),
+            Err(e) => {
+                log::debug!(target: logging::MIGRATE, "Index build progress unavailable; error={}", e);
+                None
+            }
+        };
+
+        Ok(Some(SessionActivity {
+            pid,
+            state: row.get(0),
+            wait_event: row.get(1),
+            blocked_by: row.get(2),
+            lock_waits,
+            index_build,
+            statement: query.map(|q| logging::redact(&q).chars().take(STATEMENT_CHARS).collect()),
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_activity_display() {
+        let activity = SessionActivity {
+            pid: 42,
+            state: Some("active".to_string()),
+            wait_event: Some("Lock:relation".to_string()),
+            blocked_by: vec![7, 9],
+            lock_waits: vec![LockWait {
+                locktype: "relation".to_string(),
+                relation: Some("users".to_string()),
+                mode: "ShareLock".to_string(),
+            }],
+            index_build: Some(IndexBuildProgress {
+                index: Some("users_email_idx".to_string()),
+                phase: "building index: scanning table".to_string(),
+                blocks_done: 250,
+                blocks_total: 1000,
+                tuples_done: 0,
+                tuples_total: 0,
+            }),
+            statement: Some("CREATE INDEX users_email_idx ON users (email)".to_string()),
+        };
+        assert_eq!(
+            activity.to_string(),
+            "pid=42, state=active, wait_event=Lock:relation, blocked_by={7,9}, \
+             waiting_for=ShareLock on users, \
+             index_build=building index: scanning table (25.0%), \
+             statement=CREATE INDEX users_email_idx ON users (email)"
+        );
+    }
+}
@@ -281,13 +281,14 @@ async fn connect_one(
     match kind {
         #[cfg(feature = "postgres")]
         DialectKind::Postgres => {
-            let client = crate::db::connect_with_full_config(
+            let client = crate::db::connect_with_deadline(
                 conn_string,
                 &config.database.ssl_mode,
                 config.database.connect_retries,
                 config.database.connect_timeout_secs,
                 config.database.statement_timeout_secs,
                 config.database.keepalive_secs,
+                config.database.connect_deadline_secs,
             )
             .await?;
             Ok(DbClient::with_postgres(client))
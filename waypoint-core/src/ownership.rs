@@ -0,0 +1,152 @@
+//! Ownership transfer after migrate (PostgreSQL).
+//!
+//! Objects belong to the role that created them, which in a pipeline is
+//! usually a CI migration user the application never logs in as. With
+//! `transfer_ownership_to` set, every table, view, sequence, function and
+//! type in the managed schema still owned by the migrating user is handed to
+//! the configured role once migrate succeeds (`ALTER ... OWNER TO`).
+//!
+//! Sequences backing serial and identity columns follow their table, and
+//! waypoint's own history and audit tables stay with the migrating user so
+//! that later runs can keep writing to them. All changes run in one implicit
+//! transaction: either every object moves or none does.
+
+use serde::{Deserialize, Serialize};
+
+#[cfg(feature = "postgres")]
+use tokio_postgres::Client;
+
+#[cfg(feature = "postgres")]
+use crate::db::quote_ident;
+
+/// Outcome of the post-migrate ownership transfer.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OwnershipTransfer {
+    /// Role the objects were given to.
+    pub role: String,
+    /// Objects reassigned, e.g. `TABLE public.users`.
+    pub objects: Vec<String>,
+    /// Why the transfer failed; `None` on success. Nothing is reassigned
+    /// when this is set.
+    pub error: Option<String>,
+}
+
+impl OwnershipTransfer {
+    /// Whether every object was reassigned.
+    pub fn succeeded(&self) -> bool {
+        self.error.is_none()
+    }
+}
+
+/// Objects in `schema` owned by the current user, as `(kind, name)` where
+/// `name` is already quoted and, for routines, carries the argument list.
+#[cfg(feature = "postgres")]
+const OWNED_OBJECTS_SQL: &str = "\
+SELECT CASE c.relkind WHEN 'v' THEN 'VIEW' WHEN 'm' THEN 'MATERIALIZED VIEW' \
+            WHEN 'f' THEN 'FOREIGN TABLE' WHEN 'S' THEN 'SEQUENCE' ELSE 'TABLE' END, \
+       quote_ident(c.relname) \
+FROM pg_class c JOIN pg_namespace n ON n.oid = c.relnamespace \
+WHERE n.nspname = $1 AND c.relkind IN ('r', 'p', 'v', 'm', 'f', 'S') \
+  AND c.relowner = (SELECT oid FROM pg_roles WHERE rolname = current_user) \
+  AND c.relname::text <> ALL($2::text[]) \
+  AND NOT (c.relkind = 'S' AND EXISTS ( \
+      SELECT 1 FROM pg_depend d \
+      WHERE d.classid = 'pg_class'::regclass AND d.objid = c.oid AND d.deptype IN ('a', 'i'))) \
+UNION ALL \
+SELECT CASE p.prokind WHEN 'p' THEN 'PROCEDURE' WHEN 'a' THEN 'AGGREGATE' ELSE 'FUNCTION' END, \
+       quote_ident(p.proname) || '(' || pg_get_function_identity_arguments(p.oid) || ')' \
+FROM pg_proc p JOIN pg_namespace n ON n.oid = p.pronamespace \
+WHERE n.nspname = $1 \
+  AND p.proowner = (SELECT oid FROM pg_roles WHERE rolname = current_user) \
+  AND p.proname <> 'waypoint_capture_ddl' \
+  AND NOT EXISTS (SELECT 1 FROM pg_depend d \
+      WHERE d.classid = 'pg_proc'::regclass AND d.objid = p.oid AND d.deptype = 'e') \
+UNION ALL \
+SELECT CASE t.typtype WHEN 'd' THEN 'DOMAIN' ELSE 'TYPE' END, quote_ident(t.typname) \
+FROM pg_type t JOIN pg_namespace n ON n.oid = t.typnamespace \
+WHERE n.nspname = $1 AND t.typtype IN ('c', 'd', 'e', 'r') \
+  AND t.typowner = (SELECT oid FROM pg_roles WHERE rolname = current_user) \
+  AND (t.typtype <> 'c' OR EXISTS ( \
+      SELECT 1 FROM pg_class c WHERE c.oid = t.typrelid AND c.relkind = 'c')) \
+  AND NOT EXISTS (SELECT 1 FROM pg_depend d \
+      WHERE d.classid = 'pg_type'::regclass AND d.objid = t.oid AND d.deptype = 'e') \
+ORDER BY 1, 2";
+
+/// Hand every object in `schema` owned by the migrating user to `role`.
+/// The caller holds the migration lock.
+#[cfg(feature = "postgres")]
+pub(crate) async fn transfer(
+    client: &Client,
+    schema: &str,
+    history_table: &str,
+    role: &str,
+) -> OwnershipTransfer {
+    let own_tables = vec![
+        history_table.to_string(),
+        crate::ddl_capture::audit_table_name(history_table),
+        crate::history::META_TABLE.to_string(),
+    ];
+    let mut transfer = OwnershipTransfer {
+        role: role.to_string(),
+        objects: Vec::new(),
+        error: None,
+    };
+
+    let rows = match client
+        .query(OWNED_OBJECTS_SQL, &[&schema, &own_tables])
+        .await
+    {
+        Ok(rows) => rows,
+        Err(e) => {
+            transfer.error = Some(crate::error::format_db_error(&e));
+            log::error!(
+                "Could not list objects for ownership transfer; schema={}, error={}",
+                schema,
+                transfer.error.as_deref().unwrap_or_default()
+            );
+            return transfer;
+        }
+    };
+
+    let qualified: Vec<(String, String)> = rows
+        .iter()
+        .map(|r| {
+            let kind: String = r.get(0);
+            let name: String = r.get(1);
+            (kind, format!("{}.{}", quote_ident(schema), name))
+        })
+        .collect();
+    if qualified.is_empty() {
+        return transfer;
+    }
+
+    let sql: String = qualified
+        .iter()
+        .map(|(kind, name)| format!("ALTER {} {} OWNER TO {};", kind, name, quote_ident(role)))
+        .collect::<Vec<_>>()
+        .join("\n");
+    // One simple-query batch runs as a single implicit transaction.
+    match client.batch_execute(&sql).await {
+        Ok(()) => {
+            transfer.objects = qualified
+                .into_iter()
+                .map(|(kind, name)| format!("{} {}", kind, name))
+                .collect();
+            log::info!(
+                "Transferred object ownership; role={}, objects={}",
+                role,
+                transfer.objects.len()
+            );
+        }
+        Err(e) => {
+            let error = crate::error::format_db_error(&e);
+            log::error!(
+                "Ownership transfer failed, nothing was reassigned; role={}, error={}",
+                role,
+                error
+            );
+            transfer.error = Some(error);
+        }
+    }
+    transfer
+}
@@ -0,0 +1,350 @@
+//! Smoke-test queries run after a successful migrate.
+//!
+//! Each `[[post_checks]]` entry is a SQL query with an expectation: an exact
+//! row count, a boolean in the first column of the first row, or (with
+//! neither) simply that the query succeeds. Failures do not undo the
+//! migrations unless `rollback_on_post_check_failure` is set; they are
+//! recorded on the [`MigrateReport`](crate::MigrateReport) and surface as
+//! [`WaypointError::PostCheckFailed`] via
+//! [`MigrateReport::ensure_post_checks_passed`](crate::MigrateReport::ensure_post_checks_passed).
+
+use serde::{Deserialize, Serialize};
+
+#[cfg(feature = "postgres")]
+use tokio_postgres::Client;
+
+use crate::commands::migrate::MigrateDetail;
+use crate::commands::undo::UndoReport;
+use crate::config::WaypointConfig;
+use crate::db::DbClient;
+use crate::dialect::DialectKind;
+use crate::error::{Result, WaypointError};
+use crate::migration::{scan_migrations, MigrationVersion};
+
+/// A configured post-migrate check.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PostCheck {
+    /// Name shown in reports and errors.
+    pub name: String,
+    /// Query to run.
+    pub sql: String,
+    /// Expected number of rows returned.
+    pub expect_rows: Option<i64>,
+    /// Expected boolean in the first column of the first row.
+    pub expect: Option<bool>,
+}
+
+impl PostCheck {
+    /// Reject checks with conflicting expectations.
+    pub fn validate(&self) -> Result<()> {
+        if self.expect_rows.is_some() && self.expect.is_some() {
+            return Err(WaypointError::ConfigError(format!(
+                "post_check '{}' sets both expect_rows and expect; use one",
+                self.name
+            )));
+        }
+        Ok(())
+    }
+
+    /// Judge a query result given its row count and first value, if boolean.
+    fn evaluate(&self, rows: i64, first: Option<bool>) -> PostCheckResult {
+        let (passed, detail) = match (self.expect_rows, self.expect) {
+            (Some(want), _) => (
+                rows == want,
+                format!("expected {} row(s), got {}", want, rows),
+            ),
+            (None, Some(want)) => match first {
+                Some(got) => (got == want, format!("expected {}, got {}", want, got)),
+                None => (false, format!("expected {}, got no boolean value", want)),
+            },
+            (None, None) => (true, format!("query succeeded ({} row(s))", rows)),
+        };
+        PostCheckResult {
+            name: self.name.clone(),
+            passed,
+            detail,
+        }
+    }
+
+    fn errored(&self, e: impl std::fmt::Display) -> PostCheckResult {
+        PostCheckResult {
+            name: self.name.clone(),
+            passed: false,
+            detail: format!("query failed: {}", e),
+        }
+    }
+}
+
+/// Outcome of a single post-migrate check.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PostCheckResult {
+    /// Check name.
+    pub name: String,
+    /// Whether the expectation was met.
+    pub passed: bool,
+    /// What was expected and what was observed.
+    pub detail: String,
+}
+
+/// Outcome of undoing a migrate run after its post-checks failed.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PostCheckRollback {
+    /// Versions that were undone, newest first.
+    pub versions_undone: Vec<String>,
+    /// Why the rollback was skipped or stopped; `None` when every version
+    /// applied by the run was undone.
+    pub error: Option<String>,
+}
+
+impl PostCheckRollback {
+    /// Whether every version applied by the run was undone.
+    pub fn succeeded(&self) -> bool {
+        self.error.is_none()
+    }
+
+    fn finish(report: UndoReport, outcome: Result<()>) -> Self {
+        let rollback = PostCheckRollback {
+            versions_undone: report.details.into_iter().map(|d| d.version).collect(),
+            error: outcome.err().map(|e| e.to_string()),
+        };
+        match &rollback.error {
+            None => log::info!(
+                "Rolled back migrate run after failed post-checks; versions={}",
+                rollback.versions_undone.join(",")
+            ),
+            Some(e) => log::error!(
+                "Rollback after failed post-checks did not complete; undone={}, error={}",
+                rollback.versions_undone.join(","),
+                e
+            ),
+        }
+        rollback
+    }
+
+    fn skipped(reason: String) -> Self {
+        log::warn!(
+            "Not rolling back after failed post-checks; reason={}",
+            reason
+        );
+        PostCheckRollback {
+            versions_undone: Vec::new(),
+            error: Some(reason),
+        }
+    }
+}
+
+/// Versions applied by a run, newest first, provided each has a `U` file.
+///
+/// The whole batch is refused if any version lacks one, so a rollback never
+/// leaves the schema half-undone for want of a script. Returns `None` when
+/// the run applied no versioned migrations.
+fn rollback_plan(
+    config: &WaypointConfig,
+    applied: &[MigrateDetail],
+) -> Option<std::result::Result<Vec<MigrationVersion>, String>> {
+    let mut versions: Vec<MigrationVersion> = applied
+        .iter()
+        .filter_map(|d| d.version.as_deref())
+        .filter_map(|v| MigrationVersion::parse(v).ok())
+        .collect();
+    if versions.is_empty() {
+        return None;
+    }
+    versions.sort();
+    versions.reverse();
+
+    let resolved = match scan_migrations(&config.migrations.locations) {
+        Ok(resolved) => resolved,
+        Err(e) => return Some(Err(e.to_string())),
+    };
+    let missing: Vec<&str> = versions
+        .iter()
+        .filter(|v| {
+            !resolved
+                .iter()
+                .any(|m| m.is_undo() && m.version() == Some(*v))
+        })
+        .map(|v| v.raw.as_str())
+        .collect();
+    if !missing.is_empty() {
+        return Some(Err(format!(
+            "no undo script for version(s) {}",
+            missing.join(", ")
+        )));
+    }
+    Some(Ok(versions))
+}
+
+fn empty_undo_report() -> UndoReport {
+    UndoReport {
+        report_version: crate::REPORT_VERSION,
+        migrations_undone: 0,
+        total_time_ms: 0,
+        details: Vec::new(),
+    }
+}
+
+/// Undo the versions a migrate run applied (PostgreSQL). The caller holds
+/// the migration lock.
+#[cfg(feature = "postgres")]
+pub(crate) async fn rollback_run(
+    client: &Client,
+    config: &WaypointConfig,
+    applied: &[MigrateDetail],
+) -> Option<PostCheckRollback> {
+    let versions = match rollback_plan(config, applied)? {
+        Ok(versions) => versions,
+        Err(reason) => return Some(PostCheckRollback::skipped(reason)),
+    };
+    let mut report = empty_undo_report();
+    let outcome =
+        crate::commands::undo::undo_versions(client, config, &versions, &mut report).await;
+    Some(PostCheckRollback::finish(report, outcome))
+}
+
+/// Undo the versions a migrate run applied (MySQL). The caller holds the
+/// migration lock.
+#[cfg(feature = "mysql")]
+pub(crate) async fn rollback_run_mysql(
+    client: &DbClient,
+    config: &WaypointConfig,
+    applied: &[MigrateDetail],
+) -> Option<PostCheckRollback> {
+    let versions = match rollback_plan(config, applied)? {
+        Ok(versions) => versions,
+        Err(reason) => return Some(PostCheckRollback::skipped(reason)),
+    };
+    let schema = match client.resolve_schema(&config.migrations.schema).await {
+        Ok(schema) => schema,
+        Err(e) => return Some(PostCheckRollback::skipped(e.to_string())),
+    };
+    let mut report = empty_undo_report();
+    let outcome =
+        crate::commands::undo::undo_versions_mysql(client, config, &schema, &versions, &mut report)
+            .await;
+    Some(PostCheckRollback::finish(report, outcome))
+}
+
+/// Run every check in order and collect the results (PostgreSQL legacy entry).
+#[cfg(feature = "postgres")]
+pub async fn run_post_checks(client: &Client, checks: &[PostCheck]) -> Vec<PostCheckResult> {
+    let mut results = Vec::with_capacity(checks.len());
+    for check in checks {
+        let result = match client.query(check.sql.as_str(), &[]).await {
+            Ok(rows) => {
+                let first = rows
+                    .first()
+                    .and_then(|r| r.try_get::<_, Option<bool>>(0).ok().flatten());
+                check.evaluate(rows.len() as i64, first)
+            }
+            Err(e) => check.errored(crate::error::format_db_error(&e)),
+        };
+        log_result(&result);
+        results.push(result);
+    }
+    results
+}
+
+/// Run every check in order and collect the results (dialect-aware entry).
+pub async fn run_post_checks_db(client: &DbClient, checks: &[PostCheck]) -> Vec<PostCheckResult> {
+    match client.dialect_kind() {
+        #[cfg(feature = "postgres")]
+        DialectKind::Postgres => match client.as_postgres() {
+            Ok(c) => run_post_checks(c, checks).await,
+            Err(e) => checks.iter().map(|c| c.errored(&e)).collect(),
+        },
+        #[cfg(not(feature = "postgres"))]
+        DialectKind::Postgres => checks
+            .iter()
+            .map(|c| c.errored("PostgreSQL support is not compiled in"))
+            .collect(),
+        #[cfg(feature = "mysql")]
+        DialectKind::Mysql => run_post_checks_mysql(client, checks).await,
+        #[cfg(not(feature = "mysql"))]
+        DialectKind::Mysql => checks
+            .iter()
+            .map(|c| c.errored("MySQL support is not compiled in"))
+            .collect(),
+    }
+}
+
+#[cfg(feature = "mysql")]
+async fn run_post_checks_mysql(client: &DbClient, checks: &[PostCheck]) -> Vec<PostCheckResult> {
+    use mysql_async::prelude::*;
+
+    let mut results = Vec::with_capacity(checks.len());
+    for check in checks {
+        let rows: std::result::Result<Vec<mysql_async::Row>, WaypointError> = async {
+            let mut conn = client.as_mysql()?.get_conn().await?;
+            Ok(conn.query(check.sql.as_str()).await?)
+        }
+        .await;
+        let result = match rows {
+            Ok(mut rows) => {
+                // MySQL has no boolean type; TINYINT(1) and 0/1 expressions both map here.
+                let first = rows
+                    .first_mut()
+                    .and_then(|r| r.take_opt::<Option<bool>, _>(0))
+                    .and_then(|v| v.ok())
+                    .flatten();
+                check.evaluate(rows.len() as i64, first)
+            }
+            Err(e) => check.errored(e),
+        };
+        log_result(&result);
+        results.push(result);
+    }
+    results
+}
+
+fn log_result(result: &PostCheckResult) {
+    if result.passed {
+        log::info!(
+            "Post-check passed; name={}, detail={}",
+            result.name,
+            result.detail
+        );
+    } else {
+        log::warn!(
+            "Post-check failed; name={}, detail={}",
+            result.name,
+            result.detail
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn check(expect_rows: Option<i64>, expect: Option<bool>) -> PostCheck {
+        PostCheck {
+            name: "c".into(),
+            sql: "SELECT 1".into(),
+            expect_rows,
+            expect,
+        }
+    }
+
+    #[test]
+    fn test_evaluate_row_count() {
+        assert!(check(Some(1), None).evaluate(1, None).passed);
+        let r = check(Some(0), None).evaluate(2, None);
+        assert!(!r.passed);
+        assert_eq!(r.detail, "expected 0 row(s), got 2");
+    }
+
+    #[test]
+    fn test_evaluate_boolean() {
+        assert!(check(None, Some(true)).evaluate(1, Some(true)).passed);
+        assert!(!check(None, Some(true)).evaluate(1, Some(false)).passed);
+        assert!(!check(None, Some(false)).evaluate(0, None).passed);
+    }
+
+    #[test]
+    fn test_evaluate_success_only_and_validate() {
+        assert!(check(None, None).evaluate(0, None).passed);
+        assert!(check(Some(1), Some(true)).validate().is_err());
+        assert!(check(Some(1), None).validate().is_ok());
+    }
+}
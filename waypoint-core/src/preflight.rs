@@ -1,21 +1,23 @@
 //! Pre-flight health checks run before migrations.
 //!
 //! Checks database health metrics like recovery mode, active connections,
-//! long-running queries, replication lag, and lock contention.
+//! long-running queries, replication lag, and lock contention. Also enforces
+//! change-freeze policy: allowed migration windows and a server-side freeze
+//! flag, when configured. Policy checks still run on migrate when the health
+//! checks are disabled (`--skip-preflight`, `--dev`).
 
-use serde::Serialize;
+use chrono::{DateTime, Datelike, NaiveTime, Utc, Weekday};
+use serde::{Deserialize, Serialize};
 
 #[cfg(feature = "postgres")]
 use tokio_postgres::Client;
 
 use crate::db::DbClient;
 use crate::dialect::DialectKind;
-use crate::error::Result;
-#[cfg(any(not(feature = "postgres"), not(feature = "mysql")))]
-use crate::error::WaypointError;
+use crate::error::{Result, WaypointError};
 
 /// Result of a single pre-flight check.
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PreflightCheck {
     /// Human-readable name of the check (e.g. "Recovery Mode").
     pub name: String,
@@ -26,7 +28,7 @@ pub struct PreflightCheck {
 }
 
 /// Status of a pre-flight check.
-#[derive(Debug, Clone, Serialize, PartialEq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub enum CheckStatus {
     /// The check passed successfully.
     Pass,
@@ -47,8 +49,11 @@ impl std::fmt::Display for CheckStatus {
 }
 
 /// Aggregate report of all pre-flight checks.
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct PreflightReport {
+    /// Report format version; see [`crate::REPORT_VERSION`].
+    #[serde(default)]
+    pub report_version: u32,
     /// Individual check results.
     pub checks: Vec<PreflightCheck>,
     /// Whether all checks passed (no failures).
@@ -72,6 +77,20 @@ pub struct PreflightConfig {
     pub max_replication_lag_secs: i64,
     /// Threshold in seconds for detecting long-running queries.
     pub long_query_threshold_secs: i64,
+    /// Allowed migration windows in UTC (see [`MigrationWindow::parse`]).
+    /// Empty means migrations may run at any time.
+    pub windows: Vec<String>,
+    /// Query that reports a change freeze. Migration is refused if any row's
+    /// first column is true; an optional second text column is shown as the
+    /// reason.
+    pub freeze_query: Option<String>,
+}
+
+impl PreflightConfig {
+    /// Whether migration windows or a freeze query are configured.
+    pub fn has_policy(&self) -> bool {
+        !self.windows.is_empty() || self.freeze_query.is_some()
+    }
 }
 
 impl Default for PreflightConfig {
@@ -81,10 +100,179 @@ impl Default for PreflightConfig {
             max_replication_lag_mb: 100,
             max_replication_lag_secs: 30,
             long_query_threshold_secs: 300,
+            windows: Vec::new(),
+            freeze_query: None,
+        }
+    }
+}
+
+/// A recurring time window (UTC) during which migrations may start.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MigrationWindow {
+    /// Days on which the window opens, indexed from Monday.
+    days: [bool; 7],
+    start: NaiveTime,
+    end: NaiveTime,
+}
+
+impl MigrationWindow {
+    /// Parse a window spec of the form `[DAYS] HH:MM-HH:MM`.
+    ///
+    /// `DAYS` is a comma-separated list of days or day ranges (`Mon-Fri`,
+    /// `Sat,Sun`, `Fri-Mon`) and defaults to every day. A window whose end is
+    /// before its start runs past midnight into the next day.
+    pub fn parse(spec: &str) -> Result<Self> {
+        let invalid = |why: &str| {
+            WaypointError::ConfigError(format!(
+                "Invalid migration window '{}': {} (expected e.g. 'Mon-Fri 22:00-06:00')",
+                spec, why
+            ))
+        };
+
+        let mut parts = spec.split_whitespace();
+        let (days_spec, times) = match (parts.next(), parts.next(), parts.next()) {
+            (Some(times), None, None) => (None, times),
+            (Some(days), Some(times), None) => (Some(days), times),
+            _ => return Err(invalid("wrong number of fields")),
+        };
+
+        let mut days = [days_spec.is_none(); 7];
+        for item in days_spec.into_iter().flat_map(|d| d.split(',')) {
+            let (from, to) = match item.split_once('-') {
+                Some((a, b)) => (parse_weekday(a), parse_weekday(b)),
+                None => (parse_weekday(item), parse_weekday(item)),
+            };
+            let (Some(from), Some(to)) = (from, to) else {
+                return Err(invalid(&format!("unknown day '{}'", item)));
+            };
+            let mut day = from;
+            loop {
+                days[day.num_days_from_monday() as usize] = true;
+                if day == to {
+                    break;
+                }
+                day = day.succ();
+            }
+        }
+
+        let (start, end) = times
+            .split_once('-')
+            .ok_or_else(|| invalid("missing time range"))?;
+        let parse_time = |s: &str| {
+            NaiveTime::parse_from_str(s, "%H:%M").map_err(|_| invalid(&format!("bad time '{}'", s)))
+        };
+        let (start, end) = (parse_time(start)?, parse_time(end)?);
+        if start == end {
+            return Err(invalid("start and end are equal"));
+        }
+
+        Ok(Self { days, start, end })
+    }
+
+    /// Whether `at` falls inside this window.
+    pub fn contains(&self, at: DateTime<Utc>) -> bool {
+        let time = at.time();
+        let today = at.weekday();
+        let opens = |day: Weekday| self.days[day.num_days_from_monday() as usize];
+        if self.start < self.end {
+            opens(today) && time >= self.start && time < self.end
+        } else {
+            (opens(today) && time >= self.start) || (opens(today.pred()) && time < self.end)
         }
     }
 }
 
+fn parse_weekday(s: &str) -> Option<Weekday> {
+    match s.to_ascii_lowercase().as_str() {
+        "mon" => Some(Weekday::Mon),
+        "tue" => Some(Weekday::Tue),
+        "wed" => Some(Weekday::Wed),
+        "thu" => Some(Weekday::Thu),
+        "fri" => Some(Weekday::Fri),
+        "sat" => Some(Weekday::Sat),
+        "sun" => Some(Weekday::Sun),
+        _ => None,
+    }
+}
+
+/// Fail unless `now` falls inside one of the configured windows.
+pub fn check_migration_window(windows: &[String], now: DateTime<Utc>) -> PreflightCheck {
+    let name = "Migration Window".to_string();
+    for spec in windows {
+        match MigrationWindow::parse(spec) {
+            Ok(window) if window.contains(now) => {
+                return PreflightCheck {
+                    name,
+                    status: CheckStatus::Pass,
+                    detail: format!("Inside window '{}'", spec),
+                };
+            }
+            Ok(_) => {}
+            Err(e) => {
+                return PreflightCheck {
+                    name,
+                    status: CheckStatus::Fail,
+                    detail: e.to_string(),
+                };
+            }
+        }
+    }
+    PreflightCheck {
+        name,
+        status: CheckStatus::Fail,
+        detail: format!(
+            "Outside allowed windows ({}); now {} UTC",
+            windows.join(", "),
+            now.format("%a %H:%M")
+        ),
+    }
+}
+
+/// Common checks that depend only on config (no database access).
+fn policy_checks(config: &PreflightConfig) -> Vec<PreflightCheck> {
+    let mut checks = Vec::new();
+    if !config.windows.is_empty() {
+        checks.push(check_migration_window(&config.windows, Utc::now()));
+    }
+    checks
+}
+
+fn freeze_check(frozen: Option<Option<String>>) -> PreflightCheck {
+    match frozen {
+        Some(reason) => PreflightCheck {
+            name: "Change Freeze".to_string(),
+            status: CheckStatus::Fail,
+            detail: match reason {
+                Some(r) if !r.is_empty() => format!("Change freeze in effect: {}", r),
+                _ => "Change freeze in effect".to_string(),
+            },
+        },
+        None => PreflightCheck {
+            name: "Change Freeze".to_string(),
+            status: CheckStatus::Pass,
+            detail: "No freeze in effect".to_string(),
+        },
+    }
+}
+
+fn report(checks: Vec<PreflightCheck>) -> PreflightReport {
+    let passed = !checks.iter().any(|c| c.status == CheckStatus::Fail);
+    PreflightReport {
+        report_version: crate::REPORT_VERSION,
+        checks,
+        passed,
+    }
+}
+
+/// A freeze query that cannot be evaluated fails closed.
+fn freeze_query_failed(e: impl std::fmt::Display) -> PreflightCheck {
+    PreflightCheck {
+        name: "Change Freeze".to_string(),
+        status: CheckStatus::Fail,
+        detail: format!("Could not evaluate freeze_query: {}", e),
+    }
+}
+
 /// Run all pre-flight checks against the database (PostgreSQL legacy entry).
 #[cfg(feature = "postgres")]
 pub async fn run_preflight(client: &Client, config: &PreflightConfig) -> Result<PreflightReport> {
@@ -96,10 +284,26 @@ pub async fn run_preflight(client: &Client, config: &PreflightConfig) -> Result<
     checks.push(check_replication_lag(client, config.max_replication_lag_mb).await);
     checks.push(check_database_size(client).await);
     checks.push(check_lock_contention(client).await);
+    checks.extend(pg_policy_checks(client, config).await);
+    Ok(report(checks))
+}
 
-    let passed = !checks.iter().any(|c| c.status == CheckStatus::Fail);
+/// Run only the migration window and freeze checks (PostgreSQL legacy entry).
+#[cfg(feature = "postgres")]
+pub async fn run_policy_checks(
+    client: &Client,
+    config: &PreflightConfig,
+) -> Result<PreflightReport> {
+    Ok(report(pg_policy_checks(client, config).await))
+}
 
-    Ok(PreflightReport { checks, passed })
+#[cfg(feature = "postgres")]
+async fn pg_policy_checks(client: &Client, config: &PreflightConfig) -> Vec<PreflightCheck> {
+    let mut checks = policy_checks(config);
+    if let Some(query) = &config.freeze_query {
+        checks.push(check_freeze(client, query).await);
+    }
+    checks
 }
 
 /// Run all pre-flight checks against the database (dialect-aware entry).
@@ -123,6 +327,27 @@ pub async fn run_preflight_db(
     }
 }
 
+/// Run only the migration window and freeze checks (dialect-aware entry).
+pub async fn run_policy_checks_db(
+    client: &DbClient,
+    config: &PreflightConfig,
+) -> Result<PreflightReport> {
+    match client.dialect_kind() {
+        #[cfg(feature = "postgres")]
+        DialectKind::Postgres => run_policy_checks(client.as_postgres()?, config).await,
+        #[cfg(not(feature = "postgres"))]
+        DialectKind::Postgres => Err(WaypointError::ConfigError(
+            "PostgreSQL support is not compiled in (enable the `postgres` feature)".into(),
+        )),
+        #[cfg(feature = "mysql")]
+        DialectKind::Mysql => Ok(report(mysql_policy_checks(client, config).await)),
+        #[cfg(not(feature = "mysql"))]
+        DialectKind::Mysql => Err(WaypointError::ConfigError(
+            "MySQL support is not compiled in (enable the `mysql` feature)".into(),
+        )),
+    }
+}
+
 #[cfg(feature = "postgres")]
 async fn check_recovery_mode(client: &Client) -> PreflightCheck {
     match client.query_one("SELECT pg_is_in_recovery()", &[]).await {
@@ -305,6 +530,27 @@ async fn check_lock_contention(client: &Client) -> PreflightCheck {
     }
 }
 
+#[cfg(feature = "postgres")]
+async fn check_freeze(client: &Client, query: &str) -> PreflightCheck {
+    let rows = match client.query(query, &[]).await {
+        Ok(rows) => rows,
+        Err(e) => return freeze_query_failed(e),
+    };
+    let mut frozen = None;
+    for row in &rows {
+        match row.try_get::<_, Option<bool>>(0) {
+            Ok(Some(true)) => {
+                let reason = row.try_get::<_, Option<String>>(1).ok().flatten();
+                frozen = Some(reason);
+                break;
+            }
+            Ok(_) => {}
+            Err(e) => return freeze_query_failed(e),
+        }
+    }
+    freeze_check(frozen)
+}
+
 // ── MySQL pre-flight checks ───────────────────────────────────────────────────
 
 #[cfg(feature = "mysql")]
@@ -319,9 +565,52 @@ async fn run_preflight_mysql(
     checks.push(check_replication_lag_mysql(client, config.max_replication_lag_secs).await);
     checks.push(check_database_size_mysql(client).await);
     checks.push(check_lock_contention_mysql(client).await);
+    checks.extend(mysql_policy_checks(client, config).await);
+    Ok(report(checks))
+}
 
-    let passed = !checks.iter().any(|c| c.status == CheckStatus::Fail);
-    Ok(PreflightReport { checks, passed })
+#[cfg(feature = "mysql")]
+async fn mysql_policy_checks(client: &DbClient, config: &PreflightConfig) -> Vec<PreflightCheck> {
+    let mut checks = policy_checks(config);
+    if let Some(query) = &config.freeze_query {
+        checks.push(check_freeze_mysql(client, query).await);
+    }
+    checks
+}
+
+#[cfg(feature = "mysql")]
+async fn check_freeze_mysql(client: &DbClient, query: &str) -> PreflightCheck {
+    use mysql_async::prelude::*;
+    let pool = match client.as_mysql() {
+        Ok(p) => p,
+        Err(e) => return freeze_query_failed(e),
+    };
+    let mut conn = match pool.get_conn().await {
+        Ok(c) => c,
+        Err(e) => return freeze_query_failed(e),
+    };
+    let rows: Vec<mysql_async::Row> = match conn.query(query).await {
+        Ok(rows) => rows,
+        Err(e) => return freeze_query_failed(e),
+    };
+    let mut frozen = None;
+    for mut row in rows {
+        // MySQL has no boolean type; TINYINT(1) and 0/1 expressions both map here.
+        match row.take_opt::<Option<bool>, _>(0) {
+            Some(Ok(Some(true))) => {
+                let reason = row
+                    .take_opt::<Option<String>, _>(1)
+                    .and_then(|r| r.ok())
+                    .flatten();
+                frozen = Some(reason);
+                break;
+            }
+            Some(Ok(_)) => {}
+            Some(Err(e)) => return freeze_query_failed(e),
+            None => return freeze_query_failed("query returned no columns"),
+        }
+    }
+    freeze_check(frozen)
 }
 
 #[cfg(feature = "mysql")]
@@ -622,3 +911,79 @@ async fn check_lock_contention_mysql(client: &DbClient) -> PreflightCheck {
         },
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    /// 2024-01-01 was a Monday.
+    fn at(day: u32, hour: u32, minute: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2024, 1, day, hour, minute, 0).unwrap()
+    }
+
+    #[test]
+    fn test_window_same_day() {
+        let w = MigrationWindow::parse("Mon-Fri 09:00-17:00").unwrap();
+        assert!(w.contains(at(1, 9, 0)));
+        assert!(w.contains(at(5, 16, 59)));
+        assert!(!w.contains(at(1, 17, 0)));
+        assert!(!w.contains(at(6, 12, 0)), "Saturday is outside Mon-Fri");
+    }
+
+    #[test]
+    fn test_window_past_midnight() {
+        let w = MigrationWindow::parse("Fri 22:00-06:00").unwrap();
+        assert!(w.contains(at(5, 23, 30)));
+        assert!(
+            w.contains(at(6, 5, 59)),
+            "Saturday morning continues Friday's window"
+        );
+        assert!(
+            !w.contains(at(5, 5, 0)),
+            "Friday morning belongs to Thursday"
+        );
+    }
+
+    #[test]
+    fn test_window_day_lists_and_wraparound() {
+        let every_day = MigrationWindow::parse("02:00-04:00").unwrap();
+        assert!(every_day.contains(at(7, 3, 0)));
+
+        let weekend = MigrationWindow::parse("sat,Sun 00:00-23:59").unwrap();
+        assert!(weekend.contains(at(7, 12, 0)));
+        assert!(!weekend.contains(at(3, 12, 0)));
+
+        let fri_to_mon = MigrationWindow::parse("Fri-Mon 10:00-11:00").unwrap();
+        assert!(fri_to_mon.contains(at(1, 10, 30)));
+        assert!(!fri_to_mon.contains(at(2, 10, 30)));
+    }
+
+    #[test]
+    fn test_window_parse_errors() {
+        for spec in [
+            "",
+            "Mon",
+            "Funday 01:00-02:00",
+            "Mon 25:00-26:00",
+            "01:00-01:00",
+        ] {
+            assert!(
+                MigrationWindow::parse(spec).is_err(),
+                "{spec:?} should fail"
+            );
+        }
+    }
+
+    #[test]
+    fn test_check_migration_window() {
+        let windows = vec!["Sat 01:00-03:00".to_string(), "Sun 01:00-03:00".to_string()];
+        assert_eq!(
+            check_migration_window(&windows, at(7, 2, 0)).status,
+            CheckStatus::Pass
+        );
+        let outside = check_migration_window(&windows, at(3, 2, 0));
+        assert_eq!(outside.status, CheckStatus::Fail);
+        assert!(outside.detail.contains("Wed 02:00"));
+    }
+}
@@ -0,0 +1,1364 @@
+//! Terminal output formatting for all waypoint commands (`render` feature).
+//! Uses comfy-table for tabular output and colored for
+//! severity-aware terminal styling.
+//!
+//! These are the renderers the `waypoint` CLI prints with, exposed so other
+//! binaries embedding waypoint can print identical output. Each function
+//! writes to stdout; colouring follows the `colored` crate's global control
+//! (`NO_COLOR`, `CLICOLOR_FORCE`, or `colored::control::set_override`).
+
+use std::collections::HashMap;
+use std::sync::OnceLock;
+
+use colored::Colorize;
+use comfy_table::modifiers::UTF8_ROUND_CORNERS;
+use comfy_table::presets::UTF8_FULL;
+use comfy_table::{Cell, ContentArrangement, Table};
+
+use crate::commands::info::{MigrationInfo, MigrationState};
+use crate::config::OutputTheme;
+use crate::messages::{hint, tr, Msg};
+
+/// Format migration info as a colored table.
+pub fn print_info_table(infos: &[MigrationInfo]) {
+    if infos.is_empty() {
+        println!("{}", tr(Msg::InfoEmpty, &[]).yellow());
+        return;
+    }
+
+    let mut table = Table::new();
+    table
+        .load_preset(UTF8_FULL)
+        .apply_modifier(UTF8_ROUND_CORNERS)
+        .set_content_arrangement(ContentArrangement::Dynamic)
+        .set_header(vec![
+            Cell::new("Version"),
+            Cell::new("Description"),
+            Cell::new("Type"),
+            Cell::new("State"),
+            Cell::new("Installed On"),
+            Cell::new("Execution Time"),
+        ]);
+
+    for info in infos {
+        let version = info.version.as_deref().unwrap_or("");
+        let installed_on = info
+            .installed_on
+            .map(|dt| dt.format("%Y-%m-%d %H:%M:%S").to_string())
+            .unwrap_or_default();
+        let exec_time = info
+            .execution_time
+            .map(|t| format!("{}ms", t))
+            .unwrap_or_default();
+
+        let state_str = format_state(&info.state);
+
+        table.add_row(vec![
+            Cell::new(version),
+            Cell::new(&info.description),
+            Cell::new(&info.migration_type),
+            Cell::new(&state_str),
+            Cell::new(&installed_on),
+            Cell::new(&exec_time),
+        ]);
+    }
+
+    println!("{table}");
+}
+
+static THEME: OnceLock<OutputTheme> = OnceLock::new();
+
+/// Select how tables mark migration states (`migrations.theme`). Only the
+/// first call takes effect; the default is [`OutputTheme::Color`].
+pub fn set_theme(theme: OutputTheme) {
+    let _ = THEME.set(theme);
+}
+
+/// Return a themed string representation of a migration state.
+fn format_state(state: &MigrationState) -> String {
+    let theme = THEME.get().copied().unwrap_or_default();
+    let (symbol, name) = match state {
+        MigrationState::Pending => ("○", "Pending"),
+        MigrationState::Applied => ("✓", "Applied"),
+        MigrationState::Failed => ("✗", "Failed"),
+        MigrationState::Missing => ("?", "Missing"),
+        MigrationState::Outdated => ("↻", "Outdated"),
+        MigrationState::OutOfOrder => ("↕", "Out of Order"),
+        MigrationState::BelowBaseline => ("·", "Below Baseline"),
+        MigrationState::Ignored => ("-", "Ignored"),
+        MigrationState::Baseline => ("⚑", "Baseline"),
+        MigrationState::Undone => ("↶", "Undone"),
+    };
+    match theme {
+        OutputTheme::Color => match state {
+            MigrationState::Pending => name.yellow(),
+            MigrationState::Applied => name.green(),
+            MigrationState::Failed => name.red().bold(),
+            MigrationState::Missing => name.red(),
+            MigrationState::Outdated => name.cyan(),
+            MigrationState::OutOfOrder => name.yellow(),
+            MigrationState::BelowBaseline => name.dimmed(),
+            MigrationState::Ignored => name.dimmed(),
+            MigrationState::Baseline => name.blue(),
+            MigrationState::Undone => name.magenta(),
+        }
+        .to_string(),
+        OutputTheme::Minimal => format!("{} {}", symbol, name),
+        OutputTheme::HighContrast => {
+            let label = format!("{} {}", symbol, name);
+            match state {
+                MigrationState::Applied | MigrationState::Baseline => label.bright_blue().bold(),
+                MigrationState::Pending | MigrationState::OutOfOrder => {
+                    label.bright_yellow().bold()
+                }
+                MigrationState::Failed | MigrationState::Missing => {
+                    label.bright_white().on_magenta().bold()
+                }
+                MigrationState::Outdated | MigrationState::Undone => label.bright_cyan().bold(),
+                MigrationState::BelowBaseline | MigrationState::Ignored => label.bright_white(),
+            }
+            .to_string()
+        }
+    }
+}
+
+/// Print the most recent successful migration (`info --last`).
+pub fn print_last_applied(report: &crate::LastAppliedReport) {
+    match &report.last_applied {
+        Some(last) => println!(
+            "{}",
+            tr(
+                Msg::InfoLast,
+                &[
+                    ("script", &last.script),
+                    (
+                        "installed_on",
+                        &last.installed_on.format("%Y-%m-%d %H:%M:%S UTC")
+                    ),
+                    ("installed_by", &last.installed_by),
+                ]
+            )
+        ),
+        None => println!("{}", tr(Msg::InfoNoneApplied, &[]).yellow()),
+    }
+}
+
+/// Print a migration report summary.
+pub fn print_migrate_summary(report: &crate::MigrateReport) {
+    for warning in &report.validation_warnings {
+        println!("{} {}", tr(Msg::LabelWarning, &[]).yellow().bold(), warning);
+    }
+
+    if report.hooks_executed > 0 {
+        println!(
+            "{}",
+            tr(
+                Msg::MigrateHooks,
+                &[
+                    ("count", &report.hooks_executed),
+                    ("ms", &report.hooks_time_ms)
+                ]
+            )
+            .dimmed()
+        );
+    }
+
+    print_repeatable_statuses(&report.repeatables);
+    print_table_size_changes(&report.table_sizes);
+    if !report.captured_ddl.is_empty() {
+        println!(
+            "{}",
+            format!(
+                "Captured {} DDL command(s) via event triggers",
+                report.captured_ddl.len()
+            )
+            .dimmed()
+        );
+    }
+    if let Some(transfer) = &report.ownership_transfer {
+        match &transfer.error {
+            None if transfer.objects.is_empty() => {}
+            None => println!(
+                "{}",
+                format!(
+                    "Transferred ownership of {} object(s) to '{}'",
+                    transfer.objects.len(),
+                    transfer.role
+                )
+                .dimmed()
+            ),
+            Some(e) => println!(
+                "{} Ownership transfer to '{}' failed, no objects were reassigned: {}",
+                tr(Msg::LabelWarning, &[]).yellow().bold(),
+                transfer.role,
+                e
+            ),
+        }
+    }
+    print_post_check_results(&report.post_checks);
+    if let Some(rollback) = &report.post_check_rollback {
+        match &rollback.error {
+            None => println!(
+                "{}",
+                format!(
+                    "Rolled back version(s) {} after failed post-checks",
+                    rollback.versions_undone.join(", ")
+                )
+                .yellow()
+                .bold()
+            ),
+            Some(e) => println!(
+                "{}",
+                format!("Rollback after failed post-checks did not complete: {}", e)
+                    .red()
+                    .bold()
+            ),
+        }
+    }
+
+    if report.migrations_applied == 0 {
+        println!("{}", tr(Msg::MigrateUpToDate, &[]).green());
+        return;
+    }
+
+    println!(
+        "{}",
+        tr(
+            Msg::MigrateApplied,
+            &[
+                ("count", &report.migrations_applied),
+                ("ms", &report.total_time_ms)
+            ]
+        )
+        .green()
+        .bold()
+    );
+
+    for detail in &report.details {
+        let version = detail.version.as_deref().unwrap_or("(repeatable)");
+        println!(
+            "  {} {} — {} ({}ms)",
+            "→".green(),
+            version,
+            detail.description,
+            detail.execution_time_ms
+        );
+        if !detail.touched_tables.is_empty() {
+            println!(
+                "    {}",
+                format!("touches: {}", detail.touched_tables.join(", ")).dimmed()
+            );
+        }
+    }
+}
+
+fn print_repeatable_statuses(statuses: &[crate::commands::migrate::RepeatableStatus]) {
+    use crate::commands::migrate::RepeatableReason;
+
+    for status in statuses.iter().filter(|s| s.applies()) {
+        println!(
+            "  {} {} ({})",
+            "R".cyan(),
+            status.script,
+            status.reason.to_string().dimmed()
+        );
+    }
+    for status in statuses
+        .iter()
+        .filter(|s| matches!(s.reason, RepeatableReason::Deferred { .. }))
+    {
+        println!(
+            "  {} {} ({})",
+            "R".yellow(),
+            status.script,
+            status.reason.to_string().yellow()
+        );
+    }
+    let unchanged = statuses
+        .iter()
+        .filter(|s| matches!(s.reason, RepeatableReason::Unchanged { .. }))
+        .count();
+    if unchanged > 0 {
+        println!(
+            "{}",
+            tr(Msg::MigrateSkippedRepeatables, &[("count", &unchanged)]).dimmed()
+        );
+    }
+}
+
+fn print_table_size_changes(changes: &[crate::table_stats::TableSizeChange]) {
+    if changes.is_empty() {
+        return;
+    }
+    let mut table = Table::new();
+    table
+        .load_preset(UTF8_FULL)
+        .apply_modifier(UTF8_ROUND_CORNERS)
+        .set_content_arrangement(ContentArrangement::Dynamic)
+        .set_header(vec![
+            Cell::new("Table"),
+            Cell::new("Before"),
+            Cell::new("After"),
+            Cell::new("Change"),
+        ]);
+    for change in changes {
+        let size = |bytes: Option<i64>| bytes.map_or_else(|| "-".to_string(), format_bytes);
+        let delta = if change.delta_bytes < 0 {
+            format!("-{}", format_bytes(-change.delta_bytes))
+        } else {
+            format!("+{}", format_bytes(change.delta_bytes))
+        };
+        table.add_row(vec![
+            Cell::new(&change.table),
+            Cell::new(size(change.before_bytes)),
+            Cell::new(size(change.after_bytes)),
+            Cell::new(delta),
+        ]);
+    }
+    println!("{table}");
+}
+
+/// Render a byte count with a binary unit, e.g. `1.5 GiB`.
+fn format_bytes(bytes: i64) -> String {
+    const UNITS: [&str; 5] = ["B", "KiB", "MiB", "GiB", "TiB"];
+    let mut value = bytes as f64;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{} B", bytes)
+    } else {
+        format!("{:.1} {}", value, UNITS[unit])
+    }
+}
+
+fn print_post_check_results(results: &[crate::post_check::PostCheckResult]) {
+    for result in results {
+        if result.passed {
+            println!(
+                "{}",
+                format!("Post-check '{}' passed: {}", result.name, result.detail).dimmed()
+            );
+        } else {
+            println!(
+                "{}",
+                format!("Post-check '{}' FAILED: {}", result.name, result.detail)
+                    .red()
+                    .bold()
+            );
+        }
+    }
+}
+
+/// Print a validate report.
+pub fn print_validate_result(report: &crate::ValidateReport) {
+    if report.valid {
+        println!("{}", tr(Msg::ValidateOk, &[]).green().bold());
+    }
+
+    for warning in &report.warnings {
+        println!("{} {}", tr(Msg::LabelWarning, &[]).yellow().bold(), warning);
+    }
+
+    for issue in &report.issues {
+        println!("{} {}", tr(Msg::LabelError, &[]).red().bold(), issue);
+    }
+}
+
+/// Print a repair report.
+pub fn print_repair_result(report: &crate::RepairReport) {
+    if report.failed_removed == 0 && report.checksums_updated == 0 {
+        println!("{}", tr(Msg::RepairNone, &[]).green());
+        return;
+    }
+
+    println!("{}", tr(Msg::RepairDone, &[]).green().bold());
+    for detail in &report.details {
+        println!("  {} {}", "→".green(), detail);
+    }
+}
+
+/// Print an undo report summary.
+pub fn print_undo_summary(report: &crate::UndoReport) {
+    if report.migrations_undone == 0 {
+        println!("{}", tr(Msg::UndoNone, &[]).green());
+        return;
+    }
+
+    println!(
+        "{}",
+        tr(
+            Msg::UndoDone,
+            &[
+                ("count", &report.migrations_undone),
+                ("ms", &report.total_time_ms)
+            ]
+        )
+        .green()
+        .bold()
+    );
+
+    for detail in &report.details {
+        println!(
+            "  {} {} — {} ({}ms)",
+            "←".magenta(),
+            detail.version,
+            detail.description,
+            detail.execution_time_ms
+        );
+    }
+}
+
+/// Print items dropped by clean.
+pub fn print_clean_result(dropped: &[String]) {
+    if dropped.is_empty() {
+        println!("{}", tr(Msg::CleanNone, &[]).green());
+        return;
+    }
+
+    println!(
+        "{}",
+        tr(Msg::CleanDone, &[("count", &dropped.len())])
+            .green()
+            .bold()
+    );
+    for item in dropped {
+        println!("  {} {}", "✗".red(), item);
+    }
+}
+
+/// Print lint report with colored severity.
+pub fn print_lint_report(report: &crate::LintReport) {
+    if report.issues.is_empty() {
+        println!(
+            "{}",
+            format!("Checked {} file(s). No issues found.", report.files_checked)
+                .green()
+                .bold()
+        );
+        return;
+    }
+
+    println!(
+        "{}",
+        format!(
+            "Checked {} file(s): {} error(s), {} warning(s), {} info",
+            report.files_checked, report.error_count, report.warning_count, report.info_count
+        )
+        .bold()
+    );
+    println!();
+
+    for issue in &report.issues {
+        let severity = match issue.severity {
+            crate::commands::lint::LintSeverity::Error => {
+                format!("[{}]", issue.rule_id).red().bold().to_string()
+            }
+            crate::commands::lint::LintSeverity::Warning => {
+                format!("[{}]", issue.rule_id).yellow().bold().to_string()
+            }
+            crate::commands::lint::LintSeverity::Info => {
+                format!("[{}]", issue.rule_id).blue().to_string()
+            }
+        };
+
+        let line_info = issue.line.map(|l| format!(":{}", l)).unwrap_or_default();
+
+        println!(
+            "  {} {}{} {}",
+            severity, issue.script, line_info, issue.message
+        );
+
+        if let Some(ref suggestion) = issue.suggestion {
+            println!("    {} {}", "→".dimmed(), suggestion.dimmed());
+        }
+    }
+}
+
+/// Print diff report.
+pub fn print_diff_report(report: &crate::DiffReport) {
+    if !report.has_changes {
+        println!("{}", "No schema differences detected.".green().bold());
+        return;
+    }
+
+    println!(
+        "{}",
+        format!("Found {} schema difference(s):", report.diffs.len())
+            .yellow()
+            .bold()
+    );
+    println!();
+
+    for diff in &report.diffs {
+        let line = format!("{}", diff);
+        if line.starts_with('+') {
+            println!("  {}", line.green());
+        } else if line.starts_with('-') {
+            println!("  {}", line.red());
+        } else {
+            println!("  {}", line.yellow());
+        }
+    }
+
+    if !report.generated_sql.is_empty() {
+        println!();
+        println!("{}", "Generated SQL:".bold());
+        println!("{}", report.generated_sql.dimmed());
+    }
+}
+
+/// Print migration lock status.
+pub fn print_lock_report(report: &crate::LockReport) {
+    for pid in &report.terminated {
+        println!(
+            "{}",
+            tr(Msg::LockTerminated, &[("pid", pid)]).yellow().bold()
+        );
+    }
+    if !report.terminated.is_empty() {
+        return;
+    }
+
+    if report.holders.is_empty() {
+        println!(
+            "{}",
+            tr(
+                Msg::LockFree,
+                &[("table", &report.table), ("key", &report.lock_key)]
+            )
+            .green()
+        );
+        return;
+    }
+
+    let mut table = Table::new();
+    table
+        .load_preset(UTF8_FULL)
+        .apply_modifier(UTF8_ROUND_CORNERS)
+        .set_content_arrangement(ContentArrangement::Dynamic)
+        .set_header(vec![
+            Cell::new("PID"),
+            Cell::new("Lock"),
+            Cell::new("Application"),
+            Cell::new("User"),
+            Cell::new("Client"),
+            Cell::new("Connected"),
+            Cell::new("State"),
+        ]);
+    for holder in &report.holders {
+        table.add_row(vec![
+            Cell::new(holder.pid),
+            Cell::new(if holder.granted { "held" } else { "waiting" }),
+            Cell::new(holder.application_name.as_deref().unwrap_or("")),
+            Cell::new(holder.user.as_deref().unwrap_or("")),
+            Cell::new(holder.client_addr.as_deref().unwrap_or("")),
+            Cell::new(
+                holder
+                    .backend_start
+                    .map(|t| t.format("%Y-%m-%d %H:%M:%S").to_string())
+                    .unwrap_or_default(),
+            ),
+            Cell::new(holder.state.as_deref().unwrap_or("")),
+        ]);
+    }
+    println!(
+        "{}",
+        tr(
+            Msg::LockHeld,
+            &[("table", &report.table), ("key", &report.lock_key)]
+        )
+        .bold()
+    );
+    println!("{table}");
+    if report.is_held() {
+        println!("{}", hint(Msg::HintLockRelease, &[]).dimmed());
+    }
+}
+
+/// Print drift report.
+pub fn print_drift_report(report: &crate::DriftReport) {
+    if !report.has_drift {
+        println!(
+            "{}",
+            tr(Msg::DriftNone, &[("schema", &report.schema)])
+                .green()
+                .bold()
+        );
+        return;
+    }
+
+    println!(
+        "{}",
+        tr(
+            Msg::DriftFound,
+            &[("schema", &report.schema), ("count", &report.drifts.len())]
+        )
+        .red()
+        .bold()
+    );
+    println!();
+
+    for drift in &report.drifts {
+        let icon = match drift.drift_type {
+            crate::commands::drift::DriftType::ExtraObject => "+".green(),
+            crate::commands::drift::DriftType::MissingObject => "-".red(),
+            crate::commands::drift::DriftType::ModifiedObject => "~".yellow(),
+        };
+        println!("  {} {} — {}", icon, drift.object, drift.detail.dimmed());
+    }
+
+    println!();
+    println!("{}", hint(Msg::HintDriftDetected, &[]).dimmed());
+}
+
+/// Print snapshot report.
+pub fn print_snapshot_report(report: &crate::SnapshotReport) {
+    println!(
+        "{}",
+        format!(
+            "Snapshot '{}' created ({} objects captured)",
+            report.snapshot_id, report.objects_captured
+        )
+        .green()
+        .bold()
+    );
+    println!("  {} {}", "→".green(), report.snapshot_path);
+}
+
+/// Print restore report.
+pub fn print_restore_report(report: &crate::RestoreReport) {
+    println!(
+        "{}",
+        format!(
+            "Restored from snapshot '{}' ({} objects restored)",
+            report.snapshot_id, report.objects_restored
+        )
+        .green()
+        .bold()
+    );
+}
+
+/// Print list of available snapshots.
+pub fn print_snapshot_list(snapshots: &[crate::commands::snapshot::SnapshotInfo]) {
+    if snapshots.is_empty() {
+        println!("{}", "No snapshots found.".yellow());
+        return;
+    }
+
+    let mut table = Table::new();
+    table
+        .load_preset(UTF8_FULL)
+        .apply_modifier(UTF8_ROUND_CORNERS)
+        .set_content_arrangement(ContentArrangement::Dynamic)
+        .set_header(vec![
+            Cell::new("ID"),
+            Cell::new("Created"),
+            Cell::new("Size"),
+        ]);
+
+    for s in snapshots {
+        let size = if s.size_bytes > 1024 * 1024 {
+            format!("{:.1}MB", s.size_bytes as f64 / (1024.0 * 1024.0))
+        } else if s.size_bytes > 1024 {
+            format!("{:.1}KB", s.size_bytes as f64 / 1024.0)
+        } else {
+            format!("{}B", s.size_bytes)
+        };
+        table.add_row(vec![
+            Cell::new(&s.id),
+            Cell::new(&s.created),
+            Cell::new(&size),
+        ]);
+    }
+
+    println!("{table}");
+}
+
+/// Print preflight report.
+pub fn print_preflight_report(report: &crate::PreflightReport) {
+    println!(
+        "{}",
+        if report.passed {
+            tr(Msg::PreflightPassed, &[]).green().bold()
+        } else {
+            tr(Msg::PreflightFailed, &[]).red().bold()
+        }
+    );
+    println!();
+
+    for check in &report.checks {
+        let icon = match check.status {
+            crate::preflight::CheckStatus::Pass => "✓".green(),
+            crate::preflight::CheckStatus::Warn => "!".yellow(),
+            crate::preflight::CheckStatus::Fail => "✗".red(),
+        };
+        println!("  {} {} — {}", icon, check.name, check.detail);
+    }
+}
+
+/// Print explain report (enhanced dry-run).
+pub fn print_explain_report(report: &crate::ExplainReport) {
+    print_repeatable_statuses(&report.repeatables);
+    if report.migrations.is_empty() {
+        println!("{}", tr(Msg::ExplainNone, &[]).green());
+        return;
+    }
+
+    println!(
+        "{}",
+        tr(Msg::ExplainPending, &[("count", &report.migrations.len())])
+            .yellow()
+            .bold()
+    );
+    println!();
+
+    for migration in &report.migrations {
+        let version = migration.version.as_deref().unwrap_or("(repeatable)");
+        println!("  {} {} [{}]", "→".yellow(), version, migration.script);
+        if !migration.touched_tables.is_empty() {
+            println!(
+                "    {}",
+                format!("touches: {}", migration.touched_tables.join(", ")).dimmed()
+            );
+        }
+
+        for (i, stmt) in migration.statements.iter().enumerate() {
+            let prefix = format!("    [{}/{}]", i + 1, migration.statements.len());
+            if stmt.is_ddl {
+                println!(
+                    "  {} {} {}",
+                    prefix.dimmed(),
+                    stmt.statement_preview.dimmed(),
+                    "(DDL)".dimmed()
+                );
+            } else {
+                let cost_info = match (stmt.estimated_rows, stmt.estimated_cost) {
+                    (Some(rows), Some(cost)) => {
+                        format!("(~{:.0} rows, cost {:.1})", rows, cost)
+                    }
+                    _ => String::new(),
+                };
+                println!(
+                    "  {} {} {}",
+                    prefix.dimmed(),
+                    stmt.statement_preview,
+                    cost_info.dimmed()
+                );
+            }
+
+            for warning in &stmt.warnings {
+                println!("    {} {}", "!".yellow(), warning.yellow());
+            }
+        }
+        if let Some(draft) = &migration.undo_draft {
+            println!(
+                "    {}",
+                format!(
+                    "no undo script; a draft reverses {} statement(s), {} need a manual undo \
+                     (--with-undo prints it)",
+                    draft.reversed,
+                    draft.unsupported.len()
+                )
+                .dimmed()
+            );
+        }
+        println!();
+    }
+}
+
+/// Print the draft undo scripts of a dry-run plan, one file after another.
+pub fn print_undo_drafts(report: &crate::ExplainReport) {
+    use crate::commands::new::{undo_file_contents, undo_script_name};
+
+    for migration in &report.migrations {
+        let Some(draft) = &migration.undo_draft else {
+            continue;
+        };
+        println!(
+            "{}",
+            format!(
+                "-- {} (draft, not written)",
+                undo_script_name(&migration.script)
+            )
+            .bold()
+        );
+        println!("{}", undo_file_contents(&migration.script, draft));
+    }
+}
+
+/// Print the files created by `waypoint new`.
+pub fn print_new_report(report: &crate::NewReport) {
+    for path in &report.created {
+        println!("{} {}", "Created".green().bold(), path);
+    }
+    if let Some(draft) = &report.undo_draft {
+        if !draft.is_complete() {
+            println!(
+                "{}",
+                format!(
+                    "{} statement(s) need a manual undo; see the TODO lines in the undo script.",
+                    draft.unsupported.len()
+                )
+                .yellow()
+            );
+        }
+    }
+}
+
+/// Print import report.
+pub fn print_import_report(report: &crate::ImportReport) {
+    let mut table = Table::new();
+    table
+        .load_preset(UTF8_FULL)
+        .apply_modifier(UTF8_ROUND_CORNERS)
+        .set_content_arrangement(ContentArrangement::Dynamic)
+        .set_header(vec![
+            Cell::new("Source"),
+            Cell::new("Version"),
+            Cell::new("Script"),
+            Cell::new("TODOs"),
+        ]);
+    for m in &report.migrations {
+        let todos = if m.todos > 0 {
+            m.todos.to_string().yellow().to_string()
+        } else {
+            String::new()
+        };
+        table.add_row(vec![
+            Cell::new(&m.source_id),
+            Cell::new(m.version.as_deref().unwrap_or("(repeatable)")),
+            Cell::new(&m.script),
+            Cell::new(todos),
+        ]);
+    }
+    println!("{table}");
+    println!(
+        "{}",
+        format!(
+            "Imported {} {} migration(s) into {}; mapping written to {}.",
+            report.migrations.len(),
+            report.source,
+            report.output,
+            crate::commands::import::MAPPING_FILE
+        )
+        .green()
+        .bold()
+    );
+    if report.todo_count > 0 {
+        println!(
+            "{}",
+            format!(
+                "{} change(s) need manual conversion; see the TODO lines in the files above.",
+                report.todo_count
+            )
+            .yellow()
+        );
+    }
+    println!(
+        "To adopt a database already at a given revision, run \
+         'waypoint baseline --baseline-version <version>' with the version it maps to."
+    );
+}
+
+/// Print conflict report.
+pub fn print_conflict_report(report: &crate::ConflictReport) {
+    if !report.has_conflicts {
+        println!(
+            "{}",
+            format!(
+                "No migration conflicts detected against '{}'.",
+                report.base_branch
+            )
+            .green()
+            .bold()
+        );
+        return;
+    }
+
+    println!(
+        "{}",
+        format!(
+            "Migration conflicts detected against '{}': {} conflict(s)",
+            report.base_branch,
+            report.conflicts.len()
+        )
+        .red()
+        .bold()
+    );
+    println!();
+
+    for conflict in &report.conflicts {
+        let icon = match conflict.conflict_type {
+            crate::commands::check_conflicts::ConflictType::VersionCollision => "!!".red().bold(),
+            crate::commands::check_conflicts::ConflictType::SemanticConflict => {
+                "!~".yellow().bold()
+            }
+        };
+        println!(
+            "  {} {} — {}",
+            icon, conflict.conflict_type, conflict.description
+        );
+        for file in &conflict.files {
+            println!("    {} {}", "→".dimmed(), file);
+        }
+    }
+}
+
+/// Print multi-database result.
+pub fn print_multi_result(result: &crate::multi::MultiResult) {
+    for r in &result.results {
+        let icon = if r.success {
+            "✓".green()
+        } else {
+            "✗".red()
+        };
+        println!("  {} [{}] {}", icon, r.name, r.message);
+    }
+
+    if result.all_succeeded {
+        println!(
+            "{}",
+            format!(
+                "All {} database(s) migrated successfully.",
+                result.results.len()
+            )
+            .green()
+            .bold()
+        );
+    } else {
+        let failed = result.results.iter().filter(|r| !r.success).count();
+        println!("{}", format!("{} database(s) failed.", failed).red().bold());
+    }
+}
+
+/// Print multi-database info.
+pub fn print_multi_info(all_info: &HashMap<String, Vec<MigrationInfo>>) {
+    for (name, infos) in all_info {
+        println!("{}", format!("=== {} ===", name).bold());
+        print_info_table(infos);
+        println!();
+    }
+}
+
+/// Print a safety analysis report for a single migration.
+pub fn print_safety_report(report: &crate::SafetyReport) {
+    let verdict_str = match report.overall_verdict {
+        crate::safety::SafetyVerdict::Safe => "SAFE".green().bold(),
+        crate::safety::SafetyVerdict::Caution => "CAUTION".yellow().bold(),
+        crate::safety::SafetyVerdict::Danger => "DANGER".red().bold(),
+    };
+
+    println!(
+        "  {} [{}] {}",
+        verdict_str, report.script, report.overall_verdict
+    );
+
+    for stmt in &report.statements {
+        let icon = match stmt.verdict {
+            crate::safety::SafetyVerdict::Safe => "✓".green(),
+            crate::safety::SafetyVerdict::Caution => "!".yellow(),
+            crate::safety::SafetyVerdict::Danger => "✗".red(),
+        };
+        let table_info = stmt
+            .affected_table
+            .as_ref()
+            .map(|t| {
+                let size = stmt
+                    .estimated_rows
+                    .map(|r| format!(" (~{} rows)", r))
+                    .unwrap_or_default();
+                format!(" on {}{}", t, size)
+            })
+            .unwrap_or_default();
+
+        println!(
+            "    {} {} — {}{}",
+            icon, stmt.statement_preview, stmt.lock_level, table_info
+        );
+
+        if stmt.data_loss {
+            println!(
+                "      {} {}",
+                "⚠".red(),
+                "Data loss: operation is irreversible".red()
+            );
+        }
+
+        for suggestion in &stmt.suggestions {
+            println!("      {} {}", "→".dimmed(), suggestion.dimmed());
+        }
+    }
+}
+
+/// Print the overall safety verdict.
+pub fn print_safety_overall(verdict: crate::safety::SafetyVerdict) {
+    let msg = match verdict {
+        crate::safety::SafetyVerdict::Safe => "Overall: SAFE — all migrations can proceed safely."
+            .green()
+            .bold(),
+        crate::safety::SafetyVerdict::Caution => {
+            "Overall: CAUTION — some migrations require attention."
+                .yellow()
+                .bold()
+        }
+        crate::safety::SafetyVerdict::Danger => {
+            "Overall: DANGER — some migrations are high risk. Use --force to override."
+                .red()
+                .bold()
+        }
+    };
+    println!("\n{}", msg);
+}
+
+/// Print advisor report.
+pub fn print_advisor_report(report: &crate::AdvisorReport) {
+    if report.advisories.is_empty() {
+        println!(
+            "{}",
+            format!("Schema '{}' looks good. No advisories.", report.schema)
+                .green()
+                .bold()
+        );
+        return;
+    }
+
+    println!(
+        "{}",
+        format!(
+            "Schema '{}': {} advisory(ies) ({} warning, {} suggestion, {} info)",
+            report.schema,
+            report.advisories.len(),
+            report.warning_count,
+            report.suggestion_count,
+            report.info_count
+        )
+        .bold()
+    );
+    println!();
+
+    for advisory in &report.advisories {
+        let severity = match advisory.severity {
+            crate::advisor::AdvisorySeverity::Warning => {
+                format!("[{}]", advisory.rule_id).red().bold().to_string()
+            }
+            crate::advisor::AdvisorySeverity::Suggestion => format!("[{}]", advisory.rule_id)
+                .yellow()
+                .bold()
+                .to_string(),
+            crate::advisor::AdvisorySeverity::Info => {
+                format!("[{}]", advisory.rule_id).blue().to_string()
+            }
+        };
+
+        println!(
+            "  {} {} — {} ({})",
+            severity, advisory.object, advisory.explanation, advisory.category
+        );
+
+        if let Some(ref fix) = advisory.fix_sql {
+            println!("    {} {}", "fix:".dimmed(), fix.dimmed());
+        }
+    }
+}
+
+/// Print simulation report.
+pub fn print_simulation_report(report: &crate::SimulationReport) {
+    if report.passed {
+        println!(
+            "{}",
+            format!(
+                "Simulation passed: {} migration(s) applied successfully in temp schema.",
+                report.migrations_simulated
+            )
+            .green()
+            .bold()
+        );
+    } else {
+        println!(
+            "{}",
+            format!(
+                "Simulation FAILED: {} error(s) in temp schema.",
+                report.errors.len()
+            )
+            .red()
+            .bold()
+        );
+        for error in &report.errors {
+            println!("  {} {} — {}", "✗".red(), error.script, error.error);
+        }
+    }
+
+    if !report.warnings.is_empty() {
+        println!(
+            "{}",
+            format!(
+                "Simulation warnings ({}): some source objects could not be replicated into the temp schema.",
+                report.warnings.len()
+            )
+            .yellow()
+        );
+        for w in &report.warnings {
+            println!("  {} {}", "!".yellow(), w);
+        }
+    }
+}
+
+/// Print the result of `verify --shadow-url`.
+#[cfg(feature = "postgres")]
+pub fn print_verify_report(report: &crate::VerifyReport) {
+    if report.passed {
+        println!(
+            "{}",
+            format!(
+                "Shadow verification passed: {} migration(s) replayed, schema '{}' matches.",
+                report.replayed.len(),
+                report.schema
+            )
+            .green()
+            .bold()
+        );
+        return;
+    }
+
+    if !report.differences.is_empty() {
+        println!(
+            "{}",
+            format!(
+                "Shadow verification FAILED: schema '{}' differs from a replay of {} migration(s) in {} difference(s)",
+                report.schema,
+                report.replayed.len(),
+                report.differences.len()
+            )
+            .red()
+            .bold()
+        );
+        println!();
+        for diff in &report.differences {
+            println!("  {}", diff);
+        }
+        println!();
+        println!(
+            "{}",
+            "The schema was changed outside migrations, or a migration does not replay deterministically."
+                .dimmed()
+        );
+    }
+
+    if !report.repeatable_issues.is_empty() {
+        if !report.differences.is_empty() {
+            println!();
+        }
+        println!(
+            "{}",
+            format!(
+                "Shadow verification FAILED: {} repeatable migration(s) are not re-runnable",
+                report.repeatable_issues.len()
+            )
+            .red()
+            .bold()
+        );
+        println!();
+        for issue in &report.repeatable_issues {
+            println!("  {} {} — {}", "✗".red(), issue.script, issue.problem);
+        }
+    }
+}
+
+/// Print the result of provisioning a template database.
+#[cfg(feature = "postgres")]
+pub fn print_provision_template_report(report: &crate::ProvisionTemplateReport) {
+    println!(
+        "{}",
+        format!(
+            "Template database '{}' provisioned ({} migration(s) applied{})",
+            report.template,
+            report.migrations_applied,
+            if report.replaced {
+                ", replaced existing"
+            } else {
+                ""
+            }
+        )
+        .green()
+        .bold()
+    );
+}
+
+/// Print the result of cloning a database from a template.
+#[cfg(feature = "postgres")]
+pub fn print_clone_report(report: &crate::CloneReport) {
+    println!(
+        "{}",
+        format!(
+            "Created database '{}' from template '{}'",
+            report.database, report.template
+        )
+        .green()
+        .bold()
+    );
+}
+
+// ── --quiet summaries ────────────────────────────────────────────────────────
+//
+// Under `--quiet` the CLI prints no tables: nothing when a command's result
+// is healthy, otherwise the single line returned by the matching function
+// below. Failures are errors and go to stderr either way.
+
+/// Quiet line for migrate: validation warnings or a failed ownership transfer.
+pub fn quiet_migrate(report: &crate::MigrateReport) -> Option<String> {
+    let ownership_failed = report
+        .ownership_transfer
+        .as_ref()
+        .is_some_and(|t| t.error.is_some());
+    let warnings = report.validation_warnings.len() + usize::from(ownership_failed);
+    (warnings > 0).then(|| {
+        format!(
+            "Applied {} migration(s) with {} warning(s)",
+            report.migrations_applied, warnings
+        )
+    })
+}
+
+/// Quiet line for info: failed migrations, or applied ones whose file is gone.
+pub fn quiet_info(infos: &[MigrationInfo]) -> Option<String> {
+    let count = |state: MigrationState| infos.iter().filter(|i| i.state == state).count();
+    let (failed, missing) = (
+        count(MigrationState::Failed),
+        count(MigrationState::Missing),
+    );
+    (failed + missing > 0).then(|| format!("{} failed, {} missing migration(s)", failed, missing))
+}
+
+/// Quiet line for `info --last`: only when nothing has been applied yet.
+pub fn quiet_last_applied(report: &crate::LastAppliedReport) -> Option<String> {
+    report
+        .last_applied
+        .is_none()
+        .then(|| tr(Msg::InfoNoneApplied, &[]))
+}
+
+/// Quiet line for validate: warnings on an otherwise valid history.
+pub fn quiet_validate(report: &crate::ValidateReport) -> Option<String> {
+    (!report.warnings.is_empty())
+        .then(|| format!("Validated with {} warning(s)", report.warnings.len()))
+}
+
+/// Quiet line for lint: any errors or warnings.
+pub fn quiet_lint(report: &crate::LintReport) -> Option<String> {
+    (report.error_count + report.warning_count > 0).then(|| {
+        format!(
+            "Checked {} file(s): {} error(s), {} warning(s)",
+            report.files_checked, report.error_count, report.warning_count
+        )
+    })
+}
+
+/// Quiet line for diff: the number of differences found.
+pub fn quiet_diff(report: &crate::DiffReport) -> Option<String> {
+    report
+        .has_changes
+        .then(|| format!("Found {} schema difference(s)", report.diffs.len()))
+}
+
+/// Quiet line for `lock status`: who holds the lock. A release that
+/// terminated the holder did what was asked and prints nothing.
+pub fn quiet_lock(report: &crate::LockReport) -> Option<String> {
+    if !report.terminated.is_empty() {
+        return None;
+    }
+    report
+        .holders
+        .iter()
+        .find(|h| h.granted)
+        .map(|h| format!("Migration lock is held by pid {}", h.pid))
+}
+
+/// Quiet line for preflight: failed or warning checks.
+pub fn quiet_preflight(report: &crate::PreflightReport) -> Option<String> {
+    if !report.passed {
+        return Some(tr(Msg::PreflightFailed, &[]));
+    }
+    let warnings = report
+        .checks
+        .iter()
+        .filter(|c| c.status == crate::preflight::CheckStatus::Warn)
+        .count();
+    (warnings > 0).then(|| format!("Pre-flight checks passed with {} warning(s)", warnings))
+}
+
+/// Quiet line for import: `-- TODO` lines left to convert by hand.
+pub fn quiet_import(report: &crate::ImportReport) -> Option<String> {
+    (report.todo_count > 0).then(|| {
+        format!(
+            "Imported {} migration(s); {} TODO line(s) need manual conversion",
+            report.migrations.len(),
+            report.todo_count
+        )
+    })
+}
+
+/// Quiet line for `safety <file>`: any verdict other than SAFE.
+pub fn quiet_safety(report: &crate::SafetyReport) -> Option<String> {
+    (report.overall_verdict != crate::safety::SafetyVerdict::Safe)
+        .then(|| format!("{}: {}", report.script, report.overall_verdict))
+}
+
+/// Quiet line for `safety` over all pending migrations.
+pub fn quiet_safety_overall(verdict: crate::safety::SafetyVerdict) -> Option<String> {
+    (verdict != crate::safety::SafetyVerdict::Safe).then(|| format!("Overall: {}", verdict))
+}
+
+/// Quiet line for advise: the number of advisories.
+pub fn quiet_advisor(report: &crate::AdvisorReport) -> Option<String> {
+    (!report.advisories.is_empty()).then(|| {
+        format!(
+            "Schema '{}': {} advisory(ies) ({} warning, {} suggestion, {} info)",
+            report.schema,
+            report.advisories.len(),
+            report.warning_count,
+            report.suggestion_count,
+            report.info_count
+        )
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_quiet_lines_only_when_attention_is_needed() {
+        let mut lint: crate::LintReport = serde_json::from_value(serde_json::json!({
+            "issues": [],
+            "files_checked": 12,
+            "error_count": 0,
+            "warning_count": 0,
+            "info_count": 3,
+        }))
+        .unwrap();
+        assert_eq!(quiet_lint(&lint), None);
+        lint.warning_count = 2;
+        assert_eq!(
+            quiet_lint(&lint).as_deref(),
+            Some("Checked 12 file(s): 0 error(s), 2 warning(s)")
+        );
+
+        let mut lock: crate::LockReport = serde_json::from_value(serde_json::json!({
+            "table": "waypoint_schema_history",
+            "lock_key": "1",
+            "holders": [{"pid": 4242, "granted": true, "application_name": null,
+                         "user": null, "client_addr": null, "backend_start": null,
+                         "state": "idle", "query": null}],
+        }))
+        .unwrap();
+        assert_eq!(
+            quiet_lock(&lock).as_deref(),
+            Some("Migration lock is held by pid 4242")
+        );
+        lock.terminated.push(4242);
+        assert_eq!(quiet_lock(&lock), None);
+    }
+}
@@ -3,14 +3,24 @@
 //! Captures before/after schema snapshots around migration execution
 //! and generates reverse DDL that can undo the migration without
 //! requiring manual `U{version}__*.sql` files.
+//!
+//! [`draft_undo`] works from the migration text alone, before it has run, and
+//! drafts a `U{version}__*.sql` script for the simple, reversible statements:
+//! `CREATE TABLE`, named `CREATE INDEX`, and `ALTER TABLE ... ADD COLUMN`,
+//! in the syntax of the target dialect. Their `IF NOT EXISTS` forms are
+//! drafted commented out, since the object may have existed before.
+
+use std::sync::LazyLock;
 
-use serde::Serialize;
+use regex_lite::Regex;
+use serde::{Deserialize, Serialize};
 
 #[cfg(feature = "postgres")]
 use tokio_postgres::Client;
 
 #[cfg(feature = "postgres")]
 use crate::db::quote_ident;
+
 use crate::db::DbClient;
 use crate::dialect::DialectKind;
 use crate::error::Result;
@@ -325,11 +335,330 @@ pub async fn get_reversal_db(
     }
 }
 
+// ── Static undo drafts ───────────────────────────────────────────────────────
+
+/// An identifier or `schema.name`, plain, double-quoted or backtick-quoted,
+/// possibly a `${...}` placeholder.
+const NAME: &str = r#"(?:"[^"]+"|`[^`]+`|[\w${}:]+)(?:\.(?:"[^"]+"|`[^`]+`|[\w${}:]+))?"#;
+
+static DRAFT_CREATE_TABLE_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(&format!(
+        r"(?i)^CREATE\s+(?:(?:GLOBAL|LOCAL)\s+)?(?:(?:TEMP|TEMPORARY|UNLOGGED)\s+)?TABLE\s+(IF\s+NOT\s+EXISTS\s+)?({NAME})"
+    ))
+    .unwrap()
+});
+
+static DRAFT_CREATE_INDEX_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(&format!(
+        r"(?i)^CREATE\s+(?:UNIQUE\s+)?INDEX\s+(CONCURRENTLY\s+)?(IF\s+NOT\s+EXISTS\s+)?({NAME})\s+ON\s+(?:ONLY\s+)?({NAME})"
+    ))
+    .unwrap()
+});
+
+static DRAFT_ALTER_TABLE_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(&format!(
+        r"(?is)^ALTER\s+TABLE\s+(?:IF\s+EXISTS\s+)?(?:ONLY\s+)?({NAME})\s+(.+)$"
+    ))
+    .unwrap()
+});
+
+static DRAFT_ADD_COLUMN_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(&format!(
+        r"(?i)^ADD\s+(COLUMN\s+)?(IF\s+NOT\s+EXISTS\s+)?({NAME})"
+    ))
+    .unwrap()
+});
+
+/// Words after `ADD` that start a constraint rather than a column.
+const CONSTRAINT_KEYWORDS: &[&str] = &[
+    "CONSTRAINT",
+    "PRIMARY",
+    "UNIQUE",
+    "FOREIGN",
+    "CHECK",
+    "EXCLUDE",
+];
+
+/// Further words after `ADD` that start an index on MySQL (`ADD INDEX`,
+/// `ADD KEY`, ...). PostgreSQL accepts these as column names.
+const MYSQL_INDEX_KEYWORDS: &[&str] = &["INDEX", "KEY", "FULLTEXT", "SPATIAL"];
+
+/// A draft undo script derived from a migration's SQL.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UndoDraft {
+    /// Reverse statements, last statement first. Statements that could not
+    /// be reversed appear in place as `-- TODO` comments, and reversals of
+    /// `IF NOT EXISTS` statements commented out under a `-- WARNING`.
+    pub sql: String,
+    /// Number of statements reversed.
+    pub reversed: usize,
+    /// Previews of the statements that need a manual undo.
+    pub unsupported: Vec<String>,
+}
+
+/// How a statement reverses.
+enum Reversal {
+    /// The statement always created what the reversal drops.
+    Exact(String),
+    /// `IF NOT EXISTS`: the object may predate the migration, and dropping
+    /// it would destroy something the migration did not create.
+    IfCreated(String),
+}
+
+impl UndoDraft {
+    /// Whether every statement of the migration was reversed.
+    pub fn is_complete(&self) -> bool {
+        self.unsupported.is_empty()
+    }
+}
+
+/// Draft undo SQL for a migration from its text.
+///
+/// Each `CREATE TABLE` becomes a `DROP TABLE`, each named `CREATE INDEX` a
+/// `DROP INDEX` (keeping `CONCURRENTLY`), and each `ALTER TABLE` made only of
+/// `ADD COLUMN` clauses an `ALTER TABLE ... DROP COLUMN`. On MySQL an index
+/// is dropped from its table (`DROP INDEX i ON t`). Names are kept as
+/// written, placeholders included. The reversal of an `IF NOT EXISTS`
+/// statement is commented out under a warning and counted as unsupported.
+/// Anything else is left as a `-- TODO` for the author.
+pub fn draft_undo(sql: &str, dialect: DialectKind) -> UndoDraft {
+    let mut reverse = Vec::new();
+    let mut reversed = 0;
+    let mut unsupported = Vec::new();
+
+    for stmt in crate::sql_parser::split_statements(sql) {
+        let stmt = strip_leading_comments(stmt);
+        if stmt.is_empty() {
+            continue;
+        }
+        let preview = || -> String {
+            stmt.split_whitespace()
+                .collect::<Vec<_>>()
+                .join(" ")
+                .chars()
+                .take(80)
+                .collect()
+        };
+        match reverse_statement(stmt, dialect) {
+            Some(Reversal::Exact(undo)) => {
+                reversed += 1;
+                reverse.push(undo);
+            }
+            Some(Reversal::IfCreated(undo)) => {
+                let preview = preview();
+                reverse.push(format!(
+                    "-- WARNING: IF NOT EXISTS may have found the object already there; \
+                     uncomment only if this migration created it: {}\n-- {}",
+                    preview, undo
+                ));
+                unsupported.push(preview);
+            }
+            None => {
+                let preview = preview();
+                reverse.push(format!("-- TODO: reverse manually: {}", preview));
+                unsupported.push(preview);
+            }
+        }
+    }
+    reverse.reverse();
+
+    UndoDraft {
+        sql: reverse.join("\n"),
+        reversed,
+        unsupported,
+    }
+}
+
+/// Drop leading `--` comment lines and whitespace from a statement.
+fn strip_leading_comments(stmt: &str) -> &str {
+    let mut rest = stmt.trim_start();
+    while rest.starts_with("--") {
+        rest = match rest.find('\n') {
+            Some(i) => rest[i + 1..].trim_start(),
+            None => "",
+        };
+    }
+    rest.trim_end().trim_end_matches(';').trim_end()
+}
+
+fn reverse_statement(stmt: &str, dialect: DialectKind) -> Option<Reversal> {
+    let reversal = |if_not_exists: bool, undo: String| {
+        if if_not_exists {
+            Reversal::IfCreated(undo)
+        } else {
+            Reversal::Exact(undo)
+        }
+    };
+
+    if let Some(caps) = DRAFT_CREATE_TABLE_RE.captures(stmt) {
+        let if_not_exists = caps.get(1).is_some();
+        let if_exists = if if_not_exists { "IF EXISTS " } else { "" };
+        return Some(reversal(
+            if_not_exists,
+            format!("DROP TABLE {}{};", if_exists, &caps[2]),
+        ));
+    }
+
+    if let Some(caps) = DRAFT_CREATE_INDEX_RE.captures(stmt) {
+        let concurrently = if caps.get(1).is_some() {
+            "CONCURRENTLY "
+        } else {
+            ""
+        };
+        let if_not_exists = caps.get(2).is_some();
+        let if_exists = if if_not_exists { "IF EXISTS " } else { "" };
+        if dialect == DialectKind::Mysql {
+            return Some(reversal(
+                if_not_exists,
+                format!("DROP INDEX {}{} ON {};", if_exists, &caps[3], &caps[4]),
+            ));
+        }
+        // An index lives in its table's schema.
+        let index = match caps[4].rsplit_once('.') {
+            Some((schema, _)) if !caps[3].contains('.') => format!("{}.{}", schema, &caps[3]),
+            _ => caps[3].to_string(),
+        };
+        return Some(reversal(
+            if_not_exists,
+            format!("DROP INDEX {}{}{};", concurrently, if_exists, index),
+        ));
+    }
+
+    if let Some(caps) = DRAFT_ALTER_TABLE_RE.captures(stmt) {
+        let mut drops = Vec::new();
+        let mut if_not_exists = false;
+        for clause in split_top_level_commas(&caps[2]) {
+            let add = DRAFT_ADD_COLUMN_RE.captures(clause)?;
+            let column = &add[3];
+            if add.get(1).is_none()
+                && (CONSTRAINT_KEYWORDS
+                    .iter()
+                    .any(|k| column.eq_ignore_ascii_case(k))
+                    || (dialect == DialectKind::Mysql
+                        && MYSQL_INDEX_KEYWORDS
+                            .iter()
+                            .any(|k| column.eq_ignore_ascii_case(k))))
+            {
+                return None;
+            }
+            let if_exists = if add.get(2).is_some() {
+                if_not_exists = true;
+                "IF EXISTS "
+            } else {
+                ""
+            };
+            drops.push(format!("DROP COLUMN {}{}", if_exists, column));
+        }
+        return Some(reversal(
+            if_not_exists,
+            format!("ALTER TABLE {} {};", &caps[1], drops.join(", ")),
+        ));
+    }
+
+    None
+}
+
+/// Split on commas outside parentheses and quotes.
+fn split_top_level_commas(s: &str) -> Vec<&str> {
+    let mut parts = Vec::new();
+    let (mut depth, mut quote, mut start) = (0i32, None, 0);
+    for (i, c) in s.char_indices() {
+        match (quote, c) {
+            (Some(q), c) if c == q => quote = None,
+            (Some(_), _) => {}
+            (None, '\'' | '"' | '`') => quote = Some(c),
+            (None, '(') => depth += 1,
+            (None, ')') => depth -= 1,
+            (None, ',') if depth == 0 => {
+                parts.push(s[start..i].trim());
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    parts.push(s[start..].trim());
+    parts
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::schema::*;
 
+    #[test]
+    fn test_draft_undo_reverses_simple_ddl() {
+        let sql = "-- waypoint:env dev\n\
+            CREATE TABLE ${waypoint:schema}.users (id BIGINT PRIMARY KEY, email TEXT);\n\
+            CREATE UNIQUE INDEX CONCURRENTLY users_email_idx ON ${waypoint:schema}.users (email);\n\
+            ALTER TABLE orders ADD COLUMN note TEXT DEFAULT 'a,b', ADD total NUMERIC(10, 2);\n";
+        let draft = draft_undo(sql, DialectKind::Postgres);
+        assert!(draft.is_complete());
+        assert_eq!(draft.reversed, 3);
+        assert_eq!(
+            draft.sql,
+            "ALTER TABLE orders DROP COLUMN note, DROP COLUMN total;\n\
+             DROP INDEX CONCURRENTLY ${waypoint:schema}.users_email_idx;\n\
+             DROP TABLE ${waypoint:schema}.users;"
+        );
+
+        let sql = "CREATE TABLE `users` (`id` BIGINT PRIMARY KEY, `email` VARCHAR(255));\n\
+            CREATE UNIQUE INDEX `users_email_idx` ON `app`.`users` (`email`);\n\
+            ALTER TABLE `orders` ADD COLUMN `note` TEXT, ADD `total` DECIMAL(10, 2);\n\
+            ALTER TABLE `orders` ADD INDEX `orders_total_idx` (`total`);\n";
+        let draft = draft_undo(sql, DialectKind::Mysql);
+        assert_eq!(draft.reversed, 3);
+        assert_eq!(draft.unsupported.len(), 1);
+        assert_eq!(
+            draft.sql,
+            "-- TODO: reverse manually: ALTER TABLE `orders` ADD INDEX `orders_total_idx` (`total`)\n\
+             ALTER TABLE `orders` DROP COLUMN `note`, DROP COLUMN `total`;\n\
+             DROP INDEX `users_email_idx` ON `app`.`users`;\n\
+             DROP TABLE `users`;"
+        );
+    }
+
+    #[test]
+    fn test_draft_undo_marks_unsupported_statements() {
+        let sql = "ALTER TABLE users ADD CONSTRAINT users_email_key UNIQUE (email);\n\
+                   CREATE INDEX ON users (email);\n\
+                   CREATE TABLE audit (id INT);";
+        let draft = draft_undo(sql, DialectKind::Postgres);
+        assert_eq!(draft.reversed, 1);
+        assert_eq!(draft.unsupported.len(), 2);
+        assert!(draft.sql.starts_with(
+            "DROP TABLE audit;\n-- TODO: reverse manually: CREATE INDEX ON users (email)"
+        ));
+    }
+
+    #[test]
+    fn test_draft_undo_comments_out_if_not_exists_reversals() {
+        let sql = "CREATE TABLE IF NOT EXISTS audit (id INT);\n\
+                   CREATE INDEX IF NOT EXISTS audit_id_idx ON audit (id);\n\
+                   ALTER TABLE users ADD COLUMN note TEXT, ADD COLUMN IF NOT EXISTS email TEXT;";
+        let draft = draft_undo(sql, DialectKind::Postgres);
+        assert_eq!(draft.reversed, 0);
+        assert_eq!(draft.unsupported.len(), 3);
+        assert!(!draft.is_complete());
+        let live: Vec<&str> = draft.sql.lines().filter(|l| !l.starts_with("--")).collect();
+        assert!(live.is_empty(), "{}", draft.sql);
+        for undo in [
+            "-- ALTER TABLE users DROP COLUMN note, DROP COLUMN IF EXISTS email;",
+            "-- DROP INDEX IF EXISTS audit_id_idx;",
+            "-- DROP TABLE IF EXISTS audit;",
+        ] {
+            assert!(draft.sql.contains(undo), "{}", draft.sql);
+        }
+        assert_eq!(draft.sql.matches("-- WARNING: IF NOT EXISTS").count(), 3);
+
+        let draft = draft_undo(
+            "CREATE INDEX IF NOT EXISTS `t_idx` ON `t` (`c`);",
+            DialectKind::Mysql,
+        );
+        assert!(draft
+            .sql
+            .ends_with("\n-- DROP INDEX IF EXISTS `t_idx` ON `t`;"));
+    }
+
     fn empty_snapshot() -> SchemaSnapshot {
         SchemaSnapshot {
             tables: vec![],
@@ -6,7 +6,7 @@
 //! engine paths. The actual per-engine analysers live in
 //! [`crate::engines::postgres::safety`] and [`crate::engines::mysql::safety`].
 
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 
 use crate::db::DbClient;
 use crate::dialect::DialectKind;
@@ -31,7 +31,7 @@ pub use crate::engines::postgres::safety::{
 ///
 /// The ordering matches PostgreSQL's internal lock hierarchy so that
 /// comparisons (e.g. `lock > LockLevel::ShareLock`) work correctly.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
 pub enum LockLevel {
     /// No lock acquired (new objects, functions, enums).
     None,
@@ -70,7 +70,7 @@ impl std::fmt::Display for LockLevel {
 }
 
 /// Rough classification of table size based on estimated row count.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum TableSize {
     /// Fewer than 10,000 rows.
     Small,
@@ -94,7 +94,7 @@ impl std::fmt::Display for TableSize {
 }
 
 /// Overall safety verdict for a migration statement or script.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
 pub enum SafetyVerdict {
     /// No significant risk detected.
     Safe,
@@ -115,7 +115,7 @@ impl std::fmt::Display for SafetyVerdict {
 }
 
 /// Safety analysis for a single SQL statement within a migration.
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct StatementAnalysis {
     /// A short preview of the analyzed statement.
     pub statement_preview: String,
@@ -136,8 +136,11 @@ pub struct StatementAnalysis {
 }
 
 /// Full safety report for a migration script.
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SafetyReport {
+    /// Report format version; see [`crate::REPORT_VERSION`].
+    #[serde(default)]
+    pub report_version: u32,
     /// The migration script filename or identifier.
     pub script: String,
     /// The worst-case verdict across all statements.
@@ -11,7 +11,7 @@
 
 use std::collections::{HashMap, HashSet};
 
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 
 #[cfg(feature = "postgres")]
 use tokio_postgres::Client;
@@ -50,7 +50,7 @@ pub struct SchemaSnapshot {
 }
 
 /// Definition of a database table.
-#[derive(Debug, Clone, Serialize, PartialEq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct TableDef {
     /// Schema the table belongs to.
     pub schema: String,
@@ -61,7 +61,7 @@ pub struct TableDef {
 }
 
 /// Definition of a table column.
-#[derive(Debug, Clone, Serialize, PartialEq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct ColumnDef {
     /// Name of the column.
     pub name: String,
@@ -76,7 +76,7 @@ pub struct ColumnDef {
 }
 
 /// Definition of a database view.
-#[derive(Debug, Clone, Serialize, PartialEq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct ViewDef {
     /// Schema the view belongs to.
     pub schema: String,
@@ -89,7 +89,7 @@ pub struct ViewDef {
 }
 
 /// Definition of a database index.
-#[derive(Debug, Clone, Serialize, PartialEq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct IndexDef {
     /// Schema the index belongs to.
     pub schema: String,
@@ -104,7 +104,7 @@ pub struct IndexDef {
 }
 
 /// Definition of a database sequence.
-#[derive(Debug, Clone, Serialize, PartialEq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct SequenceDef {
     /// Schema the sequence belongs to.
     pub schema: String,
@@ -115,7 +115,7 @@ pub struct SequenceDef {
 }
 
 /// Definition of a database function or procedure.
-#[derive(Debug, Clone, Serialize, PartialEq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct FunctionDef {
     /// Schema the function belongs to.
     pub schema: String,
@@ -132,7 +132,7 @@ pub struct FunctionDef {
 }
 
 /// Definition of a PostgreSQL enum type.
-#[derive(Debug, Clone, Serialize, PartialEq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct EnumDef {
     /// Schema the enum belongs to.
     pub schema: String,
@@ -143,7 +143,7 @@ pub struct EnumDef {
 }
 
 /// Definition of a table constraint.
-#[derive(Debug, Clone, Serialize, PartialEq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct ConstraintDef {
     /// Schema the constraint belongs to.
     pub schema: String,
@@ -158,7 +158,7 @@ pub struct ConstraintDef {
 }
 
 /// Definition of a database trigger.
-#[derive(Debug, Clone, Serialize, PartialEq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct TriggerDef {
     /// Schema the trigger belongs to.
     pub schema: String,
@@ -171,7 +171,7 @@ pub struct TriggerDef {
 }
 
 /// Differences between two schema snapshots.
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum SchemaDiff {
     /// A table was added in the target schema.
     TableAdded(TableDef),
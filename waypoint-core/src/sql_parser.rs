@@ -5,10 +5,10 @@
 use std::sync::LazyLock;
 
 use regex_lite::Regex;
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 
 /// A DDL operation extracted from SQL.
-#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum DdlOperation {
     /// A CREATE TABLE statement.
     CreateTable {
@@ -268,6 +268,11 @@ static CREATE_ENUM_RE: LazyLock<Regex> =
 static TRUNCATE_RE: LazyLock<Regex> =
     LazyLock::new(|| Regex::new(r"(?i)TRUNCATE\s+(?:TABLE\s+)?(?:(\w+)\.)?(\w+)").unwrap());
 
+/// Statements that write to or alter a relation without matching a DDL pattern above.
+static RELATION_TARGET_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?i)^(?:INSERT\s+INTO|UPDATE|DELETE\s+FROM|ALTER\s+TABLE(?:\s+IF\s+EXISTS)?|LOCK(?:\s+TABLE)?|REFRESH\s+MATERIALIZED\s+VIEW(?:\s+CONCURRENTLY)?)\s+(?:ONLY\s+)?(?:(\w+)\.)?(\w+)").unwrap()
+});
+
 /// Extract DDL operations from SQL content.
 pub fn extract_ddl_operations(sql: &str) -> Vec<DdlOperation> {
     let statements = split_statements(sql);
@@ -287,6 +292,91 @@ pub fn extract_ddl_operations(sql: &str) -> Vec<DdlOperation> {
     ops
 }
 
+/// Relations (tables and views) that SQL creates, alters, drops or writes to.
+///
+/// Names are unqualified, as written, and deduplicated in first-seen order.
+/// Statements that only read, or whose target cannot be parsed (dynamic SQL,
+/// CTE-prefixed DML), contribute nothing.
+pub fn touched_tables(sql: &str) -> Vec<String> {
+    let mut tables: Vec<String> = Vec::new();
+
+    for stmt in split_statements(sql) {
+        let stmt = skip_leading_comments(stmt);
+        if stmt.is_empty() {
+            continue;
+        }
+
+        let name = match parse_statement(stmt) {
+            Some(DdlOperation::Other { .. }) | None => RELATION_TARGET_RE
+                .captures(stmt)
+                .map(|caps| caps.get(2).unwrap().as_str().to_string()),
+            Some(op) => relation_of(op),
+        };
+        if let Some(name) = name {
+            if !tables.contains(&name) {
+                tables.push(name);
+            }
+        }
+    }
+
+    tables
+}
+
+/// Whether a single statement modifies rows: `INSERT`, `UPDATE`, `DELETE`,
+/// `MERGE`, or a `WITH` query (which may carry data-modifying CTEs).
+pub fn is_dml(stmt: &str) -> bool {
+    let keyword = skip_leading_comments(stmt)
+        .split(|c: char| c.is_whitespace() || c == '(')
+        .next()
+        .unwrap_or_default()
+        .to_ascii_uppercase();
+    matches!(
+        keyword.as_str(),
+        "INSERT" | "UPDATE" | "DELETE" | "MERGE" | "WITH"
+    )
+}
+
+/// The table or view a DDL operation acts on, if any.
+fn relation_of(op: DdlOperation) -> Option<String> {
+    match op {
+        DdlOperation::CreateTable { table, .. }
+        | DdlOperation::DropTable { table }
+        | DdlOperation::AlterTableAddColumn { table, .. }
+        | DdlOperation::AlterTableDropColumn { table, .. }
+        | DdlOperation::AlterTableAlterColumn { table, .. }
+        | DdlOperation::CreateIndex { table, .. }
+        | DdlOperation::AddConstraint { table, .. }
+        | DdlOperation::DropConstraint { table, .. }
+        | DdlOperation::TruncateTable { table } => Some(table),
+        DdlOperation::CreateView { name, .. } | DdlOperation::DropView { name } => Some(name),
+        DdlOperation::DropIndex { .. }
+        | DdlOperation::CreateFunction { .. }
+        | DdlOperation::DropFunction { .. }
+        | DdlOperation::CreateEnum { .. }
+        | DdlOperation::Other { .. } => None,
+    }
+}
+
+/// Skip whitespace and `--` / `/* */` comments at the start of a statement.
+fn skip_leading_comments(stmt: &str) -> &str {
+    let mut rest = stmt.trim_start();
+    loop {
+        if let Some(after) = rest.strip_prefix("--") {
+            rest = after
+                .split_once('\n')
+                .map_or("", |(_, tail)| tail)
+                .trim_start();
+        } else if let Some(after) = rest.strip_prefix("/*") {
+            rest = after
+                .split_once("*/")
+                .map_or("", |(_, tail)| tail)
+                .trim_start();
+        } else {
+            return rest.trim_end();
+        }
+    }
+}
+
 fn parse_statement(stmt: &str) -> Option<DdlOperation> {
     // Order matters — more specific patterns first
 
@@ -685,6 +775,23 @@ mod tests {
         assert_eq!(stmts.len(), 1);
     }
 
+    #[test]
+    fn test_touched_tables_ddl_and_dml() {
+        let sql = "-- add status\n\
+                   ALTER TABLE app.orders ADD COLUMN status TEXT;\n\
+                   CREATE INDEX idx_orders_status ON orders (status);\n\
+                   /* backfill */ UPDATE ONLY orders SET status = 'new';\n\
+                   INSERT INTO audit_log (msg) VALUES ('backfilled');\n\
+                   ALTER TABLE customers RENAME TO clients;\n\
+                   CREATE VIEW open_orders AS SELECT * FROM orders;\n\
+                   SELECT count(*) FROM invoices;\n\
+                   DROP INDEX idx_old;";
+        assert_eq!(
+            touched_tables(sql),
+            vec!["orders", "audit_log", "customers", "open_orders"]
+        );
+    }
+
     #[test]
     fn test_split_no_trailing_semicolon() {
         let sql = "SELECT 1";
@@ -955,4 +1062,16 @@ mod tests {
         assert_eq!(stmts.len(), 1);
         assert!(stmts[0].contains("CREATE TABLE a"));
     }
+
+    #[test]
+    fn test_is_dml() {
+        assert!(is_dml("UPDATE users SET active = false"));
+        assert!(is_dml("-- backfill\ninsert into t SELECT 1"));
+        assert!(is_dml(
+            "WITH gone AS (DELETE FROM t RETURNING id) SELECT count(*) FROM gone"
+        ));
+        assert!(!is_dml("CREATE TABLE t (id INT)"));
+        assert!(!is_dml("SELECT 1"));
+        assert!(!is_dml("UPDATED_AT_IS_NOT_A_KEYWORD"));
+    }
 }
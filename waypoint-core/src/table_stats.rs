@@ -0,0 +1,168 @@
+//! Relation size statistics captured around a migrate run.
+//!
+//! When `collect_table_sizes` is enabled, the sizes of every table in the
+//! managed schema are read before and after the batch, and the report lists
+//! the change for each table the applied migrations touch (see
+//! [`MigrateDetail::touched_tables`]). A large delta on an
+//! `ALTER TABLE` is the tell-tale sign of an unexpected full-table rewrite.
+
+use std::collections::{BTreeSet, HashMap};
+
+use serde::{Deserialize, Serialize};
+
+#[cfg(feature = "postgres")]
+use tokio_postgres::Client;
+
+use crate::commands::migrate::MigrateDetail;
+#[cfg(feature = "mysql")]
+use crate::db::DbClient;
+use crate::error::Result;
+
+/// Table name → total size in bytes (data plus indexes and TOAST).
+pub type SizeSnapshot = HashMap<String, i64>;
+
+/// Size change of one table touched by a migrate run.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TableSizeChange {
+    /// Table name as it appears in the catalog.
+    pub table: String,
+    /// Size before the run; `None` if the table did not exist.
+    pub before_bytes: Option<i64>,
+    /// Size after the run; `None` if the table was dropped.
+    pub after_bytes: Option<i64>,
+    /// `after_bytes - before_bytes`, treating a missing side as zero.
+    pub delta_bytes: i64,
+}
+
+/// Read the total size of every table in `schema` (PostgreSQL).
+#[cfg(feature = "postgres")]
+pub async fn snapshot(client: &Client, schema: &str) -> Result<SizeSnapshot> {
+    let rows = client
+        .query(
+            "SELECT c.relname, pg_total_relation_size(c.oid) \
+             FROM pg_class c JOIN pg_namespace n ON n.oid = c.relnamespace \
+             WHERE n.nspname = $1 AND c.relkind IN ('r', 'p', 'm')",
+            &[&schema],
+        )
+        .await?;
+    Ok(rows
+        .iter()
+        .map(|r| (r.get::<_, String>(0), r.get::<_, i64>(1)))
+        .collect())
+}
+
+/// Read the size of every base table in `schema` (MySQL).
+///
+/// `data_length` and `index_length` are InnoDB estimates refreshed by the
+/// engine's statistics, so small deltas are noise; rewrites still show up.
+#[cfg(feature = "mysql")]
+pub async fn snapshot_mysql(client: &DbClient, schema: &str) -> Result<SizeSnapshot> {
+    use mysql_async::prelude::*;
+
+    let mut conn = client.as_mysql()?.get_conn().await?;
+    let rows: Vec<(String, Option<i64>)> = conn
+        .exec(
+            "SELECT table_name, CAST(data_length + index_length AS SIGNED) \
+             FROM information_schema.tables \
+             WHERE table_schema = ? AND table_type = 'BASE TABLE'",
+            (schema,),
+        )
+        .await?;
+    Ok(rows
+        .into_iter()
+        .map(|(name, size)| (name, size.unwrap_or(0)))
+        .collect())
+}
+
+/// Tables touched by any of the applied migrations.
+pub(crate) fn touched_tables(applied: &[MigrateDetail]) -> BTreeSet<String> {
+    applied
+        .iter()
+        .flat_map(|d| d.touched_tables.iter().cloned())
+        .collect()
+}
+
+/// Pair before/after sizes for the touched tables, largest change first.
+///
+/// Names are matched case-insensitively when there is no exact match, since
+/// unquoted identifiers are folded by the server but not by the SQL parser.
+pub(crate) fn size_changes(
+    before: &SizeSnapshot,
+    after: &SizeSnapshot,
+    touched: &BTreeSet<String>,
+) -> Vec<TableSizeChange> {
+    let lookup = |snapshot: &SizeSnapshot, table: &str| -> Option<(String, i64)> {
+        snapshot
+            .get_key_value(table)
+            .or_else(|| {
+                snapshot
+                    .iter()
+                    .find(|(name, _)| name.eq_ignore_ascii_case(table))
+            })
+            .map(|(name, size)| (name.clone(), *size))
+    };
+
+    let mut changes: Vec<TableSizeChange> = touched
+        .iter()
+        .filter_map(|table| {
+            let b = lookup(before, table);
+            let a = lookup(after, table);
+            let name = a.as_ref().or(b.as_ref())?.0.clone();
+            let before_bytes = b.map(|(_, size)| size);
+            let after_bytes = a.map(|(_, size)| size);
+            Some(TableSizeChange {
+                table: name,
+                before_bytes,
+                after_bytes,
+                delta_bytes: after_bytes.unwrap_or(0) - before_bytes.unwrap_or(0),
+            })
+        })
+        .collect();
+    changes.sort_by(|x, y| {
+        y.delta_bytes
+            .abs()
+            .cmp(&x.delta_bytes.abs())
+            .then_with(|| x.table.cmp(&y.table))
+    });
+    changes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn snap(entries: &[(&str, i64)]) -> SizeSnapshot {
+        entries.iter().map(|(n, s)| (n.to_string(), *s)).collect()
+    }
+
+    #[test]
+    fn test_size_changes_created_dropped_and_rewritten() {
+        let before = snap(&[("orders", 1000), ("legacy", 500), ("untouched", 7)]);
+        let after = snap(&[("orders", 9000), ("audit", 100), ("untouched", 7)]);
+        let touched: BTreeSet<String> = ["Orders", "legacy", "audit", "missing"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
+
+        let changes = size_changes(&before, &after, &touched);
+        let summary: Vec<_> = changes
+            .iter()
+            .map(|c| {
+                (
+                    c.table.as_str(),
+                    c.before_bytes,
+                    c.after_bytes,
+                    c.delta_bytes,
+                )
+            })
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("orders", Some(1000), Some(9000), 8000),
+                ("legacy", Some(500), None, -500),
+                ("audit", None, Some(100), 100),
+            ]
+        );
+    }
+}
@@ -0,0 +1,157 @@
+//! Fast, isolated test databases cloned from a migrated template.
+//!
+//! Re-running every migration for every integration test gets slow as a
+//! migration set grows. [`TestDatabaseFactory`] migrates a template database
+//! once, then hands out fresh copies with `CREATE DATABASE ... TEMPLATE`, which
+//! is a file-level copy and typically takes milliseconds.
+//!
+//! ```rust,no_run
+//! use waypoint_core::config::WaypointConfig;
+//! use waypoint_core::testing::TestDatabaseFactory;
+//!
+//! # async fn run() -> Result<(), Box<dyn std::error::Error>> {
+//! let config = WaypointConfig::load(None, &Default::default())?;
+//! // Any database the role can connect to; used only for CREATE/DROP DATABASE.
+//! let factory =
+//!     TestDatabaseFactory::new("postgres://postgres@localhost/postgres", config).await?;
+//!
+//! let db = factory.create().await?;
+//! let client = db.connect().await?;
+//! client.batch_execute("INSERT INTO users (name) VALUES ('alice')").await?;
+//! db.drop_database().await?;
+//!
+//! factory.cleanup().await?;
+//! # Ok(())
+//! # }
+//! ```
+
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use tokio_postgres::Client;
+
+use crate::commands::template;
+use crate::config::WaypointConfig;
+use crate::db::{self, quote_ident};
+use crate::error::Result;
+use crate::Waypoint;
+
+/// Distinguishes factories created by the same process.
+static FACTORY_SEQ: AtomicU64 = AtomicU64::new(0);
+
+/// Creates per-test databases cloned from a once-migrated template (PostgreSQL).
+pub struct TestDatabaseFactory {
+    /// Connection URL for the maintenance database used to create/drop databases.
+    admin_url: String,
+    /// Name of the migrated template database.
+    template: String,
+    /// Counter for unique clone names.
+    counter: AtomicU64,
+    /// Serializes clones: PostgreSQL rejects a template copy while another
+    /// session is connected to the template.
+    clone_lock: tokio::sync::Mutex<()>,
+}
+
+/// A database cloned from the factory template.
+#[derive(Debug, Clone)]
+pub struct TestDatabase {
+    /// Database name.
+    pub name: String,
+    /// Connection URL for this database.
+    pub url: String,
+    admin_url: String,
+}
+
+impl TestDatabaseFactory {
+    /// Create the template database and apply all migrations from `config` to it.
+    ///
+    /// `admin_url` must point at an existing database (e.g. `postgres`) as a role
+    /// with `CREATEDB`. The `url` in `config` is ignored; the template's URL is
+    /// derived from `admin_url`.
+    pub async fn new(admin_url: &str, config: WaypointConfig) -> Result<Self> {
+        let template = format!(
+            "waypoint_tpl_{}_{}",
+            std::process::id(),
+            FACTORY_SEQ.fetch_add(1, Ordering::SeqCst)
+        );
+        let admin = db::connect(admin_url).await?;
+        admin
+            .batch_execute(&format!(
+                "DROP DATABASE IF EXISTS {}",
+                quote_ident(&template)
+            ))
+            .await?;
+        admin
+            .batch_execute(&format!("CREATE DATABASE {}", quote_ident(&template)))
+            .await?;
+
+        let mut template_config = config;
+        template_config.database.url = Some(db::with_database(admin_url, &template)?);
+        {
+            // Scoped so the template connection closes before anything clones it.
+            let wp = Waypoint::new(template_config).await?;
+            let report = wp.migrate(None).await?;
+            log::info!(
+                "Test template migrated; template={}, migrations_applied={}",
+                template,
+                report.migrations_applied
+            );
+        }
+
+        Ok(Self {
+            admin_url: admin_url.to_string(),
+            template,
+            counter: AtomicU64::new(0),
+            clone_lock: tokio::sync::Mutex::new(()),
+        })
+    }
+
+    /// Name of the migrated template database.
+    pub fn template_name(&self) -> &str {
+        &self.template
+    }
+
+    /// Clone the template into a new, uniquely named database.
+    pub async fn create(&self) -> Result<TestDatabase> {
+        let n = self.counter.fetch_add(1, Ordering::SeqCst);
+        let name = format!("{}_{}", self.template, n);
+
+        let _guard = self.clone_lock.lock().await;
+        let admin = db::connect(&self.admin_url).await?;
+        admin
+            .batch_execute(&format!(
+                "CREATE DATABASE {} TEMPLATE {}",
+                quote_ident(&name),
+                quote_ident(&self.template)
+            ))
+            .await?;
+        log::debug!("Cloned test database; name={}", name);
+
+        Ok(TestDatabase {
+            url: db::with_database(&self.admin_url, &name)?,
+            name,
+            admin_url: self.admin_url.clone(),
+        })
+    }
+
+    /// Drop the template database.
+    pub async fn cleanup(self) -> Result<()> {
+        drop_database(&self.admin_url, &self.template).await
+    }
+}
+
+impl TestDatabase {
+    /// Open a new connection to this database.
+    pub async fn connect(&self) -> Result<Client> {
+        db::connect(&self.url).await
+    }
+
+    /// Drop this database, terminating any remaining connections to it.
+    pub async fn drop_database(self) -> Result<()> {
+        drop_database(&self.admin_url, &self.name).await
+    }
+}
+
+async fn drop_database(admin_url: &str, name: &str) -> Result<()> {
+    let admin = db::connect(admin_url).await?;
+    template::drop_database(&admin, name).await
+}
@@ -0,0 +1,17 @@
+"""add email
+
+Revision ID: bb22
+Revises: aa11
+"""
+from alembic import op
+
+revision = "bb22"
+down_revision = "aa11"
+
+
+def upgrade() -> None:
+    op.execute("ALTER TABLE users ADD COLUMN email TEXT")
+
+
+def downgrade() -> None:
+    op.drop_column("users", "email")
@@ -0,0 +1,17 @@
+"""create users
+
+Revision ID: aa11
+Revises:
+"""
+from alembic import op
+
+revision = "aa11"
+down_revision = None
+
+
+def upgrade() -> None:
+    op.execute("CREATE TABLE users (id BIGINT PRIMARY KEY)")
+
+
+def downgrade() -> None:
+    op.drop_table("users")
@@ -0,0 +1,17 @@
+"""index email
+
+Revision ID: cc33
+Revises: bb22
+"""
+from alembic import op
+
+revision = "cc33"
+down_revision = "bb22"
+
+
+def upgrade() -> None:
+    op.create_index("users_email_idx", "users", ["email"])
+
+
+def downgrade() -> None:
+    op.drop_index("users_email_idx")
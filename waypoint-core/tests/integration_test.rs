@@ -15,7 +15,9 @@ use std::sync::atomic::{AtomicU64, Ordering};
 use waypoint_core::commands::info::MigrationState;
 use waypoint_core::commands::snapshot::SnapshotConfig;
 use waypoint_core::commands::undo::UndoTarget;
-use waypoint_core::config::{DatabaseConfig, HooksConfig, MigrationSettings, WaypointConfig};
+use waypoint_core::config::{
+    CliOverrides, DatabaseConfig, HooksConfig, LockScope, MigrationSettings, WaypointConfig,
+};
 use waypoint_core::db::{self, quote_ident};
 use waypoint_core::dependency::DependencyGraph;
 use waypoint_core::history;
@@ -1361,3 +1363,1762 @@ async fn test_validate_on_migrate_detects_modification() {
     teardown_schema(&conn, &schema).await;
     let _ = std::fs::remove_dir_all(&dir);
 }
+
+#[tokio::test]
+async fn test_connect_deadline_bounds_retries() {
+    // Nothing listens on port 1, so every attempt is refused immediately.
+    let started = std::time::Instant::now();
+    let result = db::connect_with_deadline(
+        "host=127.0.0.1 port=1 user=nobody dbname=nothing",
+        &waypoint_core::config::SslMode::Disable,
+        10,
+        30,
+        0,
+        0,
+        1,
+    )
+    .await;
+
+    match result {
+        Err(waypoint_core::error::WaypointError::ConnectionFailed {
+            kind,
+            attempts,
+            reason,
+        }) => {
+            assert_eq!(kind, waypoint_core::error::ConnectionErrorKind::Refused);
+            assert_eq!(attempts, 1, "backoff exceeds the deadline, so no retry");
+            assert!(reason.contains("deadline"), "reason: {}", reason);
+        }
+        other => panic!("expected ConnectionFailed, got {:?}", other.map(|_| ())),
+    }
+    assert!(started.elapsed() < std::time::Duration::from_secs(5));
+}
+
+#[tokio::test]
+async fn test_migrate_plans_before_running_hooks() {
+    let (client, schema) = setup_schema("plan").await;
+
+    let dir = std::env::temp_dir().join(format!(
+        "waypoint_test_plan_{}",
+        COUNTER.fetch_add(1, Ordering::SeqCst)
+    ));
+    std::fs::create_dir_all(&dir).unwrap();
+
+    std::fs::write(
+        dir.join("V2__Second.sql"),
+        format!("CREATE TABLE {}.plan_second (id SERIAL);", schema),
+    )
+    .unwrap();
+
+    let config = test_config(&schema, dir.to_str().unwrap());
+    let wp = Waypoint::with_client(config, client);
+    wp.migrate(None).await.expect("first migrate failed");
+
+    // An out-of-order file plus a beforeMigrate hook: the plan must reject the
+    // run before the hook gets a chance to execute.
+    std::fs::write(
+        dir.join("V1__First.sql"),
+        format!("CREATE TABLE {}.plan_first (id SERIAL);", schema),
+    )
+    .unwrap();
+    std::fs::write(
+        dir.join("beforeMigrate.sql"),
+        format!("CREATE TABLE {}.plan_hook_log (id SERIAL);", schema),
+    )
+    .unwrap();
+
+    let config2 = test_config(&schema, dir.to_str().unwrap());
+    let client2 = db::connect(&get_test_url()).await.unwrap();
+    let wp2 = Waypoint::with_client(config2, client2);
+    let result = wp2.migrate(None).await;
+    assert!(
+        matches!(
+            result,
+            Err(waypoint_core::error::WaypointError::OutOfOrder { .. })
+        ),
+        "expected OutOfOrder"
+    );
+
+    let conn = db::connect(&get_test_url()).await.unwrap();
+    let hook_ran = conn
+        .query_one(
+            "SELECT EXISTS (SELECT FROM information_schema.tables WHERE table_schema = $1 AND table_name = 'plan_hook_log')",
+            &[&schema],
+        )
+        .await
+        .unwrap();
+    assert!(
+        !hook_ran.get::<_, bool>(0),
+        "beforeMigrate hook should not run when planning fails"
+    );
+
+    teardown_schema(&conn, &schema).await;
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[tokio::test]
+async fn test_concurrent_migrate_applies_each_version_once() {
+    let (client, schema) = setup_schema("race").await;
+
+    let dir = std::env::temp_dir().join(format!(
+        "waypoint_test_race_{}",
+        COUNTER.fetch_add(1, Ordering::SeqCst)
+    ));
+    std::fs::create_dir_all(&dir).unwrap();
+
+    // A slow first migration keeps the lock held long enough for the second
+    // deployer to block on it.
+    std::fs::write(
+        dir.join("V1__Slow.sql"),
+        format!(
+            "SELECT pg_sleep(1); CREATE TABLE {}.race_one (id SERIAL);",
+            schema
+        ),
+    )
+    .unwrap();
+    std::fs::write(
+        dir.join("V2__Next.sql"),
+        format!("CREATE TABLE {}.race_two (id SERIAL);", schema),
+    )
+    .unwrap();
+
+    let wp_a = Waypoint::with_client(test_config(&schema, dir.to_str().unwrap()), client);
+    let client_b = db::connect(&get_test_url()).await.unwrap();
+    let wp_b = Waypoint::with_client(test_config(&schema, dir.to_str().unwrap()), client_b);
+
+    let (res_a, res_b) = tokio::join!(wp_a.migrate(None), wp_b.migrate(None));
+    let applied_a = res_a.expect("deployer A failed").migrations_applied;
+    let applied_b = res_b.expect("deployer B failed").migrations_applied;
+    assert_eq!(
+        applied_a + applied_b,
+        2,
+        "each migration must be applied exactly once across both deployers"
+    );
+
+    let conn = db::connect(&get_test_url()).await.unwrap();
+    let rows = conn
+        .query(
+            &format!(
+                "SELECT version, COUNT(*) FROM {}.waypoint_schema_history \
+                 WHERE version IS NOT NULL GROUP BY version ORDER BY version",
+                quote_ident(&schema)
+            ),
+            &[],
+        )
+        .await
+        .unwrap();
+    assert_eq!(rows.len(), 2);
+    for row in &rows {
+        assert_eq!(row.get::<_, i64>(1), 1, "version recorded more than once");
+    }
+
+    teardown_schema(&conn, &schema).await;
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[tokio::test]
+async fn test_non_transactional_migration_tracks_started_row() {
+    let (client, schema) = setup_schema("notx").await;
+
+    let dir = std::env::temp_dir().join(format!(
+        "waypoint_test_notx_{}",
+        COUNTER.fetch_add(1, Ordering::SeqCst)
+    ));
+    std::fs::create_dir_all(&dir).unwrap();
+
+    std::fs::write(
+        dir.join("V1__Table.sql"),
+        format!("CREATE TABLE {}.notx_tbl (id INT);", schema),
+    )
+    .unwrap();
+    std::fs::write(
+        dir.join("V2__Index.sql"),
+        format!(
+            "CREATE INDEX CONCURRENTLY notx_idx ON {s}.notx_tbl (id);\n\
+             CREATE INDEX CONCURRENTLY notx_idx_missing ON {s}.no_such_table (id);",
+            s = schema
+        ),
+    )
+    .unwrap();
+
+    let config = test_config(&schema, dir.to_str().unwrap());
+    let wp = Waypoint::with_client(config, client);
+    let result = wp.migrate(None).await;
+    assert!(result.is_err(), "second statement should fail");
+
+    let conn = db::connect(&get_test_url()).await.unwrap();
+    let applied = history::get_applied_migrations(&conn, &schema, "waypoint_schema_history")
+        .await
+        .unwrap();
+    let v2: Vec<_> = applied
+        .iter()
+        .filter(|a| a.version.as_deref() == Some("2"))
+        .collect();
+    assert_eq!(v2.len(), 1, "exactly one history row for the failed run");
+    assert!(!v2[0].success);
+
+    // The first statement ran outside a transaction, so its index survives.
+    let idx = conn
+        .query_one(
+            "SELECT EXISTS (SELECT FROM pg_indexes WHERE schemaname = $1 AND indexname = 'notx_idx')",
+            &[&schema],
+        )
+        .await
+        .unwrap();
+    assert!(idx.get::<_, bool>(0));
+
+    teardown_schema(&conn, &schema).await;
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[tokio::test]
+async fn test_ensure_guard_rejected_on_non_transactional_migration() {
+    let (client, schema) = setup_schema("notxensure").await;
+    let dir = create_temp_migrations(&[
+        (
+            "V1__Table.sql",
+            &format!("CREATE TABLE {}.notx_ensure (id INT);", schema),
+        ),
+        (
+            "V2__Index.sql",
+            &format!(
+                "-- waypoint:ensure index_exists(\"notx_ensure_idx\")\n\
+                 CREATE INDEX CONCURRENTLY notx_ensure_idx ON {}.notx_ensure (id);",
+                schema
+            ),
+        ),
+    ]);
+
+    let config = test_config(&schema, dir.path().to_str().unwrap());
+    let err = waypoint_core::commands::migrate::execute(&client, &config, None)
+        .await
+        .expect_err("ensure cannot roll back a non-transactional migration");
+    assert!(
+        matches!(
+            err,
+            waypoint_core::error::WaypointError::InvalidDirective { ref script, .. }
+                if script == "V2__Index.sql"
+        ),
+        "{err:?}"
+    );
+
+    // Rejected before anything ran: no index and no history row for V2.
+    let applied = history::get_applied_migrations(&client, &schema, "waypoint_schema_history")
+        .await
+        .unwrap();
+    assert!(applied.iter().all(|a| a.version.as_deref() != Some("2")));
+    let idx: bool = client
+        .query_one(
+            "SELECT EXISTS (SELECT FROM pg_indexes WHERE schemaname = $1 AND indexname = 'notx_ensure_idx')",
+            &[&schema],
+        )
+        .await
+        .unwrap()
+        .get(0);
+    assert!(!idx);
+
+    teardown_schema(&client, &schema).await;
+}
+
+#[tokio::test]
+async fn test_history_insert_shares_migration_transaction() {
+    let (client, schema) = setup_schema("histtx").await;
+
+    let dir = std::env::temp_dir().join(format!(
+        "waypoint_test_histtx_{}",
+        COUNTER.fetch_add(1, Ordering::SeqCst)
+    ));
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::write(
+        dir.join("V1__Table.sql"),
+        format!("CREATE TABLE {}.histtx_tbl (id INT);", schema),
+    )
+    .unwrap();
+
+    // Make every successful history INSERT fail, as if the process died
+    // between the migration SQL and the history write.
+    history::create_history_table(&client, &schema, "waypoint_schema_history")
+        .await
+        .unwrap();
+    client
+        .batch_execute(&format!(
+            "CREATE FUNCTION {s}.reject_insert() RETURNS trigger LANGUAGE plpgsql AS \
+             $$ BEGIN IF NEW.success THEN RAISE EXCEPTION 'history write failed'; END IF; RETURN NEW; END $$; \
+             CREATE TRIGGER reject_insert BEFORE INSERT ON {s}.waypoint_schema_history \
+             FOR EACH ROW EXECUTE FUNCTION {s}.reject_insert();",
+            s = quote_ident(&schema)
+        ))
+        .await
+        .unwrap();
+
+    let config = test_config(&schema, dir.to_str().unwrap());
+    let wp = Waypoint::with_client(config, client);
+    assert!(wp.migrate(None).await.is_err());
+
+    let conn = db::connect(&get_test_url()).await.unwrap();
+    let exists = conn
+        .query_one(
+            "SELECT EXISTS (SELECT FROM information_schema.tables WHERE table_schema = $1 AND table_name = 'histtx_tbl')",
+            &[&schema],
+        )
+        .await
+        .unwrap();
+    assert!(
+        !exists.get::<_, bool>(0),
+        "migration must roll back when its history INSERT fails"
+    );
+
+    teardown_schema(&conn, &schema).await;
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[tokio::test]
+async fn test_set_search_path_targets_managed_schema() {
+    let (client, schema) = setup_schema("searchpath").await;
+
+    let dir = std::env::temp_dir().join(format!(
+        "waypoint_test_searchpath_{}",
+        COUNTER.fetch_add(1, Ordering::SeqCst)
+    ));
+    std::fs::create_dir_all(&dir).unwrap();
+    // Deliberately unqualified, as in many Flyway-era migration sets.
+    std::fs::write(
+        dir.join("V1__Unqualified.sql"),
+        "CREATE TABLE sp_unqualified (id INT);",
+    )
+    .unwrap();
+    // Objects from the previous path, here `public`, must still resolve.
+    let shared = format!("{}_shared", schema);
+    client
+        .batch_execute(&format!(
+            "CREATE TABLE public.{} (id INT)",
+            quote_ident(&shared)
+        ))
+        .await
+        .unwrap();
+    std::fs::write(
+        dir.join("V2__Uses_public.sql"),
+        format!(
+            "CREATE VIEW sp_from_public AS SELECT id FROM {};",
+            quote_ident(&shared)
+        ),
+    )
+    .unwrap();
+
+    let mut config = test_config(&schema, dir.to_str().unwrap());
+    config.migrations.set_search_path = true;
+    let wp = Waypoint::with_client(config, client);
+    wp.migrate(None).await.expect("migrate failed");
+
+    // search_path is restored on the session afterwards.
+    let path: String = wp
+        .postgres_client()
+        .unwrap()
+        .query_one("SHOW search_path", &[])
+        .await
+        .unwrap()
+        .get(0);
+    assert!(
+        !path.contains(&schema),
+        "search_path not restored: {}",
+        path
+    );
+
+    let conn = db::connect(&get_test_url()).await.unwrap();
+    let exists = conn
+        .query_one(
+            "SELECT EXISTS (SELECT FROM information_schema.tables WHERE table_schema = $1 AND table_name = 'sp_unqualified')",
+            &[&schema],
+        )
+        .await
+        .unwrap();
+    assert!(
+        exists.get::<_, bool>(0),
+        "unqualified table should land in the managed schema"
+    );
+
+    teardown_schema(&conn, &schema).await;
+    conn.batch_execute(&format!(
+        "DROP TABLE IF EXISTS public.{}",
+        quote_ident(&shared)
+    ))
+    .await
+    .unwrap();
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+/// Derive a URL for the `postgres` maintenance database from TEST_DATABASE_URL.
+fn admin_test_url() -> String {
+    let url = get_test_url();
+    match url.rfind('/') {
+        Some(i) if url.contains("://") => format!("{}/postgres", &url[..i]),
+        _ => format!("{} dbname=postgres", url),
+    }
+}
+
+#[tokio::test]
+async fn test_database_factory_clones_migrated_template() {
+    use waypoint_core::testing::TestDatabaseFactory;
+
+    let dir = std::env::temp_dir().join(format!(
+        "waypoint_test_factory_{}",
+        COUNTER.fetch_add(1, Ordering::SeqCst)
+    ));
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::write(
+        dir.join("V1__Users.sql"),
+        "CREATE TABLE factory_users (id SERIAL PRIMARY KEY, name TEXT);",
+    )
+    .unwrap();
+
+    let config = test_config("public", dir.to_str().unwrap());
+    let factory = TestDatabaseFactory::new(&admin_test_url(), config)
+        .await
+        .expect("factory setup failed");
+
+    let (a, b) = (
+        factory.create().await.expect("clone a"),
+        factory.create().await.expect("clone b"),
+    );
+    assert_ne!(a.name, b.name);
+
+    let client_a = a.connect().await.unwrap();
+    client_a
+        .batch_execute("INSERT INTO factory_users (name) VALUES ('alice')")
+        .await
+        .expect("migrated table should exist in clone");
+
+    let client_b = b.connect().await.unwrap();
+    let count: i64 = client_b
+        .query_one("SELECT COUNT(*) FROM factory_users", &[])
+        .await
+        .unwrap()
+        .get(0);
+    assert_eq!(count, 0, "clones must be independent");
+
+    drop(client_a);
+    drop(client_b);
+    a.drop_database().await.unwrap();
+    b.drop_database().await.unwrap();
+    factory.cleanup().await.unwrap();
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[tokio::test]
+async fn test_provision_template_and_clone() {
+    let dir = std::env::temp_dir().join(format!(
+        "waypoint_test_provision_{}",
+        COUNTER.fetch_add(1, Ordering::SeqCst)
+    ));
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::write(
+        dir.join("V1__Accounts.sql"),
+        "CREATE TABLE tpl_accounts (id SERIAL PRIMARY KEY);",
+    )
+    .unwrap();
+
+    let mut config = test_config("public", dir.to_str().unwrap());
+    config.database.url = Some(admin_test_url());
+    let wp = Waypoint::new(config).await.unwrap();
+
+    let template = format!("waypoint_it_tpl_{}", std::process::id());
+    let clone = format!("{}_clone", template);
+
+    let report = wp.provision_template(&template, false).await.unwrap();
+    assert_eq!(report.migrations_applied, 1);
+    assert!(!report.replaced);
+
+    let admin = wp.postgres_client().unwrap();
+    let is_template: bool = admin
+        .query_one(
+            "SELECT datistemplate FROM pg_database WHERE datname = $1",
+            &[&template],
+        )
+        .await
+        .unwrap()
+        .get(0);
+    assert!(is_template);
+
+    assert!(
+        wp.provision_template(&template, false).await.is_err(),
+        "re-provisioning without --replace must fail"
+    );
+    let report = wp.provision_template(&template, true).await.unwrap();
+    assert!(report.replaced);
+
+    wp.clone_from_template(&template, &clone).await.unwrap();
+    let clone_url = waypoint_core::db::with_database(&admin_test_url(), &clone).unwrap();
+    {
+        let client = waypoint_core::db::connect(&clone_url).await.unwrap();
+        client
+            .batch_execute("INSERT INTO tpl_accounts DEFAULT VALUES")
+            .await
+            .expect("migrated table should exist in clone");
+    }
+
+    assert!(wp
+        .clone_from_template("waypoint_it_missing_tpl", &clone)
+        .await
+        .is_err());
+
+    for sql in [
+        format!("DROP DATABASE IF EXISTS {clone} WITH (FORCE)"),
+        format!("ALTER DATABASE {template} WITH IS_TEMPLATE false"),
+        format!("DROP DATABASE IF EXISTS {template}"),
+    ] {
+        admin.batch_execute(&sql).await.unwrap();
+    }
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[tokio::test]
+async fn test_failed_provision_drops_the_template() {
+    let dir = create_temp_migrations(&[
+        ("V1__Accounts.sql", "CREATE TABLE tpl_accounts (id INT);"),
+        (
+            "V2__Broken.sql",
+            "CREATE TABLE tpl_broken (id NO_SUCH_TYPE);",
+        ),
+    ]);
+    let mut config = test_config("public", dir.path().to_str().unwrap());
+    config.database.url = Some(admin_test_url());
+    let wp = Waypoint::new(config).await.unwrap();
+
+    let template = format!("waypoint_it_tpl_broken_{}", std::process::id());
+    let err = wp.provision_template(&template, false).await.unwrap_err();
+    assert!(
+        matches!(
+            err,
+            waypoint_core::error::WaypointError::MigrationFailed { ref script, .. }
+                if script == "V2__Broken.sql"
+        ),
+        "{err:?}"
+    );
+
+    let exists: bool = wp
+        .postgres_client()
+        .unwrap()
+        .query_one(
+            "SELECT EXISTS(SELECT 1 FROM pg_database WHERE datname = $1)",
+            &[&template],
+        )
+        .await
+        .unwrap()
+        .get(0);
+    assert!(!exists, "half-migrated template must be dropped");
+}
+
+#[tokio::test]
+async fn test_freeze_query_blocks_migrate() {
+    let (client, schema) = setup_schema("freeze").await;
+    client
+        .batch_execute(&format!(
+            "CREATE TABLE {s}.change_freeze (frozen BOOLEAN NOT NULL, reason TEXT);
+             INSERT INTO {s}.change_freeze VALUES (true, 'quarter-end close');",
+            s = quote_ident(&schema)
+        ))
+        .await
+        .unwrap();
+
+    let dir = create_temp_migrations(&[(
+        "V1__Create_t.sql",
+        &format!("CREATE TABLE {}.freeze_t (id INT);", schema),
+    )]);
+    let mut config = test_config(&schema, dir.path().to_str().unwrap());
+    config.preflight.freeze_query = Some(format!(
+        "SELECT frozen, reason FROM {}.change_freeze",
+        quote_ident(&schema)
+    ));
+
+    // Disabling the health checks (`--skip-preflight`, `--dev`) keeps the freeze.
+    for enabled in [true, false] {
+        config.preflight.enabled = enabled;
+        let err = waypoint_core::commands::migrate::execute(&client, &config, None)
+            .await
+            .expect_err("migrate must be refused during a freeze");
+        match err {
+            waypoint_core::error::WaypointError::PreflightFailed { checks } => {
+                assert!(checks.contains("quarter-end close"), "{checks}");
+            }
+            other => panic!("expected PreflightFailed, got {other:?}"),
+        }
+    }
+
+    client
+        .batch_execute(&format!(
+            "UPDATE {}.change_freeze SET frozen = false",
+            quote_ident(&schema)
+        ))
+        .await
+        .unwrap();
+    let report = waypoint_core::commands::migrate::execute(&client, &config, None)
+        .await
+        .unwrap();
+    assert_eq!(report.migrations_applied, 1);
+
+    teardown_schema(&client, &schema).await;
+}
+
+#[tokio::test]
+async fn test_post_checks_recorded_after_migrate() {
+    let (client, schema) = setup_schema("postchk").await;
+    let dir = create_temp_migrations(&[(
+        "V1__Seed.sql",
+        &format!(
+            "CREATE TABLE {s}.postchk_t (id INT); INSERT INTO {s}.postchk_t VALUES (1);",
+            s = schema
+        ),
+    )]);
+    let mut config = test_config(&schema, dir.path().to_str().unwrap());
+    config.post_checks = vec![
+        waypoint_core::post_check::PostCheck {
+            name: "seeded".into(),
+            sql: format!("SELECT count(*) = 1 FROM {}.postchk_t", schema),
+            expect: Some(true),
+            ..Default::default()
+        },
+        waypoint_core::post_check::PostCheck {
+            name: "empty".into(),
+            sql: format!("SELECT id FROM {}.postchk_t", schema),
+            expect_rows: Some(0),
+            ..Default::default()
+        },
+    ];
+
+    let report = waypoint_core::commands::migrate::execute(&client, &config, None)
+        .await
+        .unwrap();
+    assert_eq!(report.migrations_applied, 1);
+    assert_eq!(report.post_checks.len(), 2);
+    assert!(report.post_checks[0].passed);
+    assert!(!report.post_checks[1].passed);
+    match report.ensure_post_checks_passed() {
+        Err(waypoint_core::error::WaypointError::PostCheckFailed {
+            count,
+            details,
+            rolled_back,
+        }) => {
+            assert_eq!(count, 1);
+            assert!(details.contains("empty"), "{details}");
+            assert!(!rolled_back);
+        }
+        other => panic!("expected PostCheckFailed, got {other:?}"),
+    }
+
+    teardown_schema(&client, &schema).await;
+}
+
+#[tokio::test]
+async fn test_failed_post_check_rolls_back_run() {
+    let (client, schema) = setup_schema("postrb").await;
+    let s = quote_ident(&schema);
+    let dir = create_temp_migrations(&[
+        (
+            "V1__Base.sql",
+            &format!("CREATE TABLE {s}.postrb_base (id INT);"),
+        ),
+        (
+            "V2__Extra.sql",
+            &format!("CREATE TABLE {s}.postrb_extra (id INT);"),
+        ),
+        ("U2__Extra.sql", &format!("DROP TABLE {s}.postrb_extra;")),
+    ]);
+    let mut config = test_config(&schema, dir.path().to_str().unwrap());
+    config.migrations.rollback_on_post_check_failure = true;
+    config.post_checks = vec![waypoint_core::post_check::PostCheck {
+        name: "always fails".into(),
+        sql: "SELECT false".into(),
+        expect: Some(true),
+        ..Default::default()
+    }];
+
+    // V1 has no undo script, so the first run is not rolled back.
+    std::fs::remove_file(dir.path().join("V2__Extra.sql")).unwrap();
+    let report = waypoint_core::commands::migrate::execute(&client, &config, None)
+        .await
+        .unwrap();
+    let rollback = report.post_check_rollback.as_ref().unwrap();
+    assert!(rollback.versions_undone.is_empty());
+    assert_eq!(
+        rollback.error.as_deref(),
+        Some("no undo script for version(s) 1")
+    );
+
+    std::fs::write(
+        dir.path().join("V2__Extra.sql"),
+        format!("CREATE TABLE {s}.postrb_extra (id INT);"),
+    )
+    .unwrap();
+    let report = waypoint_core::commands::migrate::execute(&client, &config, None)
+        .await
+        .unwrap();
+    assert_eq!(report.migrations_applied, 1);
+    let rollback = report.post_check_rollback.as_ref().unwrap();
+    assert!(rollback.succeeded(), "{rollback:?}");
+    assert_eq!(rollback.versions_undone, vec!["2".to_string()]);
+    assert!(matches!(
+        report.ensure_post_checks_passed(),
+        Err(waypoint_core::error::WaypointError::PostCheckFailed {
+            rolled_back: true,
+            ..
+        })
+    ));
+
+    let extra: i64 = client
+        .query_one(
+            "SELECT count(*) FROM information_schema.tables WHERE table_schema = $1 AND table_name = 'postrb_extra'",
+            &[&schema],
+        )
+        .await
+        .unwrap()
+        .get(0);
+    assert_eq!(extra, 0);
+
+    teardown_schema(&client, &schema).await;
+}
+
+#[tokio::test]
+async fn test_collect_table_sizes_reports_touched_tables() {
+    let (client, schema) = setup_schema("tblsize").await;
+    // Unquoted so the DDL parser picks up the table names.
+    let s = &schema;
+    let dir = create_temp_migrations(&[
+        (
+            "V1__Create.sql",
+            &format!(
+                "CREATE TABLE {s}.sized (id INT, payload TEXT);
+                 INSERT INTO {s}.sized SELECT g, repeat('x', 100) FROM generate_series(1, 5000) g;
+                 CREATE TABLE {s}.bystander (id INT);"
+            ),
+        ),
+        (
+            "V2__Rewrite.sql",
+            &format!("ALTER TABLE {s}.sized ALTER COLUMN id TYPE BIGINT;"),
+        ),
+    ]);
+    let mut config = test_config(&schema, dir.path().to_str().unwrap());
+    config.migrations.collect_table_sizes = true;
+
+    let report = waypoint_core::commands::migrate::execute(&client, &config, Some("1"))
+        .await
+        .unwrap();
+    assert_eq!(report.details[0].touched_tables, vec!["sized", "bystander"]);
+    let sized = report
+        .table_sizes
+        .iter()
+        .find(|c| c.table == "sized")
+        .expect("created table is reported");
+    assert_eq!(sized.before_bytes, None);
+    assert!(sized.delta_bytes > 0);
+
+    let report = waypoint_core::commands::migrate::execute(&client, &config, None)
+        .await
+        .unwrap();
+    assert_eq!(report.table_sizes.len(), 1, "{:?}", report.table_sizes);
+    assert_eq!(report.table_sizes[0].table, "sized");
+    assert!(report.table_sizes[0].before_bytes.unwrap() > 0);
+    assert!(report.table_sizes[0].after_bytes.unwrap() > 0);
+
+    teardown_schema(&client, &schema).await;
+}
+
+#[tokio::test]
+async fn test_capture_ddl_records_dynamic_sql() {
+    let (client, schema) = setup_schema("ddlcap").await;
+    let s = quote_ident(&schema);
+    let dir = create_temp_migrations(&[(
+        "V1__Dynamic.sql",
+        &format!(
+            "DO $$ BEGIN EXECUTE 'CREATE TABLE {s}.dyn_t (id INT)'; END $$;
+             CREATE TABLE {s}.doomed (id INT);
+             DROP TABLE {s}.doomed;"
+        ),
+    )]);
+    let mut config = test_config(&schema, dir.path().to_str().unwrap());
+    config.migrations.capture_ddl = true;
+
+    let report = waypoint_core::commands::migrate::execute(&client, &config, None)
+        .await
+        .unwrap();
+    let captured: Vec<_> = report
+        .captured_ddl
+        .iter()
+        .map(|c| {
+            (
+                c.command_tag.as_str(),
+                c.object_identity.clone().unwrap_or_default(),
+            )
+        })
+        .filter(|(_, identity)| identity.ends_with("dyn_t") || identity.ends_with("doomed"))
+        .collect();
+    assert_eq!(
+        captured,
+        vec![
+            ("CREATE TABLE", format!("{schema}.dyn_t")),
+            ("CREATE TABLE", format!("{schema}.doomed")),
+            ("DROP TABLE", format!("{schema}.doomed")),
+        ]
+    );
+    // Everything else is waypoint creating its own history table.
+    assert!(report
+        .captured_ddl
+        .iter()
+        .filter(|c| c.script.is_some())
+        .all(|c| c.script.as_deref() == Some("V1__Dynamic.sql")));
+    assert_eq!(
+        report
+            .captured_ddl
+            .iter()
+            .filter(|c| c.script.is_some())
+            .count(),
+        3
+    );
+
+    let triggers: i64 = client
+        .query_one(
+            "SELECT count(*) FROM pg_event_trigger WHERE evtname LIKE 'waypoint_ddl_%' || $1",
+            &[&schema],
+        )
+        .await
+        .unwrap()
+        .get(0);
+    assert_eq!(triggers, 0, "event triggers are removed after the run");
+
+    teardown_schema(&client, &schema).await;
+}
+
+#[tokio::test]
+async fn test_validation_warnings_returned_from_migrate() {
+    let (client, schema) = setup_schema("valwarn").await;
+    let s = quote_ident(&schema);
+    let dir = create_temp_migrations(&[
+        (
+            "V1__First.sql",
+            &format!("CREATE TABLE {s}.first_t (id INT);"),
+        ),
+        (
+            "V2__Second.sql",
+            &format!("CREATE TABLE {s}.second_t (id INT);"),
+        ),
+    ]);
+    let mut config = test_config(&schema, dir.path().to_str().unwrap());
+    config.migrations.validate_on_migrate = true;
+
+    let report = waypoint_core::commands::migrate::execute(&client, &config, None)
+        .await
+        .unwrap();
+    assert!(report.validation_warnings.is_empty());
+
+    std::fs::remove_file(dir.path().join("V2__Second.sql")).unwrap();
+    let report = waypoint_core::commands::migrate::execute(&client, &config, None)
+        .await
+        .unwrap();
+    assert_eq!(report.migrations_applied, 0);
+    assert_eq!(report.validation_warnings.len(), 1);
+    assert!(
+        report.validation_warnings[0].contains("V2__Second.sql"),
+        "{:?}",
+        report.validation_warnings
+    );
+
+    // Strict mode turns the same warning into a validation failure.
+    config.migrations.strict = true;
+    let err = waypoint_core::commands::migrate::execute(&client, &config, None)
+        .await
+        .unwrap_err();
+    assert!(
+        matches!(err, waypoint_core::error::WaypointError::ValidationFailed(ref m) if m.contains("strict mode")),
+        "{}",
+        err
+    );
+
+    teardown_schema(&client, &schema).await;
+}
+
+#[tokio::test]
+async fn test_dev_mode_repairs_edited_checksums() {
+    let (client, schema) = setup_schema("devmode").await;
+    let s = quote_ident(&schema);
+    let dir = create_temp_migrations(&[(
+        "V1__First.sql",
+        &format!("CREATE TABLE {s}.first_t (id INT);"),
+    )]);
+    let mut config = test_config(&schema, dir.path().to_str().unwrap());
+    config.migrations.validate_on_migrate = true;
+
+    waypoint_core::commands::migrate::execute(&client, &config, None)
+        .await
+        .unwrap();
+    std::fs::write(
+        dir.path().join("V1__First.sql"),
+        format!("CREATE TABLE {s}.first_t (id BIGINT);"),
+    )
+    .unwrap();
+    let err = waypoint_core::commands::migrate::execute(&client, &config, None)
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("Checksum mismatch"), "{}", err);
+
+    config.migrations.dev = true;
+    let report = waypoint_core::commands::migrate::execute(&client, &config, None)
+        .await
+        .unwrap();
+    assert_eq!(report.migrations_applied, 0);
+    assert!(
+        report.validation_warnings[0].starts_with("Dev mode: Updated checksum for version 1"),
+        "{:?}",
+        report.validation_warnings
+    );
+
+    // The history now matches the edited file, so a normal run validates.
+    config.migrations.dev = false;
+    waypoint_core::commands::migrate::execute(&client, &config, None)
+        .await
+        .unwrap();
+
+    teardown_schema(&client, &schema).await;
+}
+
+#[tokio::test]
+async fn test_repeatable_reasons_on_migrate_report() {
+    use waypoint_core::commands::migrate::RepeatableReason;
+
+    let (client, schema) = setup_schema("rptreason").await;
+    let s = quote_ident(&schema);
+    let dir = create_temp_migrations(&[
+        (
+            "V1__Items.sql",
+            &format!("CREATE TABLE {s}.items (id INT, name TEXT);"),
+        ),
+        (
+            "R__Items_view.sql",
+            &format!("CREATE OR REPLACE VIEW {s}.items_v AS SELECT id FROM {s}.items;"),
+        ),
+    ]);
+    let config = test_config(&schema, dir.path().to_str().unwrap());
+
+    let report = waypoint_core::commands::migrate::execute(&client, &config, None)
+        .await
+        .unwrap();
+    assert_eq!(report.repeatables.len(), 1);
+    assert!(matches!(
+        report.repeatables[0].reason,
+        RepeatableReason::New { .. }
+    ));
+
+    let report = waypoint_core::commands::migrate::execute(&client, &config, None)
+        .await
+        .unwrap();
+    assert_eq!(report.migrations_applied, 0);
+    assert!(matches!(
+        report.repeatables[0].reason,
+        RepeatableReason::Unchanged { .. }
+    ));
+
+    std::fs::write(
+        dir.path().join("R__Items_view.sql"),
+        format!("CREATE OR REPLACE VIEW {s}.items_v AS SELECT id, name FROM {s}.items;"),
+    )
+    .unwrap();
+    let report = waypoint_core::commands::migrate::execute(&client, &config, None)
+        .await
+        .unwrap();
+    assert_eq!(report.migrations_applied, 1);
+    match &report.repeatables[0].reason {
+        RepeatableReason::Changed {
+            previous_checksum,
+            checksum,
+        } => assert_ne!(*previous_checksum, Some(*checksum)),
+        other => panic!("expected a changed reason, got {:?}", other),
+    }
+
+    teardown_schema(&client, &schema).await;
+}
+
+#[tokio::test]
+async fn test_repeatable_requires_version_is_deferred() {
+    use waypoint_core::commands::migrate::RepeatableReason;
+
+    let (client, schema) = setup_schema("rptdefer").await;
+    let s = quote_ident(&schema);
+    let dir = create_temp_migrations(&[
+        ("V1__Init.sql", &format!("CREATE TABLE {s}.init_t (id INT);")),
+        (
+            "V2__Orders.sql",
+            &format!("CREATE TABLE {s}.orders (id INT, total INT);"),
+        ),
+        (
+            "R__Orders_view.sql",
+            &format!(
+                "-- waypoint:requires-version 2\nCREATE OR REPLACE VIEW {s}.orders_v AS SELECT id FROM {s}.orders;"
+            ),
+        ),
+    ]);
+    let config = test_config(&schema, dir.path().to_str().unwrap());
+
+    let report = waypoint_core::commands::migrate::execute(&client, &config, Some("1"))
+        .await
+        .unwrap();
+    assert_eq!(report.migrations_applied, 1);
+    assert!(matches!(
+        &report.repeatables[0].reason,
+        RepeatableReason::Deferred { requires_version, .. } if requires_version == "2"
+    ));
+
+    let report = waypoint_core::commands::migrate::execute(&client, &config, None)
+        .await
+        .unwrap();
+    assert_eq!(report.migrations_applied, 2);
+    assert!(matches!(
+        report.repeatables[0].reason,
+        RepeatableReason::New { .. }
+    ));
+
+    teardown_schema(&client, &schema).await;
+}
+
+#[tokio::test]
+async fn test_transfer_ownership_after_migrate() {
+    let (client, schema) = setup_schema("owner").await;
+    let s = quote_ident(&schema);
+    let role = format!("{}_app_owner", schema);
+    client
+        .batch_execute(&format!(
+            "DROP ROLE IF EXISTS {r}; CREATE ROLE {r};",
+            r = quote_ident(&role)
+        ))
+        .await
+        .unwrap();
+    let dir = create_temp_migrations(&[(
+        "V1__Objects.sql",
+        &format!(
+            "CREATE TYPE {s}.mood AS ENUM ('ok', 'meh');
+             CREATE TABLE {s}.items (id SERIAL PRIMARY KEY, m {s}.mood);
+             CREATE VIEW {s}.items_v AS SELECT id FROM {s}.items;
+             CREATE FUNCTION {s}.item_count() RETURNS bigint LANGUAGE sql AS 'SELECT count(*) FROM {s}.items';"
+        ),
+    )]);
+    let mut config = test_config(&schema, dir.path().to_str().unwrap());
+    config.migrations.transfer_ownership_to = Some(role.clone());
+
+    let report = waypoint_core::commands::migrate::execute(&client, &config, None)
+        .await
+        .unwrap();
+    let transfer = report.ownership_transfer.unwrap();
+    assert!(transfer.succeeded(), "{:?}", transfer.error);
+    let mut kinds: Vec<&str> = transfer
+        .objects
+        .iter()
+        .map(|o| o.split(' ').next().unwrap())
+        .collect();
+    kinds.sort_unstable();
+    assert_eq!(kinds, vec!["FUNCTION", "TABLE", "TYPE", "VIEW"]);
+
+    let owners: Vec<(String, String)> = client
+        .query(
+            "SELECT c.relname::text, pg_get_userbyid(c.relowner)::text FROM pg_class c \
+             JOIN pg_namespace n ON n.oid = c.relnamespace \
+             WHERE n.nspname = $1 AND c.relkind IN ('r', 'v', 'S') ORDER BY 1",
+            &[&schema],
+        )
+        .await
+        .unwrap()
+        .iter()
+        .map(|r| (r.get(0), r.get(1)))
+        .collect();
+    for (name, owner) in &owners {
+        if name == &config.migrations.table || name == history::META_TABLE {
+            assert_ne!(owner, &role, "history tables stay with the migrating user");
+        } else {
+            assert_eq!(owner, &role, "{} was not reassigned", name);
+        }
+    }
+
+    teardown_schema(&client, &schema).await;
+    client
+        .batch_execute(&format!("DROP ROLE {}", quote_ident(&role)))
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn test_run_as_directive_sets_owner() {
+    let (client, schema) = setup_schema("runas").await;
+    let s = quote_ident(&schema);
+    let role = format!("{}_reporting", schema);
+    client
+        .batch_execute(&format!(
+            "DROP ROLE IF EXISTS {r}; CREATE ROLE {r}; GRANT USAGE, CREATE ON SCHEMA {s} TO {r};",
+            r = quote_ident(&role)
+        ))
+        .await
+        .unwrap();
+    let dir = create_temp_migrations(&[
+        (
+            "V1__Reporting.sql",
+            &format!("-- waypoint:run-as {role}\nCREATE TABLE {s}.report_t (id INT);"),
+        ),
+        (
+            "V2__App.sql",
+            &format!("CREATE TABLE {s}.app_t (id INT);"),
+        ),
+        (
+            "V3__Reporting_index.sql",
+            &format!(
+                "-- waypoint:run-as {role}\nCREATE INDEX CONCURRENTLY report_t_id ON {schema}.report_t (id);"
+            ),
+        ),
+    ]);
+    let config = test_config(&schema, dir.path().to_str().unwrap());
+
+    let report = waypoint_core::commands::migrate::execute(&client, &config, None)
+        .await
+        .unwrap();
+    assert_eq!(report.migrations_applied, 3);
+
+    let owner = |table: &'static str| {
+        let client = &client;
+        let schema = schema.clone();
+        async move {
+            client
+                .query_one(
+                    "SELECT pg_get_userbyid(c.relowner)::text FROM pg_class c \
+                     JOIN pg_namespace n ON n.oid = c.relnamespace \
+                     WHERE n.nspname = $1 AND c.relname = $2",
+                    &[&schema, &table],
+                )
+                .await
+                .unwrap()
+                .get::<_, String>(0)
+        }
+    };
+    assert_eq!(owner("report_t").await, role);
+    assert_ne!(owner("app_t").await, role);
+    let current: String = client
+        .query_one("SELECT current_user::text", &[])
+        .await
+        .unwrap()
+        .get(0);
+    assert_eq!(
+        current,
+        owner("app_t").await,
+        "role is reset after each migration"
+    );
+
+    teardown_schema(&client, &schema).await;
+    client
+        .batch_execute(&format!("DROP ROLE {}", quote_ident(&role)))
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn test_max_affected_rows_rolls_back() {
+    let (client, schema) = setup_schema("maxrows").await;
+    let s = quote_ident(&schema);
+    let dir = create_temp_migrations(&[
+        (
+            "V1__Seed.sql",
+            &format!(
+                "CREATE TABLE {s}.accounts (id INT, active BOOLEAN DEFAULT true);\n\
+                 INSERT INTO {s}.accounts (id) SELECT generate_series(1, 10);"
+            ),
+        ),
+        (
+            "V2__Deactivate_one.sql",
+            &format!(
+                "-- waypoint:max-affected-rows 1\n\
+                 UPDATE {s}.accounts SET active = false WHERE id = 1;"
+            ),
+        ),
+        (
+            "V3__Deactivate_typo.sql",
+            &format!(
+                "-- waypoint:max-affected-rows 1\n\
+                 CREATE TABLE {s}.audit (id INT);\n\
+                 UPDATE {s}.accounts SET active = false WHERE id > 0;"
+            ),
+        ),
+    ]);
+    let config = test_config(&schema, dir.path().to_str().unwrap());
+
+    let result = waypoint_core::commands::migrate::execute(&client, &config, None).await;
+    match result {
+        Err(waypoint_core::error::WaypointError::MigrationFailed { script, reason }) => {
+            assert_eq!(script, "V3__Deactivate_typo.sql");
+            assert!(reason.contains("affected 10 rows"), "{}", reason);
+        }
+        other => panic!("expected MigrationFailed, got {:?}", other.map(|_| ())),
+    }
+
+    let inactive: i64 = client
+        .query_one(
+            &format!("SELECT count(*) FROM {s}.accounts WHERE NOT active"),
+            &[],
+        )
+        .await
+        .unwrap()
+        .get(0);
+    assert_eq!(inactive, 1, "V2 applied, V3 rolled back");
+    let audit_exists: bool = client
+        .query_one(
+            "SELECT to_regclass($1) IS NOT NULL",
+            &[&format!("{s}.audit")],
+        )
+        .await
+        .unwrap()
+        .get(0);
+    assert!(
+        !audit_exists,
+        "DDL in the failed migration is rolled back too"
+    );
+
+    teardown_schema(&client, &schema).await;
+}
+
+#[tokio::test]
+async fn test_verify_against_shadow_database() {
+    let (client, schema) = setup_schema("verify").await;
+    let dir = create_temp_migrations(&[
+        (
+            "V1__Create_users.sql",
+            "CREATE TABLE ${waypoint:schema}.users (id SERIAL PRIMARY KEY, email TEXT NOT NULL);",
+        ),
+        (
+            "V2__Index_email.sql",
+            "CREATE INDEX users_email ON ${waypoint:schema}.users (email);",
+        ),
+        (
+            "R__Active_users.sql",
+            "CREATE OR REPLACE VIEW ${waypoint:schema}.active_users AS SELECT id FROM ${waypoint:schema}.users;",
+        ),
+    ]);
+    let config = test_config(&schema, dir.path().to_str().unwrap());
+    waypoint_core::commands::migrate::execute(&client, &config, None)
+        .await
+        .unwrap();
+
+    let report = waypoint_core::commands::verify::execute(&client, &config, &get_test_url())
+        .await
+        .unwrap();
+    assert!(report.passed, "{:?}", report.differences);
+    assert_eq!(
+        report.replayed,
+        vec![
+            "V1__Create_users.sql",
+            "V2__Index_email.sql",
+            "R__Active_users.sql"
+        ]
+    );
+
+    client
+        .batch_execute(&format!(
+            "ALTER TABLE {}.users ADD COLUMN nickname TEXT",
+            quote_ident(&schema)
+        ))
+        .await
+        .unwrap();
+    let report = waypoint_core::commands::verify::execute(&client, &config, &get_test_url())
+        .await
+        .unwrap();
+    assert!(!report.passed);
+    assert_eq!(report.differences.len(), 1, "{:?}", report.differences);
+    assert!(report.differences[0].to_string().contains("nickname"));
+
+    let leftover: i64 = client
+        .query_one(
+            "SELECT count(*) FROM pg_namespace WHERE nspname LIKE 'waypoint_verify_%'",
+            &[],
+        )
+        .await
+        .unwrap()
+        .get(0);
+    assert_eq!(leftover, 0, "shadow schema is dropped");
+
+    teardown_schema(&client, &schema).await;
+}
+
+#[tokio::test]
+async fn test_verify_replays_non_transactional_migration() {
+    let (client, schema) = setup_schema("verifyconc").await;
+    let dir = create_temp_migrations(&[(
+        "V1__Create_orders.sql",
+        "CREATE TABLE ${waypoint:schema}.orders (id INT, ref TEXT);\n\
+         CREATE INDEX CONCURRENTLY orders_ref ON ${waypoint:schema}.orders (ref);",
+    )]);
+    let config = test_config(&schema, dir.path().to_str().unwrap());
+    waypoint_core::commands::migrate::execute(&client, &config, None)
+        .await
+        .unwrap();
+
+    let report = waypoint_core::commands::verify::execute(&client, &config, &get_test_url())
+        .await
+        .unwrap();
+    assert!(report.passed, "{:?}", report.differences);
+    assert_eq!(report.replayed, vec!["V1__Create_orders.sql"]);
+
+    teardown_schema(&client, &schema).await;
+}
+
+#[tokio::test]
+async fn test_verify_flags_repeatables_that_are_not_rerunnable() {
+    let (client, schema) = setup_schema("verifyrep").await;
+    let dir = create_temp_migrations(&[
+        (
+            "V1__Create_users.sql",
+            "CREATE TABLE ${waypoint:schema}.users (id INT);",
+        ),
+        (
+            "R__Idempotent.sql",
+            "CREATE OR REPLACE VIEW ${waypoint:schema}.user_ids AS SELECT id FROM ${waypoint:schema}.users;",
+        ),
+        (
+            "R__Not_rerunnable.sql",
+            "CREATE TABLE ${waypoint:schema}.lookup (id INT);",
+        ),
+        (
+            "R__Grows_on_rerun.sql",
+            // An unnamed index gets a fresh name (users_id_idx1, ...) each run.
+            "CREATE INDEX ON ${waypoint:schema}.users (id);",
+        ),
+    ]);
+    let config = test_config(&schema, dir.path().to_str().unwrap());
+    waypoint_core::commands::migrate::execute(&client, &config, None)
+        .await
+        .unwrap();
+
+    let report = waypoint_core::commands::verify::execute(&client, &config, &get_test_url())
+        .await
+        .unwrap();
+    assert!(report.differences.is_empty(), "{:?}", report.differences);
+    assert!(!report.passed);
+    let flagged: Vec<(&str, &str)> = report
+        .repeatable_issues
+        .iter()
+        .map(|i| (i.script.as_str(), i.problem.as_str()))
+        .collect();
+    assert_eq!(flagged.len(), 2, "{:?}", flagged);
+    assert_eq!(flagged[0].0, "R__Grows_on_rerun.sql");
+    assert!(
+        flagged[0].1.contains("changes the schema"),
+        "{}",
+        flagged[0].1
+    );
+    assert_eq!(flagged[1].0, "R__Not_rerunnable.sql");
+    assert!(flagged[1].1.contains("already exists"), "{}", flagged[1].1);
+
+    teardown_schema(&client, &schema).await;
+}
+
+/// Records log lines so tests can assert on them. Installed once per process.
+struct CapturingLogger(std::sync::Mutex<Vec<String>>);
+
+impl log::Log for CapturingLogger {
+    fn enabled(&self, _: &log::Metadata) -> bool {
+        true
+    }
+    fn log(&self, record: &log::Record) {
+        self.0
+            .lock()
+            .unwrap()
+            .push(format!("{}: {}", record.target(), record.args()));
+    }
+    fn flush(&self) {}
+}
+
+fn captured_logs() -> &'static CapturingLogger {
+    static LOGGER: std::sync::OnceLock<&'static CapturingLogger> = std::sync::OnceLock::new();
+    LOGGER.get_or_init(|| {
+        let logger: &'static CapturingLogger =
+            Box::leak(Box::new(CapturingLogger(std::sync::Mutex::new(Vec::new()))));
+        log::set_logger(logger).expect("no other logger installed");
+        log::set_max_level(log::LevelFilter::Info);
+        logger
+    })
+}
+
+#[tokio::test]
+async fn test_heartbeat_reports_blocked_migration() {
+    let logs = captured_logs();
+    let (client, schema) = setup_schema("heartbeat").await;
+    let s = quote_ident(&schema);
+    client
+        .batch_execute(&format!("CREATE TABLE {s}.busy (id INT)"))
+        .await
+        .unwrap();
+    let dir = create_temp_migrations(&[(
+        "V1__Heartbeat_blocked.sql",
+        &format!("ALTER TABLE {s}.busy ADD COLUMN note TEXT;"),
+    )]);
+    let mut config = test_config(&schema, dir.path().to_str().unwrap());
+    config.migrations.heartbeat_secs = 1;
+
+    let holder = db::connect(&get_test_url()).await.unwrap();
+    holder
+        .batch_execute(&format!("BEGIN; LOCK TABLE {s}.busy IN ACCESS SHARE MODE"))
+        .await
+        .unwrap();
+    let holder_pid: i32 = holder
+        .query_one("SELECT pg_backend_pid()", &[])
+        .await
+        .unwrap()
+        .get(0);
+    let release = async {
+        tokio::time::sleep(std::time::Duration::from_millis(2500)).await;
+        holder.batch_execute("COMMIT").await.unwrap();
+    };
+    let (report, ()) = tokio::join!(
+        waypoint_core::commands::migrate::execute(&client, &config, None),
+        release
+    );
+    assert_eq!(report.unwrap().migrations_applied, 1);
+
+    let lines: Vec<String> = logs
+        .0
+        .lock()
+        .unwrap()
+        .iter()
+        .filter(|l| l.contains("Migration still running; script=V1__Heartbeat_blocked.sql"))
+        .cloned()
+        .collect();
+    assert!(!lines.is_empty(), "no heartbeat logged");
+    assert!(lines[0].starts_with("waypoint::migrate: "), "{}", lines[0]);
+    assert!(
+        lines[0].contains("wait_event=Lock:relation"),
+        "{}",
+        lines[0]
+    );
+    assert!(
+        lines[0].contains(&format!("blocked_by={{{}}}", holder_pid)),
+        "{}",
+        lines[0]
+    );
+    assert!(lines[0].contains("ALTER TABLE"), "{}", lines[0]);
+
+    teardown_schema(&client, &schema).await;
+}
+
+#[tokio::test]
+async fn test_monitor_observes_migrating_session() {
+    let (client, schema) = setup_schema("monitor").await;
+    let s = quote_ident(&schema);
+    client
+        .batch_execute(&format!("CREATE TABLE {s}.busy (id INT)"))
+        .await
+        .unwrap();
+    let mut config = test_config(&schema, "/nonexistent");
+    config.migrations.table = format!("{}_history", schema);
+
+    let holder = db::connect(&get_test_url()).await.unwrap();
+    holder
+        .batch_execute(&format!("BEGIN; LOCK TABLE {s}.busy IN ACCESS SHARE MODE"))
+        .await
+        .unwrap();
+    let holder_pid: i32 = holder
+        .query_one("SELECT pg_backend_pid()", &[])
+        .await
+        .unwrap()
+        .get(0);
+
+    // A "migrating" session: holds the migration lock, then blocks on DDL.
+    let migrator = db::connect(&get_test_url()).await.unwrap();
+    db::acquire_advisory_lock(&migrator, &config.migrations.table)
+        .await
+        .unwrap();
+    let migrator_pid: i32 = migrator
+        .query_one("SELECT pg_backend_pid()", &[])
+        .await
+        .unwrap()
+        .get(0);
+    let alter = format!("ALTER TABLE {s}.busy ADD COLUMN note TEXT");
+    let blocked = migrator.batch_execute(&alter);
+
+    let observe = async {
+        let monitor = waypoint_core::monitor::Monitor::connect(&config)
+            .await
+            .unwrap();
+        assert_eq!(
+            monitor.migrating_session(&config.migrations).await.unwrap(),
+            Some(migrator_pid)
+        );
+        let mut activity = None;
+        for _ in 0..50 {
+            activity = monitor.observe(migrator_pid).await.unwrap();
+            if activity.as_ref().is_some_and(|a| !a.lock_waits.is_empty()) {
+                break;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+        }
+        holder.batch_execute("COMMIT").await.unwrap();
+        activity.expect("migrating session not found")
+    };
+    let (result, activity) = tokio::join!(blocked, observe);
+    result.unwrap();
+
+    assert_eq!(activity.wait_event.as_deref(), Some("Lock:relation"));
+    assert_eq!(activity.blocked_by, vec![holder_pid]);
+    assert_eq!(activity.lock_waits[0].mode, "AccessExclusiveLock");
+    assert!(activity.lock_waits[0]
+        .relation
+        .as_deref()
+        .is_some_and(|r| r.ends_with("busy")));
+    assert!(activity
+        .to_string()
+        .contains("waiting_for=AccessExclusiveLock on"));
+
+    db::release_advisory_lock(&migrator, &config.migrations.table)
+        .await
+        .unwrap();
+    teardown_schema(&client, &schema).await;
+}
+
+#[tokio::test]
+async fn test_newer_history_layout_is_refused() {
+    let (client, schema) = setup_schema("meta").await;
+    let migrations = create_temp_migrations(&[(
+        "V1__Create_t.sql",
+        &format!("CREATE TABLE {}.t (id INT);", schema),
+    )]);
+    let config = test_config(&schema, migrations.path().to_str().unwrap());
+    let wp = Waypoint::with_client(config, client);
+    wp.migrate(None).await.unwrap();
+
+    let conn = db::connect(&get_test_url()).await.unwrap();
+    let meta = format!("{}.{}", quote_ident(&schema), history::META_TABLE);
+    let row = conn
+        .query_one(
+            &format!(
+                "SELECT tool_version, history_version, checksum_algorithm FROM {} \
+                 WHERE history_table = 'waypoint_schema_history'",
+                meta
+            ),
+            &[],
+        )
+        .await
+        .unwrap();
+    assert_eq!(row.get::<_, String>(0), env!("CARGO_PKG_VERSION"));
+    assert_eq!(row.get::<_, i32>(1), history::HISTORY_VERSION);
+    assert_eq!(row.get::<_, String>(2), history::CHECKSUM_ALGORITHM);
+
+    // A newer binary has since upgraded the layout.
+    conn.batch_execute(&format!(
+        "UPDATE {} SET history_version = {}, tool_version = '99.0.0'",
+        meta,
+        history::HISTORY_VERSION + 1
+    ))
+    .await
+    .unwrap();
+    let err = wp.migrate(None).await.unwrap_err();
+    assert!(
+        matches!(
+            err,
+            waypoint_core::error::WaypointError::IncompatibleHistory { .. }
+        ),
+        "{}",
+        err
+    );
+    assert!(err.to_string().contains("99.0.0"));
+
+    // Commands that only read the history refuse it too.
+    let err = wp.info().await.unwrap_err();
+    assert!(
+        matches!(
+            err,
+            waypoint_core::error::WaypointError::IncompatibleHistory { .. }
+        ),
+        "{}",
+        err
+    );
+    let err = wp.validate().await.unwrap_err();
+    assert!(
+        matches!(
+            err,
+            waypoint_core::error::WaypointError::IncompatibleHistory { .. }
+        ),
+        "{}",
+        err
+    );
+
+    // The refusal leaves the newer row alone.
+    let version: i32 = conn
+        .query_one(&format!("SELECT history_version FROM {}", meta), &[])
+        .await
+        .unwrap()
+        .get(0);
+    assert_eq!(version, history::HISTORY_VERSION + 1);
+
+    // Reads never write a row of their own.
+    conn.batch_execute(&format!("DELETE FROM {}", meta))
+        .await
+        .unwrap();
+    wp.info().await.unwrap();
+    wp.validate().await.unwrap();
+    let rows: i64 = conn
+        .query_one(&format!("SELECT count(*) FROM {}", meta), &[])
+        .await
+        .unwrap()
+        .get(0);
+    assert_eq!(rows, 0);
+
+    teardown_schema(&conn, &schema).await;
+}
+
+#[tokio::test]
+async fn test_last_applied_reports_latest_success() {
+    let (client, schema) = setup_schema("lastapplied").await;
+    let migrations = create_temp_migrations(&[
+        (
+            "V1__Create_table.sql",
+            &format!("CREATE TABLE {}.items (id INT);", schema),
+        ),
+        (
+            "V2__Add_name.sql",
+            &format!("ALTER TABLE {}.items ADD COLUMN name TEXT;", schema),
+        ),
+    ]);
+    let config = test_config(&schema, migrations.path().to_str().unwrap());
+    let wp = Waypoint::with_client(config, client);
+
+    assert_eq!(wp.last_applied().await.unwrap(), None);
+    let before = chrono::Utc::now() - chrono::Duration::seconds(5);
+    wp.migrate(None).await.unwrap();
+
+    let last = wp.last_applied().await.unwrap().unwrap();
+    assert_eq!(last.version.as_deref(), Some("2"));
+    assert_eq!(last.script, "V2__Add_name.sql");
+    assert!(!last.installed_by.is_empty());
+    assert!(last.installed_on > before);
+
+    teardown_schema(wp.postgres_client().unwrap(), &schema).await;
+}
+
+#[tokio::test]
+async fn test_mixed_case_schema_keeps_existing_history() {
+    let client = db::connect(&get_test_url()).await.unwrap();
+    let id = COUNTER.fetch_add(1, Ordering::SeqCst);
+    let schema = format!("WaypointTest_Mixed_{}", id);
+    client
+        .batch_execute(&format!(
+            "DROP SCHEMA IF EXISTS {0} CASCADE; CREATE SCHEMA {0}",
+            quote_ident(&schema)
+        ))
+        .await
+        .unwrap();
+
+    let dir = create_temp_migrations(&[(
+        "V1__Create_table.sql",
+        &format!("CREATE TABLE {}.mixed (id INT);", quote_ident(&schema)),
+    )]);
+
+    // History written with the exact-case name, as earlier releases did.
+    let config = test_config(&schema, dir.path().to_str().unwrap());
+    let wp = Waypoint::with_client(config, db::connect(&get_test_url()).await.unwrap());
+    assert_eq!(wp.migrate(None).await.unwrap().migrations_applied, 1);
+
+    // Loading the same name, bare or quoted, must find that history again.
+    for configured in [schema.clone(), format!("\"{}\"", schema)] {
+        let overrides = CliOverrides {
+            url: Some(get_test_url()),
+            schema: Some(configured),
+            locations: Some(vec![dir.path().to_path_buf()]),
+            ..Default::default()
+        };
+        let config = WaypointConfig::load(None, &overrides).unwrap();
+        assert_eq!(config.migrations.schema, schema);
+
+        let wp = Waypoint::with_client(config, db::connect(&get_test_url()).await.unwrap());
+        let info = wp.info().await.unwrap();
+        assert_eq!(info.len(), 1);
+        assert_eq!(info[0].state, MigrationState::Applied);
+        assert_eq!(wp.migrate(None).await.unwrap().migrations_applied, 0);
+    }
+
+    teardown_schema(&client, &schema).await;
+}
+
+#[tokio::test]
+async fn test_schema_lock_scope_lets_schemas_migrate_in_parallel() {
+    let (client, schema) = setup_schema("lockscope").await;
+    let (other_client, other_schema) = setup_schema("lockscope_other").await;
+    let migrations = create_temp_migrations(&[(
+        "V1__Create_table.sql",
+        &format!("CREATE TABLE {}.items (id INT);", schema),
+    )]);
+    let mut config = test_config(&schema, migrations.path().to_str().unwrap());
+    config.migrations.lock_scope = Some(LockScope::Schema);
+    let mut other_config = test_config(&other_schema, "/nonexistent");
+    other_config.migrations.lock_scope = Some(LockScope::Schema);
+
+    // Another deployer is busy migrating the other schema.
+    let holder = db::connect(&get_test_url()).await.unwrap();
+    let other_lock = db::pg_lock_name(&other_config.migrations).unwrap();
+    db::acquire_advisory_lock(&holder, &other_lock)
+        .await
+        .unwrap();
+
+    let wp = Waypoint::with_client(config, client);
+    let report = tokio::time::timeout(std::time::Duration::from_secs(10), wp.migrate(None))
+        .await
+        .expect("migrate waited for the other schema's lock")
+        .unwrap();
+    assert_eq!(report.migrations_applied, 1);
+    assert!(!wp.lock_status().await.unwrap().is_held());
+
+    let other = Waypoint::with_client(other_config, other_client);
+    assert!(other.lock_status().await.unwrap().is_held());
+
+    db::release_advisory_lock(&holder, &other_lock)
+        .await
+        .unwrap();
+    teardown_schema(other.postgres_client().unwrap(), &other_schema).await;
+    teardown_schema(wp.postgres_client().unwrap(), &schema).await;
+}
+
+#[tokio::test]
+async fn test_lock_status_and_forced_release() {
+    let (client, schema) = setup_schema("lockcmd").await;
+    let mut config = test_config(&schema, "/nonexistent");
+    // Key the lock on a table name no other test uses.
+    config.migrations.table = format!("{}_history", schema);
+
+    let holder = db::connect(&get_test_url()).await.unwrap();
+    holder
+        .batch_execute("SET application_name = 'stuck_migrator'")
+        .await
+        .unwrap();
+    db::acquire_advisory_lock(&holder, &config.migrations.table)
+        .await
+        .unwrap();
+    let holder_pid: i32 = holder
+        .query_one("SELECT pg_backend_pid()", &[])
+        .await
+        .unwrap()
+        .get(0);
+
+    let wp = Waypoint::with_client(config, client);
+    let report = wp.lock_status().await.unwrap();
+    assert!(report.is_held());
+    assert_eq!(report.holders[0].pid, holder_pid as i64);
+    assert_eq!(
+        report.holders[0].application_name.as_deref(),
+        Some("stuck_migrator")
+    );
+
+    let err = wp.lock_release(false).await.unwrap_err();
+    assert!(err.to_string().contains("--force"), "{}", err);
+
+    let report = wp.lock_release(true).await.unwrap();
+    assert_eq!(report.terminated, vec![holder_pid as i64]);
+    // Termination is asynchronous; give the backend a moment to exit.
+    let mut held = true;
+    for _ in 0..20 {
+        held = wp.lock_status().await.unwrap().is_held();
+        if !held {
+            break;
+        }
+        tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+    }
+    assert!(!held, "lock is released once the holder is terminated");
+
+    teardown_schema(wp.postgres_client().unwrap(), &schema).await;
+}